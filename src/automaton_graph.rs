@@ -1,16 +1,35 @@
 use std::cell::RefCell;
 use std::fmt;
-use std::fmt::{Debug, Formatter};
+use std::fmt::{Debug, Display, Formatter};
 use std::rc::Rc;
 
+mod arena;
 mod automaton;
+mod dot;
+mod dsl;
+mod editing;
+mod equivalence;
+mod jflap;
+mod layout;
+mod mermaid;
+mod site_json;
 mod state;
+mod svg;
 mod symbol;
+mod tikz;
 mod transition;
+mod transition_table;
+
+pub use arena::{ArenaGraph, Edge, StateId, SymbolId};
+pub use layout::Layout;
+pub(crate) use site_json::JsonValue;
+pub use svg::Highlight;
+pub use tikz::escape_latex;
+pub use transition_table::TableFormat;
 
 /// Represents a symbol that is to be
 /// transitioned on
-#[derive(Debug, Copy, Clone, PartialOrd, PartialEq)]
+#[derive(Debug, Copy, Clone, PartialOrd, PartialEq, Eq, Hash)]
 pub enum Symbol {
     CHAR(char),
     EPSILON, // ϵ
@@ -72,7 +91,7 @@ pub struct Automaton {
 
 /// Represents a test suite for strings to be accepted
 /// or rejected by the automaton
-#[derive(Default, Debug)]
+#[derive(Default, Debug, Clone)]
 pub struct Tests {
     pub accepting_strings: Vec<String>,
     pub rejecting_strings: Vec<String>,
@@ -85,6 +104,16 @@ pub enum SimulationError {
     IllegalPopFromStack(String),
 }
 
+/// Reasons an edit to an automaton could not be made
+#[derive(Debug, PartialEq)]
+pub enum EditError {
+    UnknownState(String),
+    DuplicateState(String),
+    UnknownTransition(String),
+    InvalidTransition(String),
+    RemovesStartState(String),
+}
+
 impl Debug for SimulationError {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         write!(f, "PDA Simulation Err: ")?;
@@ -101,3 +130,20 @@ impl Debug for SimulationError {
         }
     }
 }
+
+impl Display for EditError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            EditError::UnknownState(id) => write!(f, "There is no state '{}'", id),
+            EditError::DuplicateState(id) => write!(f, "There is already a state '{}'", id),
+            EditError::UnknownTransition(msg) | EditError::InvalidTransition(msg) => {
+                write!(f, "{}", msg)
+            }
+            EditError::RemovesStartState(id) => write!(
+                f,
+                "'{}' is the start state, choose another start state before removing it",
+                id
+            ),
+        }
+    }
+}
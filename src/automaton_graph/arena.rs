@@ -0,0 +1,192 @@
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use crate::automaton_graph::{Automaton, State, Symbol, Transition};
+
+/// Index of a state in an [ArenaGraph]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct StateId(pub u32);
+
+/// Index of a symbol interned in an [ArenaGraph]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct SymbolId(pub u32);
+
+/// A transition stored in an [ArenaGraph]
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Edge {
+    pub symbol: SymbolId,
+    pub pop_symbol: Option<SymbolId>,
+    pub push_symbol: Option<SymbolId>,
+    pub to: StateId,
+    /// Where the transition it was made from is in the transitions of the graph
+    transition_idx: u32,
+}
+
+/// The states and transitions of an automaton in flat lists referred to by index, so a simulation
+/// can step through them without following pointers, comparing ids or allocating
+///
+/// The graph is a read-only index built from an [Automaton], which stays the model that is parsed,
+/// edited and written out. Simulations and comparisons of automata work on state and symbol ids,
+/// going back to the states and transitions of the automaton only to describe where a run is
+///
+/// The edges leaving a state are sorted by symbol, with ϵ first, so those on a symbol are found
+/// with a binary search
+pub struct ArenaGraph {
+    states: Vec<Rc<State>>,
+    symbols: Vec<Symbol>,
+    symbol_ids: HashMap<Symbol, SymbolId>,
+    /// The edges leaving state i are edges[edge_starts[i]..edge_starts[i + 1]]
+    edge_starts: Vec<u32>,
+    edges: Vec<Edge>,
+    /// The transition each edge was made from, in the order the transitions were added
+    transitions: Vec<Transition>,
+    start_state: StateId,
+}
+
+impl SymbolId {
+    pub const EPSILON: SymbolId = SymbolId(0);
+}
+
+impl ArenaGraph {
+    pub fn new(automaton: &Automaton) -> ArenaGraph {
+        let states = automaton.all_states().clone();
+        let state_ids = states
+            .iter()
+            .enumerate()
+            .map(|(idx, state)| (state.id.clone(), StateId(idx as u32)))
+            .collect::<HashMap<String, StateId>>();
+        let mut graph = ArenaGraph {
+            start_state: state_ids[&automaton.get_start_state().id],
+            states,
+            symbols: vec![Symbol::EPSILON],
+            symbol_ids: HashMap::from([(Symbol::EPSILON, SymbolId::EPSILON)]),
+            edge_starts: vec![0],
+            edges: vec![],
+            transitions: vec![],
+        };
+
+        for idx in 0..graph.states.len() {
+            let state = graph.states[idx].clone();
+            let first_edge = graph.edges.len();
+            for transition in state.get_transitions().iter() {
+                let edge = Edge {
+                    symbol: graph.intern(transition.transition_on()),
+                    pop_symbol: transition.pop_symbol().map(|symbol| graph.intern(symbol)),
+                    push_symbol: transition.push_symbol().map(|symbol| graph.intern(symbol)),
+                    to: state_ids[transition.next_state_id()],
+                    transition_idx: graph.transitions.len() as u32,
+                };
+                graph.edges.push(edge);
+                graph.transitions.push(transition.clone());
+            }
+            // A stable sort keeps transitions on the same symbol in the order they were added
+            graph.edges[first_edge..].sort_by_key(|edge| edge.symbol);
+            graph.edge_starts.push(graph.edges.len() as u32);
+        }
+
+        graph
+    }
+
+    /// Returns the id of a symbol, adding it the first time it is seen
+    fn intern(&mut self, symbol: Symbol) -> SymbolId {
+        if let Some(symbol_id) = self.symbol_ids.get(&symbol) {
+            return *symbol_id;
+        }
+
+        let symbol_id = SymbolId(self.symbols.len() as u32);
+        self.symbols.push(symbol);
+        self.symbol_ids.insert(symbol, symbol_id);
+        symbol_id
+    }
+
+    pub fn start_state(&self) -> StateId {
+        self.start_state
+    }
+
+    pub fn state_count(&self) -> usize {
+        self.states.len()
+    }
+
+    pub fn state(&self, state_id: StateId) -> &Rc<State> {
+        &self.states[state_id.0 as usize]
+    }
+
+    pub fn is_accept_state(&self, state_id: StateId) -> bool {
+        self.state(state_id).is_accept_state
+    }
+
+    /// Returns the id of a symbol, [None] when no transition uses it
+    pub fn symbol_id(&self, symbol: Symbol) -> Option<SymbolId> {
+        self.symbol_ids.get(&symbol).copied()
+    }
+
+    /// Number of symbols interned, counting ϵ
+    pub fn symbol_count(&self) -> usize {
+        self.symbols.len()
+    }
+
+    pub fn symbol(&self, symbol_id: SymbolId) -> Symbol {
+        self.symbols[symbol_id.0 as usize]
+    }
+
+    /// Every symbol interned, indexed by their ids
+    pub fn symbols(&self) -> &[Symbol] {
+        &self.symbols
+    }
+
+    /// Edges leaving a state, sorted by symbol
+    pub fn edges(&self, state_id: StateId) -> &[Edge] {
+        let idx = state_id.0 as usize;
+        &self.edges[self.edge_starts[idx] as usize..self.edge_starts[idx + 1] as usize]
+    }
+
+    /// Edges leaving a state on a symbol
+    pub fn edges_on(&self, state_id: StateId, symbol_id: SymbolId) -> &[Edge] {
+        let edges = self.edges(state_id);
+        let start = edges.partition_point(|edge| edge.symbol < symbol_id);
+        let end = start + edges[start..].partition_point(|edge| edge.symbol == symbol_id);
+        &edges[start..end]
+    }
+
+    /// The transition of the automaton an edge was made from
+    pub fn transition(&self, edge: &Edge) -> &Transition {
+        &self.transitions[edge.transition_idx as usize]
+    }
+}
+
+#[cfg(test)]
+mod arena_tests {
+    use crate::automaton_graph::arena::{ArenaGraph, StateId, SymbolId};
+    use crate::automaton_graph::Symbol;
+    use crate::parser::Parser;
+
+    #[test]
+    fn test_edges_are_grouped_by_symbol() {
+        let automaton = Parser::parse_dsl(
+            "nfa start=q0 accept=q1\nq0 -b-> q1; q0 -a-> q0; q0 -ϵ-> q1; q0 -b-> q0",
+        )
+        .unwrap();
+        let graph = ArenaGraph::new(&automaton);
+        // States are numbered in the order they are first mentioned
+        let (q0, q1) = (StateId(0), StateId(1));
+        let b = graph.symbol_id(Symbol::CHAR('b')).unwrap();
+
+        assert_eq!(graph.start_state(), q0);
+        assert_eq!(graph.edges(q0).len(), 4);
+        assert_eq!(
+            graph
+                .edges_on(q0, b)
+                .iter()
+                .map(|edge| edge.to)
+                .collect::<Vec<_>>(),
+            vec![q1, q0]
+        );
+        assert_eq!(graph.edges_on(q0, SymbolId::EPSILON)[0].to, q1);
+        assert!(graph.edges_on(q1, b).is_empty());
+        assert_eq!(graph.symbol_id(Symbol::CHAR('c')), None);
+        assert_eq!(
+            graph.transition(&graph.edges_on(q0, b)[1]).next_state_id(),
+            "q0"
+        );
+    }
+}
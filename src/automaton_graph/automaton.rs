@@ -1,7 +1,8 @@
+use std::cell::RefCell;
 use std::collections::HashMap;
 use std::rc::Rc;
 
-use crate::automaton_graph::{Automaton, AutomatonType, State, Tests};
+use crate::automaton_graph::{Automaton, AutomatonType, Position, State, Tests, Transition};
 
 impl Automaton {
     pub fn new(
@@ -48,4 +49,72 @@ impl Automaton {
                 hash_map
             })
     }
+
+    /// Copies the states and transitions into a new automaton, moving the states and changing which accept
+    ///
+    /// # Arguments
+    ///
+    /// * `positions`: Position of each state, in the order the states are stored
+    /// * `is_accept`: Whether a state of this automaton is an accept state in the copy
+    pub(in crate::automaton_graph) fn rebuild(
+        &self,
+        positions: Vec<Position>,
+        is_accept: impl Fn(&State) -> bool,
+    ) -> Automaton {
+        let new_states = self
+            .all_states
+            .iter()
+            .zip(positions)
+            .map(|(state, position)| {
+                Rc::new(State::new(
+                    state.id.clone(),
+                    Some(state.alt_id.clone()),
+                    position,
+                    is_accept(state),
+                    RefCell::new(vec![]),
+                ))
+            })
+            .collect::<Vec<Rc<State>>>();
+
+        let err_message = "rebuilt states are created for every state";
+        let new_states_by_id = State::map_by_id(&new_states);
+        for (state, new_state) in self.all_states.iter().zip(&new_states) {
+            for transition in state.get_transitions().iter() {
+                new_state.add_transition(Transition::new(
+                    new_states_by_id
+                        .get(transition.next_state_id())
+                        .expect(err_message)
+                        .clone(),
+                    transition.transition_on(),
+                    transition.pop_symbol(),
+                    transition.push_symbol(),
+                ));
+            }
+        }
+
+        Automaton::new(
+            self.automaton_type,
+            new_states_by_id
+                .get(&self.start_state.id)
+                .expect(err_message)
+                .clone(),
+            new_states
+                .iter()
+                .filter(|state| state.is_accept_state)
+                .cloned()
+                .collect::<Vec<Rc<State>>>(),
+            new_states,
+            self.tests.clone(),
+        )
+    }
+}
+
+/// States are shared between transitions, so cloning copies the whole graph rather than the pointers to it
+impl Clone for Automaton {
+    fn clone(&self) -> Self {
+        self.rebuild(
+            self.all_states.iter().map(|state| state.position).collect(),
+            |state| state.is_accept_state,
+        )
+    }
 }
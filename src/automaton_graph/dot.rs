@@ -0,0 +1,151 @@
+use std::fmt::Write;
+use std::rc::Rc;
+
+use crate::automaton_graph::AutomatonType::PDA;
+use crate::automaton_graph::{Automaton, State, Symbol, Transition};
+
+impl Automaton {
+    /// Writes the automaton as a [Graphviz](https://graphviz.org/) DOT graph
+    ///
+    /// Accept states are drawn as double circles and the start state has an arrow coming
+    /// from nowhere. Transitions between the same pair of states share one edge with their labels
+    /// joined, and PDA transitions are labelled as a,X→Y
+    ///
+    /// # Arguments
+    ///
+    /// * `pin_positions`: Places the states at their [Position](crate::automaton_graph::Position)
+    ///   instead of letting Graphviz lay them out
+    pub fn to_dot(&self, pin_positions: bool) -> String {
+        let mut dot = String::from("digraph automaton {\n");
+
+        if pin_positions {
+            dot.push_str("    layout=neato;\n");
+        } else {
+            dot.push_str("    rankdir=LR;\n");
+        }
+        dot.push_str("    node [shape=circle];\n");
+        dot.push_str("    __start [shape=none, label=\"\", width=0, height=0];\n");
+
+        for state in &self.all_states {
+            // Writing to a String cannot fail
+            let _ = write!(
+                dot,
+                "    {} [label={}",
+                Self::dot_id(&state.id),
+                Self::dot_id(&state.alt_id)
+            );
+            if state.is_accept_state {
+                dot.push_str(", shape=doublecircle");
+            }
+            if pin_positions {
+                // Positions are given in inches with y going up, while the site measures pixels going down
+                let _ = write!(
+                    dot,
+                    ", pos=\"{},{}!\"",
+                    state.position.x / 72.0,
+                    -state.position.y / 72.0
+                );
+            }
+            dot.push_str("];\n");
+        }
+
+        let _ = writeln!(
+            dot,
+            "    __start -> {};",
+            Self::dot_id(&self.start_state.id)
+        );
+
+        for state in &self.all_states {
+            for (to, labels) in self.merged_edges(state) {
+                let _ = writeln!(
+                    dot,
+                    "    {} -> {} [label={}];",
+                    Self::dot_id(&state.id),
+                    Self::dot_id(&to.id),
+                    Self::dot_id(&labels.join(", "))
+                );
+            }
+        }
+
+        dot.push('}');
+        dot
+    }
+
+    /// Groups the transitions leaving a state by the state they go to, keeping the order
+    /// each state is first transitioned to
+    pub(in crate::automaton_graph) fn merged_edges(
+        &self,
+        state: &State,
+    ) -> Vec<(Rc<State>, Vec<String>)> {
+        let mut edges: Vec<(Rc<State>, Vec<String>)> = vec![];
+
+        for transition in state.get_transitions().iter() {
+            let label = self.edge_label(transition);
+            match edges
+                .iter_mut()
+                .find(|(to, _)| to.id == *transition.next_state_id())
+            {
+                Some((_, labels)) => {
+                    if !labels.contains(&label) {
+                        labels.push(label)
+                    }
+                }
+                None => edges.push((transition.to(), vec![label])),
+            }
+        }
+
+        edges
+    }
+
+    /// Labels a transition as a for DFAs & NFAs or a,X→Y for PDAs
+    fn edge_label(&self, transition: &Transition) -> String {
+        match self.automaton_type {
+            PDA => format!(
+                "{},{}→{}",
+                transition.transition_on(),
+                transition.pop_symbol().unwrap_or(Symbol::EPSILON),
+                transition.push_symbol().unwrap_or(Symbol::EPSILON)
+            ),
+            _ => transition.label(),
+        }
+    }
+
+    /// Quotes an id or label so any characters in it can be used
+    fn dot_id(id: &str) -> String {
+        format!("\"{}\"", id.replace('\\', "\\\\").replace('"', "\\\""))
+    }
+}
+
+#[cfg(test)]
+mod dot_tests {
+    use crate::parser::Parser;
+
+    const NFA_FILE: &str = r#"{"type":"NFA","states":{"start":{"top":144,"left":72},"s0":{"isAccept":true,"displayId":"q\"0\""}},"transitions":[{"stateA":"start","label":"a","stateB":"s0"},{"stateA":"start","label":"b","stateB":"s0"},{"stateA":"s0","label":"ϵ","stateB":"s0"}]}"#;
+
+    #[test]
+    fn test_dot_merges_edges() {
+        let dot = Parser::parse(NFA_FILE).unwrap().0.to_dot(false);
+
+        assert_eq!(
+            dot,
+            "digraph automaton {
+    rankdir=LR;
+    node [shape=circle];
+    __start [shape=none, label=\"\", width=0, height=0];
+    \"start\" [label=\"start\"];
+    \"s0\" [label=\"q\\\"0\\\"\", shape=doublecircle];
+    __start -> \"start\";
+    \"start\" -> \"s0\" [label=\"a, b\"];
+    \"s0\" -> \"s0\" [label=\"ϵ\"];
+}"
+        );
+    }
+
+    #[test]
+    fn test_dot_pins_positions() {
+        let dot = Parser::parse(NFA_FILE).unwrap().0.to_dot(true);
+
+        assert!(dot.contains("layout=neato;"));
+        assert!(dot.contains("\"start\" [label=\"start\", pos=\"1,-2!\"];"));
+    }
+}
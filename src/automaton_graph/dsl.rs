@@ -0,0 +1,144 @@
+use std::fmt::Write;
+use std::rc::Rc;
+
+use crate::automaton_graph::AutomatonType::{DFA, NFA, PDA};
+use crate::automaton_graph::{Automaton, State, Symbol, Transition};
+
+impl Automaton {
+    /// Writes the automaton in the compact text format read by
+    /// [Parser::parse_dsl](crate::parser::Parser::parse_dsl)
+    ///
+    /// States are only declared on their own line when they have a display id or [Position]
+    /// or are not part of any transition. Transitions between the same pair of states share one edge
+    pub fn to_dsl(&self) -> String {
+        let mut dsl = String::from(match self.automaton_type {
+            DFA => "dfa",
+            NFA => "nfa",
+            PDA => "pda",
+        });
+
+        // Writing to a String cannot fail
+        let _ = write!(dsl, " start={}", self.start_state.id);
+        if !self.accept_states.is_empty() {
+            let _ = write!(
+                dsl,
+                " accept={}",
+                self.accept_states
+                    .iter()
+                    .map(|state| state.id.as_str())
+                    .collect::<Vec<&str>>()
+                    .join(",")
+            );
+        }
+        dsl.push('\n');
+
+        for state in &self.all_states {
+            let has_position = state.position.x != 0.0 || state.position.y != 0.0;
+            let has_transitions = !state.get_transitions().is_empty()
+                || self.all_states.iter().any(|from| {
+                    from.get_transitions()
+                        .iter()
+                        .any(|transition| *transition.next_state_id() == state.id)
+                });
+            if state.alt_id == state.id && !has_position && has_transitions {
+                continue;
+            }
+
+            dsl.push_str(&state.id);
+            if state.alt_id != state.id {
+                let _ = write!(
+                    dsl,
+                    " \"{}\"",
+                    state.alt_id.replace('\\', "\\\\").replace('"', "\\\"")
+                );
+            }
+            if has_position {
+                let _ = write!(dsl, " @ {},{}", state.position.x, state.position.y);
+            }
+            dsl.push('\n');
+        }
+
+        for state in &self.all_states {
+            let mut edges: Vec<(Rc<State>, Vec<String>)> = vec![];
+            for transition in state.get_transitions().iter() {
+                let label = self.dsl_label(transition);
+                match edges
+                    .iter_mut()
+                    .find(|(to, _)| to.id == *transition.next_state_id())
+                {
+                    Some((_, labels)) => labels.push(label),
+                    None => edges.push((transition.to(), vec![label])),
+                }
+            }
+
+            for (to, labels) in edges {
+                let separator = if let PDA = self.automaton_type {
+                    " | "
+                } else {
+                    ","
+                };
+                let _ = writeln!(dsl, "{} -{}-> {}", state.id, labels.join(separator), to.id);
+            }
+        }
+
+        for (keyword, strings) in [
+            ("accepts", &self.tests.accepting_strings),
+            ("rejects", &self.tests.rejecting_strings),
+        ] {
+            if !strings.is_empty() {
+                let _ = writeln!(
+                    dsl,
+                    "{} {}",
+                    keyword,
+                    strings
+                        .iter()
+                        .map(|string| format!(
+                            "\"{}\"",
+                            string.replace('\\', "\\\\").replace('"', "\\\"")
+                        ))
+                        .collect::<Vec<String>>()
+                        .join(", ")
+                );
+            }
+        }
+
+        dsl
+    }
+
+    /// Labels a transition as a for DFAs & NFAs or a,X/Y for PDAs
+    fn dsl_label(&self, transition: &Transition) -> String {
+        match self.automaton_type {
+            PDA => format!(
+                "{},{}/{}",
+                transition.transition_on(),
+                transition.pop_symbol().unwrap_or(Symbol::EPSILON),
+                transition.push_symbol().unwrap_or(Symbol::EPSILON)
+            ),
+            _ => transition.label(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod dsl_tests {
+    use crate::parser::Parser;
+
+    #[test]
+    fn test_dsl_round_trips() {
+        let dsl = "pda start=q0 accept=q1
+q0 \"q\\\"0\\\"\" @ 10,20.5
+q2
+q0 -a,ϵ/A | b,A/ϵ-> q0
+q0 -ϵ,ϵ/ϵ-> q1
+accepts \"ab\", \"\"
+rejects \"b\"
+";
+        let automaton = Parser::parse_dsl(dsl).unwrap();
+
+        assert_eq!(automaton.to_dsl(), dsl);
+        assert_eq!(
+            Parser::parse(&automaton.to_site_json()).unwrap().0.to_dsl(),
+            dsl
+        );
+    }
+}
@@ -0,0 +1,196 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::automaton_graph::AutomatonType::{DFA, NFA, PDA};
+use crate::automaton_graph::{Automaton, EditError, Position, State, Symbol, Transition};
+
+/// Horizontal space left between a new state and the rightmost state
+const NEW_STATE_SPACING: f64 = 150.0;
+
+impl Automaton {
+    /// Adds a state with no transitions to the right of the other states
+    pub fn add_state(&mut self, id: &str) -> Result<(), EditError> {
+        if self.all_states.iter().any(|state| state.id == id) {
+            return Err(EditError::DuplicateState(String::from(id)));
+        }
+
+        let position = Position {
+            x: self
+                .all_states
+                .iter()
+                .map(|state| state.position.x)
+                .fold(f64::MIN, f64::max)
+                + NEW_STATE_SPACING,
+            y: self.start_state.position.y,
+        };
+        self.all_states.push(Rc::new(State::new(
+            String::from(id),
+            None,
+            position,
+            false,
+            RefCell::new(vec![]),
+        )));
+
+        Ok(())
+    }
+
+    /// Removes a state along with every transition going to or from it
+    pub fn remove_state(&mut self, id: &str) -> Result<(), EditError> {
+        self.find_state(id)?;
+        if self.start_state.id == id {
+            return Err(EditError::RemovesStartState(String::from(id)));
+        }
+
+        for state in &self.all_states {
+            state
+                .transition_edges
+                .borrow_mut()
+                .retain(|transition| transition.next_state_id() != id);
+        }
+        self.all_states.retain(|state| state.id != id);
+        self.accept_states.retain(|state| state.id != id);
+
+        Ok(())
+    }
+
+    /// Adds a transition between two states
+    ///
+    /// DFAs cannot be given ϵ transitions or a second transition on the same symbol, and the
+    /// stack symbols must be given for PDAs and left out for DFAs and NFAs
+    ///
+    /// # Arguments
+    ///
+    /// * `from`: Id of the state the transition leaves
+    /// * `to`: Id of the state the transition goes to
+    /// * `symbol`: Symbol read by the transition
+    /// * `pop`: Symbol popped from the stack by PDAs
+    /// * `push`: Symbol pushed on the stack by PDAs
+    pub fn add_edge(
+        &self,
+        from: &str,
+        to: &str,
+        symbol: Symbol,
+        pop: Option<Symbol>,
+        push: Option<Symbol>,
+    ) -> Result<(), EditError> {
+        let from_state = self.find_state(from)?;
+        let to_state = self.find_state(to)?;
+
+        match self.automaton_type {
+            PDA if pop.is_none() || push.is_none() => {
+                return Err(EditError::InvalidTransition(String::from(
+                    "PDA transitions need the symbols they pop and push",
+                )))
+            }
+            DFA | NFA if pop.is_some() || push.is_some() => {
+                return Err(EditError::InvalidTransition(String::from(
+                    "Only PDA transitions can pop and push symbols",
+                )))
+            }
+            DFA if symbol == Symbol::EPSILON => {
+                return Err(EditError::InvalidTransition(String::from(
+                    "DFAs cannot have ϵ transitions",
+                )))
+            }
+            DFA if from_state
+                .get_transitions()
+                .iter()
+                .any(|transition| transition.transition_on() == symbol) =>
+            {
+                return Err(EditError::InvalidTransition(format!(
+                    "'{}' already has a transition on {} and DFAs can only have one",
+                    from, symbol
+                )))
+            }
+            _ => {}
+        }
+
+        from_state.add_transition(Transition::new(to_state, symbol, pop, push));
+        Ok(())
+    }
+
+    /// Removes every transition between two states on a symbol
+    pub fn remove_edge(&self, from: &str, to: &str, symbol: Symbol) -> Result<(), EditError> {
+        let from_state = self.find_state(from)?;
+        self.find_state(to)?;
+
+        let mut transitions = from_state.transition_edges.borrow_mut();
+        let transition_count = transitions.len();
+        transitions.retain(|transition| {
+            transition.next_state_id() != to || transition.transition_on() != symbol
+        });
+
+        if transitions.len() == transition_count {
+            return Err(EditError::UnknownTransition(format!(
+                "There is no transition from '{}' to '{}' on {}",
+                from, to, symbol
+            )));
+        }
+        Ok(())
+    }
+
+    pub fn set_start_state(&mut self, id: &str) -> Result<(), EditError> {
+        self.start_state = self.find_state(id)?;
+        Ok(())
+    }
+
+    /// Makes a state an accept state or stops it from being one
+    pub fn set_accept_state(&mut self, id: &str, is_accept: bool) -> Result<(), EditError> {
+        self.find_state(id)?;
+
+        // Whether a state accepts cannot be changed once it is shared, so the states are copied
+        *self = self.rebuild(
+            self.all_states.iter().map(|state| state.position).collect(),
+            |state| {
+                if state.id == id {
+                    is_accept
+                } else {
+                    state.is_accept_state
+                }
+            },
+        );
+        Ok(())
+    }
+
+    fn find_state(&self, id: &str) -> Result<Rc<State>, EditError> {
+        self.all_states
+            .iter()
+            .find(|state| state.id == id)
+            .cloned()
+            .ok_or_else(|| EditError::UnknownState(String::from(id)))
+    }
+}
+
+#[cfg(test)]
+mod editing_tests {
+    use crate::automaton_graph::{EditError, Symbol};
+    use crate::parser::Parser;
+
+    #[test]
+    fn test_edit_dfa() {
+        let mut dfa = Parser::parse_dsl("dfa start=q0 accept=q0\nq0 -a-> q0").unwrap();
+
+        dfa.add_state("q1").unwrap();
+        dfa.add_edge("q0", "q1", Symbol::CHAR('b'), None, None)
+            .unwrap();
+        assert!(matches!(
+            dfa.add_edge("q0", "q1", Symbol::CHAR('a'), None, None),
+            Err(EditError::InvalidTransition(_))
+        ));
+        dfa.set_accept_state("q1", true).unwrap();
+        dfa.set_accept_state("q0", false).unwrap();
+        dfa.remove_edge("q0", "q0", Symbol::CHAR('a')).unwrap();
+        assert_eq!(
+            dfa.to_dsl(),
+            "dfa start=q0 accept=q1\nq1 @ 150,0\nq0 -b-> q1\n"
+        );
+
+        assert_eq!(
+            dfa.remove_state("q0"),
+            Err(EditError::RemovesStartState(String::from("q0")))
+        );
+        dfa.set_start_state("q1").unwrap();
+        dfa.remove_state("q0").unwrap();
+        assert_eq!(dfa.all_states().len(), 1);
+    }
+}
@@ -0,0 +1,120 @@
+use std::cmp::Ordering;
+use std::collections::{HashSet, VecDeque};
+use std::mem;
+
+use crate::automaton_graph::{ArenaGraph, Automaton, StateId, Symbol, SymbolId};
+
+/// States an automaton can be in after reading a string, kept sorted so equal sets compare equal
+type StateSet = Vec<StateId>;
+
+impl Automaton {
+    /// Finds the shortest string accepted by one DFA or NFA and rejected by the other,
+    /// returning [None] when both accept the same language
+    ///
+    /// Both automata are run on every string at once, following every state each of them can be in,
+    /// so DFAs and NFAs can be compared with each other. Only DFAs and NFAs can be compared,
+    /// as whether two PDAs accept the same language cannot be decided
+    ///
+    /// # Arguments
+    ///
+    /// * `other`: Automaton to compare with
+    pub fn distinguishing_string(&self, other: &Automaton) -> Option<String> {
+        let graphs = (ArenaGraph::new(self), ArenaGraph::new(other));
+        let mut alphabet: Vec<Symbol> = vec![];
+        for symbol in graphs.0.symbols().iter().chain(graphs.1.symbols()) {
+            if *symbol != Symbol::EPSILON && !alphabet.contains(symbol) {
+                alphabet.push(*symbol);
+            }
+        }
+        alphabet.sort_by(|a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+
+        let start = (
+            Self::state_set(&graphs.0, vec![graphs.0.start_state()]),
+            Self::state_set(&graphs.1, vec![graphs.1.start_state()]),
+        );
+        let mut visited: HashSet<(StateSet, StateSet)> = HashSet::new();
+        visited.insert(start.clone());
+        // Searching breadth first finds the shortest string
+        let mut to_visit: VecDeque<(StateSet, StateSet, String)> = VecDeque::new();
+        to_visit.push_back((start.0, start.1, String::new()));
+
+        let is_accepting = |graph: &ArenaGraph, states: &StateSet| {
+            states.iter().any(|state| graph.is_accept_state(*state))
+        };
+
+        while let Some((states, other_states, string)) = to_visit.pop_front() {
+            if is_accepting(&graphs.0, &states) != is_accepting(&graphs.1, &other_states) {
+                return Some(string);
+            }
+
+            for symbol in &alphabet {
+                let next = (
+                    Self::states_after(&graphs.0, &states, *symbol),
+                    Self::states_after(&graphs.1, &other_states, *symbol),
+                );
+                if visited.insert(next.clone()) {
+                    to_visit.push_back((next.0, next.1, format!("{}{}", string, symbol)));
+                }
+            }
+        }
+
+        None
+    }
+
+    /// States reached from any of the states by a transition on the symbol, then on ϵ transitions
+    fn states_after(graph: &ArenaGraph, states: &StateSet, symbol: Symbol) -> StateSet {
+        let targets = match graph.symbol_id(symbol) {
+            Some(symbol_id) => states
+                .iter()
+                .flat_map(|state| graph.edges_on(*state, symbol_id))
+                .map(|edge| edge.to)
+                .collect(),
+            None => vec![],
+        };
+        Self::state_set(graph, targets)
+    }
+
+    /// Adds the states reached on ϵ transitions and sorts the states so equal sets compare equal
+    fn state_set(graph: &ArenaGraph, states: Vec<StateId>) -> StateSet {
+        let mut is_in_set = vec![false; graph.state_count()];
+        let mut closure = vec![];
+        let mut to_visit = states;
+
+        while let Some(state) = to_visit.pop() {
+            if mem::replace(&mut is_in_set[state.0 as usize], true) {
+                continue;
+            }
+            to_visit.extend(
+                graph
+                    .edges_on(state, SymbolId::EPSILON)
+                    .iter()
+                    .map(|edge| edge.to),
+            );
+            closure.push(state);
+        }
+
+        closure.sort();
+        closure
+    }
+}
+
+#[cfg(test)]
+mod equivalence_tests {
+    use crate::parser::Parser;
+
+    #[test]
+    fn test_distinguishing_string() {
+        // Both accept strings with an odd number of a's
+        let dfa = Parser::parse_dsl("dfa start=q0 accept=q1\nq0 -a-> q1; q1 -a-> q0").unwrap();
+        let nfa = Parser::parse_dsl(
+            "nfa start=p0 accept=p1\np0 -a-> p1; p1 -ϵ-> p2; p2 -a-> p3; p3 -a-> p1",
+        )
+        .unwrap();
+        assert_eq!(dfa.distinguishing_string(&nfa), None);
+
+        let longer =
+            Parser::parse_dsl("dfa start=q0 accept=q1\nq0 -a-> q1; q1 -a-> q0; q0 -b-> q0")
+                .unwrap();
+        assert_eq!(dfa.distinguishing_string(&longer), Some(String::from("ba")));
+    }
+}
@@ -0,0 +1,176 @@
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::rc::Rc;
+
+use crate::automaton_graph::AutomatonType::{DFA, NFA, PDA};
+use crate::automaton_graph::{Automaton, State, Symbol};
+
+impl Automaton {
+    /// Writes the automaton as a [JFLAP](https://www.jflap.org/) .jff file
+    ///
+    /// JFLAP needs numeric state ids, so states are numbered in the order they are stored
+    /// and their display ids become the state names. ϵ is written as an empty element.
+    /// JFLAP starts a PDA with Z on its stack, so a start state that only pushes Z
+    /// is left out and the state it moves to is marked initial instead
+    pub fn to_jff(&self) -> String {
+        let mut jff = String::from(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"no\"?><!--Created with automaton_core.--><structure>\n",
+        );
+
+        let jflap_type = match self.automaton_type {
+            DFA | NFA => "fa",
+            PDA => "pda",
+        };
+        let (states, start_state) = self.jff_states();
+        let jflap_ids = states
+            .iter()
+            .enumerate()
+            .map(|(idx, state)| (state.id.as_str(), idx))
+            .collect::<HashMap<&str, usize>>();
+
+        // Writing to a String cannot fail
+        let _ = writeln!(jff, "\t<type>{}</type>", jflap_type);
+        jff.push_str("\t<automaton>\n\t\t<!--The list of states.-->\n");
+
+        for (idx, state) in states.iter().enumerate() {
+            let _ = writeln!(
+                jff,
+                "\t\t<state id=\"{}\" name=\"{}\">",
+                idx,
+                Self::escape_xml(&state.alt_id)
+            );
+            let _ = writeln!(jff, "\t\t\t<x>{:?}</x>", state.position.x);
+            let _ = writeln!(jff, "\t\t\t<y>{:?}</y>", state.position.y);
+            if state.id == start_state.id {
+                jff.push_str("\t\t\t<initial/>\n");
+            }
+            if state.is_accept_state {
+                jff.push_str("\t\t\t<final/>\n");
+            }
+            jff.push_str("\t\t</state>\n");
+        }
+
+        jff.push_str("\t\t<!--The list of transitions.-->\n");
+
+        for (idx, state) in states.iter().enumerate() {
+            for transition in state.get_transitions().iter() {
+                jff.push_str("\t\t<transition>\n");
+                let _ = writeln!(jff, "\t\t\t<from>{}</from>", idx);
+                let _ = writeln!(
+                    jff,
+                    "\t\t\t<to>{}</to>",
+                    jflap_ids[transition.next_state_id().as_str()]
+                );
+                jff.push_str(&Self::jff_symbol("read", Some(transition.transition_on())));
+                if let PDA = self.automaton_type {
+                    jff.push_str(&Self::jff_symbol("pop", transition.pop_symbol()));
+                    jff.push_str(&Self::jff_symbol("push", transition.push_symbol()));
+                }
+                jff.push_str("\t\t</transition>\n");
+            }
+        }
+
+        jff.push_str("\t</automaton>\n</structure>");
+        jff
+    }
+
+    /// Finds the states to write along with the one marked initial, leaving out a start state
+    /// of a PDA that only pushes Z as JFLAP already starts with Z on the stack
+    fn jff_states(&self) -> (Vec<&Rc<State>>, Rc<State>) {
+        let transitions = self.start_state.get_transitions();
+        let pushes_initial_stack_symbol = matches!(self.automaton_type, PDA)
+            && !self.start_state.is_accept_state
+            && transitions.len() == 1
+            && transitions[0].transition_on() == Symbol::EPSILON
+            && transitions[0].pop_symbol() == Some(Symbol::EPSILON)
+            && transitions[0].push_symbol() == Some(Symbol::CHAR('Z'))
+            && *transitions[0].next_state_id() != self.start_state.id
+            && self.all_states.iter().all(|state| {
+                state
+                    .get_transitions()
+                    .iter()
+                    .all(|transition| *transition.next_state_id() != self.start_state.id)
+            });
+
+        if pushes_initial_stack_symbol {
+            (
+                self.all_states
+                    .iter()
+                    .filter(|state| state.id != self.start_state.id)
+                    .collect(),
+                transitions[0].to(),
+            )
+        } else {
+            (self.all_states.iter().collect(), self.start_state.clone())
+        }
+    }
+
+    /// Writes the element holding a symbol of a transition
+    ///
+    /// Eg: <read>a</read> or <read/> for ϵ
+    fn jff_symbol(element: &str, symbol: Option<Symbol>) -> String {
+        match symbol {
+            Some(Symbol::CHAR(c)) => format!(
+                "\t\t\t<{}>{}</{}>\n",
+                element,
+                Self::escape_xml(&c.to_string()),
+                element
+            ),
+            Some(Symbol::EPSILON) | None => format!("\t\t\t<{}/>\n", element),
+        }
+    }
+
+    /// Escapes the characters xml does not allow in text and attribute values
+    pub(crate) fn escape_xml(text: &str) -> String {
+        text.chars().fold(String::new(), |mut escaped, c| {
+            match c {
+                '&' => escaped.push_str("&amp;"),
+                '<' => escaped.push_str("&lt;"),
+                '>' => escaped.push_str("&gt;"),
+                '"' => escaped.push_str("&quot;"),
+                '\'' => escaped.push_str("&apos;"),
+                c => escaped.push(c),
+            }
+            escaped
+        })
+    }
+}
+
+#[cfg(test)]
+mod jflap_tests {
+    use crate::parser::Parser;
+
+    const SITE_PDA: &str = r#"{"type":"PDA","states":{"start":{"displayId":"q<0>"},"s0":{"isAccept":true,"top":10.5,"left":20}},"transitions":[{"stateA":"start","label":"a,ϵ,A","stateB":"start"},{"stateA":"start","label":"b,A,ϵ","stateB":"s0"}]}"#;
+
+    #[test]
+    fn test_jff_round_trips() {
+        let jff = Parser::parse(SITE_PDA).unwrap().0.to_jff();
+
+        assert!(jff.contains("<state id=\"0\" name=\"q&lt;0&gt;\">\n\t\t\t<x>0.0</x>\n\t\t\t<y>0.0</y>\n\t\t\t<initial/>\n\t\t</state>"));
+        assert!(jff.contains("<read>b</read>\n\t\t\t<pop>A</pop>\n\t\t\t<push/>"));
+        assert_eq!(Parser::parse_jff(&jff).unwrap().to_jff(), jff);
+    }
+    #[test]
+    fn test_jff_initial_stack_symbol_is_not_pushed_again() {
+        let automaton = Parser::parse_jff(
+            r#"<structure><type>pda</type><automaton>
+            <state id="0"><x>0</x><y>0</y><initial/></state>
+            <state id="1"><x>100</x><y>0</y><final/></state>
+            <transition><from>0</from><to>0</to><read>a</read><pop/><push>A</push></transition>
+            <transition><from>0</from><to>1</to><read/><pop>Z</pop><push/></transition>
+            </automaton></structure>"#,
+        )
+        .unwrap();
+        assert_eq!(automaton.get_start_state().id, "init");
+
+        let jff = automaton.to_jff();
+        assert!(!jff.contains("name=\"init\""));
+        assert!(jff.contains(
+            "<state id=\"0\" name=\"0\">\n\t\t\t<x>0.0</x>\n\t\t\t<y>0.0</y>\n\t\t\t<initial/>"
+        ));
+
+        let reparsed = Parser::parse_jff(&jff).unwrap();
+        assert_eq!(reparsed.all_states().len(), 3);
+        assert_eq!(reparsed.to_jff(), jff);
+    }
+}
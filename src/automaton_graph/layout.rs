@@ -0,0 +1,384 @@
+use std::collections::VecDeque;
+
+use crate::automaton_graph::{Automaton, Position};
+
+/// Space left around the states at the edges of the layout
+const MARGIN: f64 = 60.0;
+/// Horizontal space between the layers of a layered layout
+const LAYER_SPACING: f64 = 150.0;
+/// Vertical space between states in the same layer
+const STATE_SPACING: f64 = 100.0;
+/// Number of times the order of the layers is swept to remove crossings
+const ORDERING_SWEEPS: usize = 8;
+/// Number of steps taken by the force-directed simulation
+const FORCE_ITERATIONS: usize = 300;
+
+/// Ways the states of an automaton can be positioned
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Layout {
+    /// Places states in columns by how many transitions they are from the start state,
+    /// ordering each column to reduce edge crossings
+    Layered,
+    /// Spreads states out by treating transitions as springs and states as repelling each other
+    ForceDirected,
+}
+
+impl Layout {
+    /// Finds a layout by the name given on the command line
+    pub fn from_name(name: &str) -> Option<Layout> {
+        match name {
+            "layered" => Some(Layout::Layered),
+            "force" => Some(Layout::ForceDirected),
+            _ => None,
+        }
+    }
+}
+
+impl Automaton {
+    /// Creates a copy of the automaton with every state placed by a layout
+    ///
+    /// Operations that create new states, which are all placed at [Position::default],
+    /// use this so the states can be told apart when drawn or exported
+    ///
+    /// # Arguments
+    ///
+    /// * `layout`: How to position the states
+    pub fn with_layout(&self, layout: Layout) -> Automaton {
+        self.rebuild(self.layout_positions(layout), |state| state.is_accept_state)
+    }
+
+    /// Finds a position for each state, in the order the states are stored
+    ///
+    /// # Arguments
+    ///
+    /// * `layout`: How to position the states
+    pub fn layout_positions(&self, layout: Layout) -> Vec<Position> {
+        let layered = self.layered_positions();
+        match layout {
+            Layout::Layered => layered,
+            Layout::ForceDirected => self.force_directed_positions(layered),
+        }
+    }
+
+    /// Pairs of indexes of states joined by a transition, leaving out self loops
+    fn layout_edges(&self) -> Vec<(usize, usize)> {
+        let index_of = |state_id: &str| {
+            self.all_states
+                .iter()
+                .position(|state| state.id == state_id)
+                .unwrap_or_default()
+        };
+
+        let mut edges = vec![];
+        for (from, state) in self.all_states.iter().enumerate() {
+            for transition in state.get_transitions().iter() {
+                let to = index_of(transition.next_state_id());
+                if from != to && !edges.contains(&(from, to)) {
+                    edges.push((from, to));
+                }
+            }
+        }
+        edges
+    }
+
+    /// Places states in layers going right from the start state using a Sugiyama-style layout
+    ///
+    /// Each state's layer is how many transitions it is from the start state, and the
+    /// states of each layer are ordered by the average position of their neighbours in the
+    /// layer next to them, keeping the order with the fewest edge crossings
+    fn layered_positions(&self) -> Vec<Position> {
+        let state_count = self.all_states.len();
+        let edges = self.layout_edges();
+        let start_idx = self
+            .all_states
+            .iter()
+            .position(|state| state.id == self.start_state.id)
+            .unwrap_or_default();
+
+        // Breadth first search from the start state, then from any states it cannot reach
+        let mut layer_of: Vec<Option<usize>> = vec![None; state_count];
+        for root in std::iter::once(start_idx).chain(0..state_count) {
+            if layer_of[root].is_some() {
+                continue;
+            }
+            layer_of[root] = Some(0);
+            let mut queue = VecDeque::from([root]);
+
+            while let Some(state_idx) = queue.pop_front() {
+                let next_layer = layer_of[state_idx].map(|layer| layer + 1);
+                for &(_, to) in edges.iter().filter(|(from, _)| *from == state_idx) {
+                    if layer_of[to].is_none() {
+                        layer_of[to] = next_layer;
+                        queue.push_back(to);
+                    }
+                }
+            }
+        }
+        let layer_of = layer_of
+            .into_iter()
+            .map(Option::unwrap_or_default)
+            .collect::<Vec<usize>>();
+
+        let layer_count = layer_of.iter().max().map_or(0, |layer| layer + 1);
+        let mut layers: Vec<Vec<usize>> = vec![vec![]; layer_count];
+        for (state_idx, layer) in layer_of.iter().enumerate() {
+            layers[*layer].push(state_idx);
+        }
+
+        // Edges between states in neighbouring layers, going from the left layer to the right
+        let adjacent_edges = edges
+            .iter()
+            .filter_map(
+                |&(from, to)| match layer_of[to] as isize - layer_of[from] as isize {
+                    1 => Some((from, to)),
+                    -1 => Some((to, from)),
+                    _ => None,
+                },
+            )
+            .collect::<Vec<(usize, usize)>>();
+
+        let mut best_layers = layers.clone();
+        let mut fewest_crossings = Self::count_crossings(&layers, &adjacent_edges, state_count);
+
+        for sweep in 0..ORDERING_SWEEPS {
+            // Alternate between ordering by the layer on the left and the layer on the right
+            let going_right = sweep % 2 == 0;
+            let layer_order: Vec<usize> = if going_right {
+                (1..layer_count).collect()
+            } else {
+                (0..layer_count.saturating_sub(1)).rev().collect()
+            };
+
+            for layer in layer_order {
+                let neighbour_layer = if going_right { layer - 1 } else { layer + 1 };
+                let order_of = |state_idx: usize| {
+                    layers[neighbour_layer]
+                        .iter()
+                        .position(|idx| *idx == state_idx)
+                };
+
+                let mut barycenters = layers[layer]
+                    .iter()
+                    .enumerate()
+                    .map(|(current_order, &state_idx)| {
+                        let neighbour_orders = adjacent_edges
+                            .iter()
+                            .filter_map(|&(left, right)| match going_right {
+                                true if right == state_idx => order_of(left),
+                                false if left == state_idx => order_of(right),
+                                _ => None,
+                            })
+                            .collect::<Vec<usize>>();
+
+                        // States with no neighbours keep their place
+                        let barycenter = if neighbour_orders.is_empty() {
+                            current_order as f64
+                        } else {
+                            neighbour_orders.iter().sum::<usize>() as f64
+                                / neighbour_orders.len() as f64
+                        };
+                        (barycenter, state_idx)
+                    })
+                    .collect::<Vec<(f64, usize)>>();
+
+                barycenters.sort_by(|a, b| a.0.total_cmp(&b.0));
+                layers[layer] = barycenters.into_iter().map(|(_, idx)| idx).collect();
+            }
+
+            let crossings = Self::count_crossings(&layers, &adjacent_edges, state_count);
+            if crossings < fewest_crossings {
+                fewest_crossings = crossings;
+                best_layers = layers.clone();
+            }
+        }
+
+        // Centre each layer vertically against the tallest one
+        let tallest_layer = best_layers.iter().map(Vec::len).max().unwrap_or_default();
+        let mut positions = vec![Position::default(); state_count];
+        for (layer, states) in best_layers.iter().enumerate() {
+            let offset = (tallest_layer - states.len()) as f64 * STATE_SPACING / 2.0;
+            for (order, &state_idx) in states.iter().enumerate() {
+                positions[state_idx] = Position {
+                    x: MARGIN + layer as f64 * LAYER_SPACING,
+                    y: MARGIN + offset + order as f64 * STATE_SPACING,
+                };
+            }
+        }
+
+        positions
+    }
+
+    /// Counts the pairs of edges between neighbouring layers that cross each other
+    fn count_crossings(
+        layers: &[Vec<usize>],
+        adjacent_edges: &[(usize, usize)],
+        state_count: usize,
+    ) -> usize {
+        let mut layer_of = vec![0; state_count];
+        let mut order_of = vec![0; state_count];
+        for (layer, states) in layers.iter().enumerate() {
+            for (order, &state_idx) in states.iter().enumerate() {
+                layer_of[state_idx] = layer;
+                order_of[state_idx] = order;
+            }
+        }
+
+        let mut crossings = 0;
+        for (idx, &(left_a, right_a)) in adjacent_edges.iter().enumerate() {
+            for &(left_b, right_b) in &adjacent_edges[idx + 1..] {
+                // Edges that share a state meet rather than cross
+                if layer_of[left_a] == layer_of[left_b]
+                    && order_of[left_a] != order_of[left_b]
+                    && order_of[right_a] != order_of[right_b]
+                    && (order_of[left_a] < order_of[left_b])
+                        != (order_of[right_a] < order_of[right_b])
+                {
+                    crossings += 1;
+                }
+            }
+        }
+
+        crossings
+    }
+
+    /// Spreads states out using the Fruchterman-Reingold algorithm, starting from the layered layout
+    ///
+    /// The start state is kept on the left edge of the layout so the automaton reads left to right
+    fn force_directed_positions(&self, mut positions: Vec<Position>) -> Vec<Position> {
+        let state_count = positions.len();
+        if state_count < 2 {
+            return positions;
+        }
+
+        let edges = self.layout_edges();
+        let start_idx = self
+            .all_states
+            .iter()
+            .position(|state| state.id == self.start_state.id)
+            .unwrap_or_default();
+        // Ideal distance between states
+        let spacing = LAYER_SPACING;
+        let mut temperature = LAYER_SPACING;
+
+        for _ in 0..FORCE_ITERATIONS {
+            let mut displacements = vec![(0.0, 0.0); state_count];
+
+            // Every pair of states pushes each other away
+            for a in 0..state_count {
+                for b in a + 1..state_count {
+                    let (dx, dy, distance) =
+                        Self::layout_distance(positions[a], positions[b], a, b);
+                    let force = spacing * spacing / distance;
+                    displacements[a].0 += dx / distance * force;
+                    displacements[a].1 += dy / distance * force;
+                    displacements[b].0 -= dx / distance * force;
+                    displacements[b].1 -= dy / distance * force;
+                }
+            }
+
+            // Transitions pull the states they join together
+            for &(from, to) in &edges {
+                let (dx, dy, distance) =
+                    Self::layout_distance(positions[from], positions[to], from, to);
+                let force = distance * distance / spacing;
+                displacements[from].0 -= dx / distance * force;
+                displacements[from].1 -= dy / distance * force;
+                displacements[to].0 += dx / distance * force;
+                displacements[to].1 += dy / distance * force;
+            }
+
+            for (state_idx, (dx, dy)) in displacements.into_iter().enumerate() {
+                let length = (dx * dx + dy * dy).sqrt().max(f64::EPSILON);
+                let step = length.min(temperature);
+                positions[state_idx].x += dx / length * step;
+                positions[state_idx].y += dy / length * step;
+            }
+
+            // Keep the start state to the left of every other state
+            let leftmost = positions
+                .iter()
+                .enumerate()
+                .filter(|(idx, _)| *idx != start_idx)
+                .map(|(_, position)| position.x)
+                .fold(f64::INFINITY, f64::min);
+            positions[start_idx].x = positions[start_idx].x.min(leftmost - spacing / 2.0);
+
+            temperature = (temperature * 0.98).max(1.0);
+        }
+
+        // Move the layout so it starts at the margin
+        let min_x = positions.iter().map(|p| p.x).fold(f64::INFINITY, f64::min);
+        let min_y = positions.iter().map(|p| p.y).fold(f64::INFINITY, f64::min);
+        positions
+            .into_iter()
+            .map(|position| Position {
+                x: (position.x - min_x + MARGIN).round(),
+                y: (position.y - min_y + MARGIN).round(),
+            })
+            .collect()
+    }
+
+    /// Direction and distance between two positions, nudging apart states that are on top of each other
+    fn layout_distance(a: Position, b: Position, a_idx: usize, b_idx: usize) -> (f64, f64, f64) {
+        let (mut dx, mut dy) = (a.x - b.x, a.y - b.y);
+        if dx == 0.0 && dy == 0.0 {
+            // Separate them in a direction that depends on which states they are so the layout is repeatable
+            dx = (a_idx as f64 - b_idx as f64).signum();
+            dy = 0.5;
+        }
+        let distance = (dx * dx + dy * dy).sqrt().max(0.01);
+        (dx, dy, distance)
+    }
+}
+
+#[cfg(test)]
+mod layout_tests {
+    use crate::automaton_graph::Layout;
+    use crate::parser::Parser;
+
+    #[test]
+    fn test_layered_layout_avoids_crossings() {
+        // Listing b before a makes the first order cross the edges a->c and b->d
+        let automaton =
+            Parser::parse_dsl("dfa start=s\nd; c\ns -0-> a; s -1-> b\nb -0-> d; a -0-> c").unwrap();
+        let positions = automaton.layout_positions(Layout::Layered);
+        let position_of = |state_id: &str| {
+            positions[automaton
+                .all_states()
+                .iter()
+                .position(|state| state.id == state_id)
+                .unwrap()]
+        };
+
+        assert_eq!(position_of("s").x, 60.0);
+        assert_eq!(position_of("s").y, 110.0);
+        assert_eq!(position_of("a").x, 210.0);
+        assert_eq!(position_of("c").x, 360.0);
+        assert_eq!(
+            position_of("a").y < position_of("b").y,
+            position_of("c").y < position_of("d").y
+        );
+    }
+
+    #[test]
+    fn test_force_directed_layout_keeps_start_on_the_left() {
+        let automaton =
+            Parser::parse_dsl("nfa start=q0\nq1 -a-> q0; q2 -a-> q0; q1 -b-> q2; q3 -a-> q1")
+                .unwrap()
+                .with_layout(Layout::ForceDirected);
+        let start_x = automaton.get_start_state().position.x;
+
+        assert_eq!(start_x, 60.0);
+        for state in automaton.all_states() {
+            assert!(state.id == "q0" || state.position.x > start_x);
+        }
+        for (a_idx, a) in automaton.all_states().iter().enumerate() {
+            for b in &automaton.all_states()[a_idx + 1..] {
+                assert!(
+                    (a.position.x - b.position.x).abs() + (a.position.y - b.position.y).abs()
+                        > 30.0
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,104 @@
+use std::fmt::Write;
+
+use crate::automaton_graph::Automaton;
+
+impl Automaton {
+    /// Writes the automaton as a [Mermaid](https://mermaid.js.org/) `stateDiagram-v2`,
+    /// ready to be placed in a ```mermaid block of a Markdown page
+    ///
+    /// States are named s0, s1, ... in the order they are stored and show their display ids.
+    /// The start state has an arrow from `[*]`, accept states are drawn with a thick border and
+    /// transitions between the same pair of states share one edge with their labels joined
+    pub fn to_mermaid(&self) -> String {
+        let mut mermaid = String::from("stateDiagram-v2\n    direction LR\n");
+        let state_name = |state_id: &str| {
+            format!(
+                "s{}",
+                self.all_states
+                    .iter()
+                    .position(|state| state.id == state_id)
+                    .unwrap_or_default()
+            )
+        };
+
+        for state in &self.all_states {
+            // Writing to a String cannot fail
+            let _ = writeln!(
+                mermaid,
+                "    state \"{}\" as {}",
+                Self::escape_mermaid(&state.alt_id),
+                state_name(&state.id)
+            );
+        }
+
+        let _ = writeln!(mermaid, "    [*] --> {}", state_name(&self.start_state.id));
+
+        for state in &self.all_states {
+            for (to, labels) in self.merged_edges(state) {
+                let _ = writeln!(
+                    mermaid,
+                    "    {} --> {} : {}",
+                    state_name(&state.id),
+                    state_name(&to.id),
+                    Self::escape_mermaid(&labels.join(", "))
+                );
+            }
+        }
+
+        if !self.accept_states.is_empty() {
+            mermaid.push_str("    classDef accept stroke-width:4px\n");
+            let _ = writeln!(
+                mermaid,
+                "    class {} accept",
+                self.accept_states
+                    .iter()
+                    .map(|state| state_name(&state.id))
+                    .collect::<Vec<String>>()
+                    .join(",")
+            );
+        }
+
+        mermaid
+    }
+
+    /// Replaces the characters Mermaid gives a meaning to with entity codes
+    fn escape_mermaid(text: &str) -> String {
+        text.chars().fold(String::new(), |mut escaped, c| {
+            match c {
+                '#' => escaped.push_str("#35;"),
+                ';' => escaped.push_str("#59;"),
+                '"' => escaped.push_str("#quot;"),
+                c => escaped.push(c),
+            }
+            escaped
+        })
+    }
+}
+
+#[cfg(test)]
+mod mermaid_tests {
+    use crate::parser::Parser;
+
+    #[test]
+    fn test_mermaid_state_diagram() {
+        let mermaid = Parser::parse_dsl(
+            "pda start=q0 accept=q1\nq1 \"q;1\"\nq0 -a,ϵ/A | b,A/ϵ-> q0; q0 -ϵ,ϵ/ϵ-> q1",
+        )
+        .unwrap()
+        .to_mermaid();
+
+        assert_eq!(
+            mermaid,
+            "stateDiagram-v2
+    direction LR
+    state \"q#59;1\" as s0
+    state \"q0\" as s1
+    [*] --> s1
+    s1 --> s1 : a,ϵ→A, b,A→ϵ
+    s1 --> s0 : ϵ,ϵ→ϵ
+    classDef accept stroke-width:4px
+    class s0 accept
+"
+        );
+    }
+}
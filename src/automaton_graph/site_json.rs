@@ -0,0 +1,426 @@
+use std::fmt;
+use std::fmt::{Display, Formatter, Write};
+
+use crate::automaton_graph::AutomatonType::{DFA, NFA, PDA};
+use crate::automaton_graph::{Automaton, Symbol, Transition};
+
+/// The json values needed to describe an automaton, objects keep their keys in the order given
+#[derive(Debug, Clone)]
+pub(crate) enum JsonValue {
+    Object(Vec<(String, JsonValue)>),
+    Array(Vec<JsonValue>),
+    String(String),
+    Number(f64),
+    Bool(bool),
+}
+
+impl Automaton {
+    /// Writes the automaton in the json format used by the [automaton simulator site](https://automatonsimulator.com/)
+    ///
+    /// The output is indented the same way as files saved from the site and is read back by
+    /// [Parser::parse](crate::parser::Parser::parse) into the same automaton
+    pub fn to_site_json(&self) -> String {
+        self.site_json_value().to_string()
+    }
+
+    /// Builds the json written by [Automaton::to_site_json], so it can be placed inside other json
+    pub(crate) fn site_json_value(&self) -> JsonValue {
+        let type_name = match self.automaton_type {
+            DFA => "dfa",
+            NFA => "nfa",
+            PDA => "pda",
+        };
+
+        JsonValue::Object(vec![
+            (
+                String::from("type"),
+                JsonValue::String(type_name.to_uppercase()),
+            ),
+            (String::from(type_name), self.site_automaton_section()),
+            (String::from("states"), self.site_states()),
+            (String::from("transitions"), self.site_transitions()),
+            (
+                String::from("bulkTests"),
+                JsonValue::Object(vec![
+                    (
+                        String::from("accept"),
+                        JsonValue::String(self.tests.accepting_strings.join("\n")),
+                    ),
+                    (
+                        String::from("reject"),
+                        JsonValue::String(self.tests.rejecting_strings.join("\n")),
+                    ),
+                ]),
+            ),
+        ])
+    }
+
+    /// Creates the "dfa" / "nfa" / "pda" object which the site simulates the automaton from
+    ///
+    /// Eg: "dfa": { "transitions": { "start": { "a": "s0" } }, "startState": "start", "acceptStates": ["s0"] }
+    fn site_automaton_section(&self) -> JsonValue {
+        let transitions = self
+            .all_states
+            .iter()
+            .filter(|state| !state.get_transitions().is_empty())
+            .map(|state| {
+                let transitions = state.get_transitions();
+                let state_transitions = match self.automaton_type {
+                    // Each symbol leads to a single state
+                    DFA => transitions
+                        .iter()
+                        .map(|transition| {
+                            (
+                                Self::site_symbol(Some(transition.transition_on())),
+                                JsonValue::String(transition.next_state_id().clone()),
+                            )
+                        })
+                        .collect(),
+                    // Each symbol leads to a list of states
+                    NFA => Self::group_by(transitions.iter(), |transition| {
+                        Self::site_symbol(Some(transition.transition_on()))
+                    })
+                    .into_iter()
+                    .map(|(symbol, transitions)| {
+                        (
+                            symbol,
+                            JsonValue::Array(
+                                transitions
+                                    .iter()
+                                    .map(|transition| {
+                                        JsonValue::String(transition.next_state_id().clone())
+                                    })
+                                    .collect(),
+                            ),
+                        )
+                    })
+                    .collect(),
+                    // Each symbol and then each popped symbol leads to a list of states and pushes
+                    PDA => Self::group_by(transitions.iter(), |transition| {
+                        Self::site_symbol(Some(transition.transition_on()))
+                    })
+                    .into_iter()
+                    .map(|(symbol, transitions)| {
+                        (
+                            symbol,
+                            JsonValue::Object(
+                                Self::group_by(transitions.into_iter(), |transition| {
+                                    Self::site_symbol(transition.pop_symbol())
+                                })
+                                .into_iter()
+                                .map(|(pop, transitions)| {
+                                    (
+                                        pop,
+                                        JsonValue::Array(
+                                            transitions
+                                                .iter()
+                                                .map(|transition| Self::site_pda_move(transition))
+                                                .collect(),
+                                        ),
+                                    )
+                                })
+                                .collect(),
+                            ),
+                        )
+                    })
+                    .collect(),
+                };
+
+                (state.id.clone(), JsonValue::Object(state_transitions))
+            })
+            .collect();
+
+        JsonValue::Object(vec![
+            (String::from("transitions"), JsonValue::Object(transitions)),
+            (
+                String::from("startState"),
+                JsonValue::String(self.start_state.id.clone()),
+            ),
+            (
+                String::from("acceptStates"),
+                JsonValue::Array(
+                    self.all_states
+                        .iter()
+                        .filter(|state| state.is_accept_state)
+                        .map(|state| JsonValue::String(state.id.clone()))
+                        .collect(),
+                ),
+            ),
+        ])
+    }
+
+    /// Creates the "states" object holding where each state is drawn
+    fn site_states(&self) -> JsonValue {
+        JsonValue::Object(
+            self.all_states
+                .iter()
+                .map(|state| {
+                    (
+                        state.id.clone(),
+                        JsonValue::Object(vec![
+                            (
+                                String::from("isAccept"),
+                                JsonValue::Bool(state.is_accept_state),
+                            ),
+                            (String::from("top"), JsonValue::Number(state.position.y)),
+                            (String::from("left"), JsonValue::Number(state.position.x)),
+                            (
+                                String::from("displayId"),
+                                JsonValue::String(state.alt_id.clone()),
+                            ),
+                        ]),
+                    )
+                })
+                .collect(),
+        )
+    }
+
+    /// Creates the "transitions" list of the labelled arrows drawn between states
+    fn site_transitions(&self) -> JsonValue {
+        JsonValue::Array(
+            self.all_states
+                .iter()
+                .flat_map(|state| {
+                    state
+                        .get_transitions()
+                        .iter()
+                        .map(|transition| {
+                            JsonValue::Object(vec![
+                                (String::from("stateA"), JsonValue::String(state.id.clone())),
+                                (
+                                    String::from("label"),
+                                    JsonValue::String(self.site_label(transition)),
+                                ),
+                                (
+                                    String::from("stateB"),
+                                    JsonValue::String(transition.next_state_id().clone()),
+                                ),
+                            ])
+                        })
+                        .collect::<Vec<JsonValue>>()
+                })
+                .collect(),
+        )
+    }
+
+    /// PDA labels always have their pop and push symbols written out as the site expects three symbols
+    fn site_label(&self, transition: &Transition) -> String {
+        match self.automaton_type {
+            PDA => format!(
+                "{},{},{}",
+                transition.transition_on(),
+                transition.pop_symbol().unwrap_or(Symbol::EPSILON),
+                transition.push_symbol().unwrap_or(Symbol::EPSILON)
+            ),
+            DFA | NFA => transition.label(),
+        }
+    }
+
+    /// Creates the state moved to and the symbol pushed by a PDA transition
+    ///
+    /// Eg: { "state": "s0", "stackPushChars": "A" }
+    fn site_pda_move(transition: &Transition) -> JsonValue {
+        JsonValue::Object(vec![
+            (
+                String::from("state"),
+                JsonValue::String(transition.next_state_id().clone()),
+            ),
+            (
+                String::from("stackPushChars"),
+                JsonValue::String(Self::site_symbol(transition.push_symbol())),
+            ),
+        ])
+    }
+
+    /// The site writes ϵ as an empty string in the object it simulates from
+    fn site_symbol(symbol: Option<Symbol>) -> String {
+        match symbol {
+            Some(Symbol::CHAR(c)) => c.to_string(),
+            Some(Symbol::EPSILON) | None => String::new(),
+        }
+    }
+
+    /// Groups transitions by a key keeping the order each key was first seen in
+    fn group_by<'a>(
+        transitions: impl Iterator<Item = &'a Transition>,
+        key: impl Fn(&Transition) -> String,
+    ) -> Vec<(String, Vec<&'a Transition>)> {
+        let mut groups: Vec<(String, Vec<&Transition>)> = vec![];
+        for transition in transitions {
+            let transition_key = key(transition);
+            match groups
+                .iter_mut()
+                .find(|(group_key, _)| *group_key == transition_key)
+            {
+                Some((_, group)) => group.push(transition),
+                None => groups.push((transition_key, vec![transition])),
+            }
+        }
+        groups
+    }
+}
+
+impl JsonValue {
+    /// Writes the value indenting nested objects and lists by two spaces
+    fn write(&self, f: &mut Formatter<'_>, indent: usize) -> fmt::Result {
+        let inner_indent = "  ".repeat(indent + 1);
+
+        match self {
+            JsonValue::Object(entries) if entries.is_empty() => write!(f, "{{}}"),
+            JsonValue::Array(values) if values.is_empty() => write!(f, "[]"),
+            JsonValue::Object(entries) => {
+                writeln!(f, "{{")?;
+                for (idx, (key, value)) in entries.iter().enumerate() {
+                    write!(f, "{}", inner_indent)?;
+                    Self::write_string(f, key)?;
+                    write!(f, ": ")?;
+                    value.write(f, indent + 1)?;
+                    writeln!(f, "{}", if idx + 1 < entries.len() { "," } else { "" })?;
+                }
+                write!(f, "{}}}", "  ".repeat(indent))
+            }
+            JsonValue::Array(values) => {
+                writeln!(f, "[")?;
+                for (idx, value) in values.iter().enumerate() {
+                    write!(f, "{}", inner_indent)?;
+                    value.write(f, indent + 1)?;
+                    writeln!(f, "{}", if idx + 1 < values.len() { "," } else { "" })?;
+                }
+                write!(f, "{}]", "  ".repeat(indent))
+            }
+            JsonValue::String(string) => Self::write_string(f, string),
+            JsonValue::Number(number) => write!(f, "{}", number),
+            JsonValue::Bool(value) => write!(f, "{}", value),
+        }
+    }
+
+    /// Writes a string in quotation marks escaping the characters json does not allow in strings
+    fn write_string(f: &mut Formatter<'_>, string: &str) -> fmt::Result {
+        f.write_char('"')?;
+        for c in string.chars() {
+            match c {
+                '"' => f.write_str("\\\"")?,
+                '\\' => f.write_str("\\\\")?,
+                '\n' => f.write_str("\\n")?,
+                '\r' => f.write_str("\\r")?,
+                '\t' => f.write_str("\\t")?,
+                c if c.is_control() => write!(f, "\\u{:04x}", c as u32)?,
+                c => f.write_char(c)?,
+            }
+        }
+        f.write_char('"')
+    }
+}
+
+impl Display for JsonValue {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        self.write(f, 0)
+    }
+}
+
+#[cfg(test)]
+mod site_json_tests {
+    use crate::parser::Parser;
+
+    const PDA_FILE: &str = r#"{
+  "type": "PDA",
+  "pda": {
+    "transitions": {
+      "start": {
+        "a": {
+          "": [
+            {
+              "state": "start",
+              "stackPushChars": "A"
+            }
+          ]
+        },
+        "b": {
+          "A": [
+            {
+              "state": "s0",
+              "stackPushChars": ""
+            }
+          ]
+        }
+      }
+    },
+    "startState": "start",
+    "acceptStates": [
+      "s0"
+    ]
+  },
+  "states": {
+    "start": {
+      "isAccept": false,
+      "top": 0,
+      "left": 0,
+      "displayId": "start"
+    },
+    "s0": {
+      "isAccept": true,
+      "top": 278.66667650585936,
+      "left": -12.5,
+      "displayId": "q \"0\"\n"
+    }
+  },
+  "transitions": [
+    {
+      "stateA": "start",
+      "label": "a,ϵ,A",
+      "stateB": "start"
+    },
+    {
+      "stateA": "start",
+      "label": "b,A,ϵ",
+      "stateB": "s0"
+    }
+  ],
+  "bulkTests": {
+    "accept": "ab\naabb",
+    "reject": "ba"
+  }
+}"#;
+
+    #[test]
+    fn test_site_json_round_trips() {
+        let automaton = Parser::parse(PDA_FILE).unwrap().0;
+        assert_eq!(automaton.to_site_json(), PDA_FILE);
+    }
+
+    #[test]
+    fn test_site_json_round_trips_parsed_files() {
+        let automaton = Parser::parse(
+            r#"{"type": "NFA", "states": {"start": {}, "s0": {"isAccept": true}},
+            "transitions": [{"stateA": "start", "label": "a", "stateB": "s0"},
+            {"stateA": "start", "label": "a", "stateB": "start"}, {"stateA": "s0", "label": "ϵ", "stateB": "start"}]}"#,
+        )
+        .unwrap().0;
+        let site_json = automaton.to_site_json();
+
+        assert!(site_json.contains("\"a\": [\n          \"s0\",\n          \"start\"\n        ]"));
+        assert!(site_json.contains("\"s0\": {\n        \"\": [\n          \"start\"\n        ]"));
+        assert_eq!(
+            Parser::parse(&site_json).unwrap().0.to_site_json(),
+            site_json
+        );
+    }
+
+    #[test]
+    fn test_automaton_without_bulk_tests_round_trips() {
+        let dsl = "dfa start=q0 accept=q1\nq0 -a-> q1\nq1 -a-> q0\n";
+        let site_json = Parser::parse_dsl(dsl).unwrap().to_site_json();
+
+        assert!(site_json.contains("\"accept\": \"\",\n    \"reject\": \"\""));
+        assert_eq!(Parser::parse(&site_json).unwrap().0.to_dsl(), dsl);
+    }
+
+    #[test]
+    fn test_empty_test_strings_round_trip() {
+        let dsl = "dfa start=q0 accept=q0\nq0 -a-> q1\nq1 -a-> q0\naccepts \"\", \"aa\"\nrejects \"a\", \"\"\n";
+        let site_json = Parser::parse_dsl(dsl).unwrap().to_site_json();
+
+        assert!(site_json.contains("\"accept\": \"\\naa\",\n    \"reject\": \"a\\n\""));
+        assert_eq!(Parser::parse(&site_json).unwrap().0.to_dsl(), dsl);
+    }
+}
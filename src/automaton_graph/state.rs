@@ -1,5 +1,6 @@
 use crate::automaton_graph::{Position, State, Transition};
 use std::cell::{Ref, RefCell};
+use std::collections::HashMap;
 use std::rc::Rc;
 
 impl State {
@@ -29,8 +30,11 @@ impl State {
         self.transition_edges.borrow()
     }
 
-    /// Define a function to search for a node by its ID
-    pub fn find_state_by_id(states: &Vec<Rc<State>>, target_id: &str) -> Option<Rc<State>> {
-        states.iter().find(|node| node.id == target_id).cloned()
+    /// Maps the id of every state to the state, so many states can be found without searching the list for each
+    pub fn map_by_id(states: &[Rc<State>]) -> HashMap<String, Rc<State>> {
+        states
+            .iter()
+            .map(|state| (state.id.clone(), state.clone()))
+            .collect()
     }
 }
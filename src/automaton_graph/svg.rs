@@ -0,0 +1,330 @@
+use std::collections::HashSet;
+use std::fmt::Write;
+
+use crate::automaton_graph::{Automaton, Layout, Position};
+
+/// Radius of the circle drawn for a state
+const STATE_RADIUS: f64 = 20.0;
+/// Space left around the states at the edges of the picture
+const MARGIN: f64 = 60.0;
+/// How far edges going both ways between two states are bent apart
+const CURVE_OFFSET: f64 = 30.0;
+
+const STROKE_COLOUR: &str = "#000000";
+const HIGHLIGHT_COLOUR: &str = "#d62728";
+const HIGHLIGHT_FILL: &str = "#ffe08a";
+
+/// States and transitions to draw in a different colour, such as the
+/// configuration reached while simulating a string
+#[derive(Debug, Default)]
+pub struct Highlight {
+    /// Ids of the states to highlight
+    pub states: HashSet<String>,
+    /// Transitions to highlight given as the ids of the states they go from and to
+    pub transitions: HashSet<(String, String)>,
+}
+
+impl Automaton {
+    /// Draws the automaton as an SVG image without needing any external tools
+    pub fn to_svg(&self) -> String {
+        self.to_svg_highlighted(&Highlight::default())
+    }
+
+    /// Draws the automaton as an SVG image with some states and transitions highlighted
+    ///
+    /// States are drawn at their [Position], accept states as double circles and the start
+    /// state with an arrow pointing into it. Transitions between the same pair of states are
+    /// drawn as one edge with their labels joined
+    ///
+    /// # Arguments
+    ///
+    /// * `highlight`: The states and transitions to draw highlighted
+    pub fn to_svg_highlighted(&self, highlight: &Highlight) -> String {
+        let positions = self.svg_positions();
+        let position_of = |state_id: &str| {
+            self.all_states
+                .iter()
+                .position(|state| state.id == state_id)
+                .map_or(Position::default(), |idx| positions[idx])
+        };
+
+        let width = positions.iter().map(|p| p.x).fold(0.0, f64::max) + MARGIN;
+        let height = positions.iter().map(|p| p.y).fold(0.0, f64::max) + MARGIN;
+
+        let mut svg = String::new();
+        // Writing to a String cannot fail
+        let _ = writeln!(
+            svg,
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{:.1}\" height=\"{:.1}\" viewBox=\"0 0 {:.1} {:.1}\" font-family=\"sans-serif\" font-size=\"14\">",
+            width, height, width, height
+        );
+        svg.push_str("  <defs>\n");
+        for (id, colour) in [
+            ("arrow", STROKE_COLOUR),
+            ("arrow-highlight", HIGHLIGHT_COLOUR),
+        ] {
+            let _ = writeln!(
+                svg,
+                "    <marker id=\"{}\" viewBox=\"0 0 10 10\" refX=\"10\" refY=\"5\" markerWidth=\"8\" markerHeight=\"8\" orient=\"auto-start-reverse\"><path d=\"M 0 0 L 10 5 L 0 10 z\" fill=\"{}\"/></marker>",
+                id, colour
+            );
+        }
+        svg.push_str("  </defs>\n");
+
+        // Edges are drawn first so the states are drawn over their ends
+        for state in &self.all_states {
+            let from = position_of(&state.id);
+
+            for (to_state, labels) in self.merged_edges(state) {
+                let to = position_of(&to_state.id);
+                let is_highlighted = highlight
+                    .transitions
+                    .contains(&(state.id.clone(), to_state.id.clone()));
+                let (colour, marker, stroke_width) = if is_highlighted {
+                    (HIGHLIGHT_COLOUR, "arrow-highlight", 3)
+                } else {
+                    (STROKE_COLOUR, "arrow", 1)
+                };
+
+                let is_bidirectional = to_state.id != state.id
+                    && to_state
+                        .get_transitions()
+                        .iter()
+                        .any(|transition| *transition.next_state_id() == state.id);
+
+                let (path, label_position) = if to_state.id == state.id {
+                    Self::svg_self_loop(from)
+                } else if is_bidirectional {
+                    Self::svg_curved_edge(from, to)
+                } else {
+                    Self::svg_straight_edge(from, to)
+                };
+
+                let _ = writeln!(
+                    svg,
+                    "  <path d=\"{}\" fill=\"none\" stroke=\"{}\" stroke-width=\"{}\" marker-end=\"url(#{})\"/>",
+                    path, colour, stroke_width, marker
+                );
+                let _ = writeln!(
+                    svg,
+                    "  <text x=\"{:.1}\" y=\"{:.1}\" text-anchor=\"middle\" dominant-baseline=\"middle\" fill=\"{}\">{}</text>",
+                    label_position.x,
+                    label_position.y,
+                    colour,
+                    Self::escape_xml(&labels.join(", "))
+                );
+            }
+        }
+
+        for (state, position) in self.all_states.iter().zip(&positions) {
+            let is_highlighted = highlight.states.contains(&state.id);
+            let (colour, fill, stroke_width) = if is_highlighted {
+                (HIGHLIGHT_COLOUR, HIGHLIGHT_FILL, 3)
+            } else {
+                (STROKE_COLOUR, "#ffffff", 1)
+            };
+
+            if state.id == self.start_state.id {
+                let _ = writeln!(
+                    svg,
+                    "  <path d=\"M {:.1} {:.1} L {:.1} {:.1}\" stroke=\"{}\" marker-end=\"url(#arrow)\"/>",
+                    position.x - STATE_RADIUS * 2.5,
+                    position.y,
+                    position.x - STATE_RADIUS,
+                    position.y,
+                    STROKE_COLOUR
+                );
+            }
+
+            let _ = writeln!(
+                svg,
+                "  <circle cx=\"{:.1}\" cy=\"{:.1}\" r=\"{:.1}\" fill=\"{}\" stroke=\"{}\" stroke-width=\"{}\"/>",
+                position.x, position.y, STATE_RADIUS, fill, colour, stroke_width
+            );
+            if state.is_accept_state {
+                let _ = writeln!(
+                    svg,
+                    "  <circle cx=\"{:.1}\" cy=\"{:.1}\" r=\"{:.1}\" fill=\"none\" stroke=\"{}\" stroke-width=\"{}\"/>",
+                    position.x,
+                    position.y,
+                    STATE_RADIUS - 4.0,
+                    colour,
+                    stroke_width
+                );
+            }
+            let _ = writeln!(
+                svg,
+                "  <text x=\"{:.1}\" y=\"{:.1}\" text-anchor=\"middle\" dominant-baseline=\"middle\">{}</text>",
+                position.x,
+                position.y,
+                Self::escape_xml(&state.alt_id)
+            );
+        }
+
+        svg.push_str("</svg>");
+        svg
+    }
+
+    /// Finds where each state is drawn, moving the states so the picture starts at the margin
+    ///
+    /// States that all share the same position, such as those of a file written by hand,
+    /// are laid out so they can be told apart
+    fn svg_positions(&self) -> Vec<Position> {
+        let all_stacked = self.all_states.windows(2).all(|states| {
+            states[0].position.x == states[1].position.x
+                && states[0].position.y == states[1].position.y
+        });
+        let positions = if all_stacked && self.all_states.len() > 1 {
+            self.layout_positions(Layout::Layered)
+        } else {
+            self.all_states.iter().map(|state| state.position).collect()
+        };
+
+        let min_x = positions.iter().map(|p| p.x).fold(f64::INFINITY, f64::min);
+        let min_y = positions.iter().map(|p| p.y).fold(f64::INFINITY, f64::min);
+
+        // Leave room above the top states for their self loops
+        positions
+            .iter()
+            .map(|position| Position {
+                x: position.x - min_x + MARGIN,
+                y: position.y - min_y + MARGIN * 1.5,
+            })
+            .collect()
+    }
+
+    /// A line between the edges of two states, returning the path and where its label goes
+    fn svg_straight_edge(from: Position, to: Position) -> (String, Position) {
+        let (dx, dy) = Self::svg_direction(from, to);
+        let start = Self::svg_offset(from, dx, dy, STATE_RADIUS);
+        let end = Self::svg_offset(to, -dx, -dy, STATE_RADIUS);
+
+        // Labels sit just to the side of the middle of the line
+        let label = Position {
+            x: (start.x + end.x) / 2.0 + dy * 12.0,
+            y: (start.y + end.y) / 2.0 - dx * 12.0,
+        };
+
+        (
+            format!(
+                "M {:.1} {:.1} L {:.1} {:.1}",
+                start.x, start.y, end.x, end.y
+            ),
+            label,
+        )
+    }
+
+    /// A curve bent to the left of the direction of travel so edges going both ways
+    /// between two states do not overlap, returning the path and where its label goes
+    fn svg_curved_edge(from: Position, to: Position) -> (String, Position) {
+        let (dx, dy) = Self::svg_direction(from, to);
+        let control = Position {
+            x: (from.x + to.x) / 2.0 + dy * CURVE_OFFSET * 2.0,
+            y: (from.y + to.y) / 2.0 - dx * CURVE_OFFSET * 2.0,
+        };
+
+        let (start_dx, start_dy) = Self::svg_direction(from, control);
+        let (end_dx, end_dy) = Self::svg_direction(to, control);
+        let start = Self::svg_offset(from, start_dx, start_dy, STATE_RADIUS);
+        let end = Self::svg_offset(to, end_dx, end_dy, STATE_RADIUS);
+
+        // The middle of a quadratic curve is halfway between its control point and the middle of its ends
+        let label = Position {
+            x: (start.x + end.x) / 4.0 + control.x / 2.0 + dy * 12.0,
+            y: (start.y + end.y) / 4.0 + control.y / 2.0 - dx * 12.0,
+        };
+
+        (
+            format!(
+                "M {:.1} {:.1} Q {:.1} {:.1} {:.1} {:.1}",
+                start.x, start.y, control.x, control.y, end.x, end.y
+            ),
+            label,
+        )
+    }
+
+    /// A loop above a state, returning the path and where its label goes
+    fn svg_self_loop(state: Position) -> (String, Position) {
+        let start = Self::svg_offset(state, -0.5, -0.87, STATE_RADIUS);
+        let end = Self::svg_offset(state, 0.5, -0.87, STATE_RADIUS);
+
+        (
+            format!(
+                "M {:.1} {:.1} C {:.1} {:.1} {:.1} {:.1} {:.1} {:.1}",
+                start.x,
+                start.y,
+                state.x - STATE_RADIUS * 1.5,
+                state.y - STATE_RADIUS * 3.0,
+                state.x + STATE_RADIUS * 1.5,
+                state.y - STATE_RADIUS * 3.0,
+                end.x,
+                end.y
+            ),
+            Position {
+                x: state.x,
+                y: state.y - STATE_RADIUS * 3.0,
+            },
+        )
+    }
+
+    /// Unit vector pointing from one position to another
+    fn svg_direction(from: Position, to: Position) -> (f64, f64) {
+        let (dx, dy) = (to.x - from.x, to.y - from.y);
+        let length = (dx * dx + dy * dy).sqrt();
+        if length == 0.0 {
+            (1.0, 0.0)
+        } else {
+            (dx / length, dy / length)
+        }
+    }
+
+    /// Moves a position a distance along a direction
+    fn svg_offset(position: Position, dx: f64, dy: f64, distance: f64) -> Position {
+        Position {
+            x: position.x + dx * distance,
+            y: position.y + dy * distance,
+        }
+    }
+}
+
+#[cfg(test)]
+mod svg_tests {
+    use crate::automaton_graph::Highlight;
+    use crate::parser::Parser;
+
+    const NFA_FILE: &str = r#"{"type":"NFA","states":{"start":{"top":100,"left":100},"s0":{"isAccept":true,"top":100,"left":250,"displayId":"q<0>"}},
+        "transitions":[{"stateA":"start","label":"a","stateB":"s0"},{"stateA":"s0","label":"b","stateB":"start"},{"stateA":"s0","label":"a","stateB":"s0"}]}"#;
+
+    #[test]
+    fn test_svg_draws_states_and_edges() {
+        let svg = Parser::parse(NFA_FILE).unwrap().0.to_svg();
+
+        assert!(svg.starts_with(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"270.0\" height=\"150.0\""
+        ));
+        // Both states with an inner circle for the accept state
+        assert_eq!(svg.matches("<circle").count(), 3);
+        assert!(svg.contains(">q&lt;0&gt;</text>"));
+        // Two curved edges between the states and a loop on s0
+        assert_eq!(svg.matches(" Q ").count(), 2);
+        assert_eq!(svg.matches(" C ").count(), 1);
+        assert!(!svg.contains("url(#arrow-highlight)"));
+    }
+
+    #[test]
+    fn test_svg_highlights() {
+        let highlight = Highlight {
+            states: ["s0".to_string()].into_iter().collect(),
+            transitions: [("start".to_string(), "s0".to_string())]
+                .into_iter()
+                .collect(),
+        };
+        let svg = Parser::parse(NFA_FILE)
+            .unwrap()
+            .0
+            .to_svg_highlighted(&highlight);
+
+        assert_eq!(svg.matches("url(#arrow-highlight)").count(), 1);
+        assert_eq!(svg.matches(super::HIGHLIGHT_FILL).count(), 1);
+    }
+}
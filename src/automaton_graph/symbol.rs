@@ -17,3 +17,14 @@ impl From<Symbol> for char {
         }
     }
 }
+
+impl From<char> for Symbol {
+    /// Converts a char to a symbol treating 'ϵ' as an epsilon
+    fn from(value: char) -> Symbol {
+        if value == char::from(Symbol::EPSILON) {
+            Symbol::EPSILON
+        } else {
+            Symbol::CHAR(value)
+        }
+    }
+}
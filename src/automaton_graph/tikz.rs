@@ -0,0 +1,130 @@
+use std::fmt::Write;
+
+use crate::automaton_graph::Automaton;
+
+/// Number of pixels on the automaton simulator site drawn as a centimetre
+const PIXELS_PER_CM: f64 = 50.0;
+
+impl Automaton {
+    /// Writes the automaton as a `tikzpicture` drawn with the TikZ `automata` library,
+    /// placing each state at its [Position](crate::automaton_graph::Position)
+    ///
+    /// The document needs `\usepackage{tikz}` and `\usetikzlibrary{automata, arrows.meta}`
+    pub fn to_tikz(&self) -> String {
+        let mut tikz = String::from(
+            "\\begin{tikzpicture}[->, >={Stealth[round]}, shorten >=1pt, auto, semithick]\n",
+        );
+        let node_name = |state_id: &str| {
+            format!(
+                "q{}",
+                self.all_states
+                    .iter()
+                    .position(|state| state.id == state_id)
+                    .unwrap_or_default()
+            )
+        };
+
+        for state in &self.all_states {
+            let mut node_style = String::from("state");
+            if state.id == self.start_state.id {
+                node_style.push_str(", initial");
+            }
+            if state.is_accept_state {
+                node_style.push_str(", accepting");
+            }
+
+            // Writing to a String cannot fail
+            let _ = writeln!(
+                tikz,
+                "  \\node[{}] ({}) at ({:.2}, {:.2}) {{{}}};",
+                node_style,
+                node_name(&state.id),
+                state.position.x / PIXELS_PER_CM,
+                -state.position.y / PIXELS_PER_CM,
+                escape_latex(&state.alt_id)
+            );
+        }
+
+        tikz.push_str("  \\path");
+        for state in &self.all_states {
+            for (to, labels) in self.merged_edges(state) {
+                let edge_style = if to.id == state.id {
+                    "loop above"
+                } else if to
+                    .get_transitions()
+                    .iter()
+                    .any(|transition| *transition.next_state_id() == state.id)
+                {
+                    // Edges going both ways between two states are bent so they do not overlap
+                    "bend left"
+                } else {
+                    ""
+                };
+
+                let _ = write!(
+                    tikz,
+                    "\n    ({}) edge [{}] node {{{}}} ({})",
+                    node_name(&state.id),
+                    edge_style,
+                    escape_latex(&labels.join(", ")),
+                    if to.id == state.id {
+                        String::new()
+                    } else {
+                        node_name(&to.id)
+                    }
+                );
+            }
+        }
+        tikz.push_str(";\n\\end{tikzpicture}");
+
+        tikz
+    }
+}
+
+/// Escapes the characters that have a special meaning in LaTeX, writing symbols
+/// used by automata such as ϵ in math mode
+pub fn escape_latex(text: &str) -> String {
+    text.chars().fold(String::new(), |mut escaped, c| {
+        match c {
+            '\\' => escaped.push_str("\\textbackslash{}"),
+            '&' | '%' | '$' | '#' | '_' | '{' | '}' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            '~' => escaped.push_str("\\textasciitilde{}"),
+            '^' => escaped.push_str("\\textasciicircum{}"),
+            'ϵ' => escaped.push_str("$\\epsilon$"),
+            '→' => escaped.push_str("$\\to$"),
+            c => escaped.push(c),
+        }
+        escaped
+    })
+}
+
+#[cfg(test)]
+mod tikz_tests {
+    use crate::parser::Parser;
+
+    #[test]
+    fn test_tikz_picture() {
+        let tikz = Parser::parse(
+            r#"{"type":"NFA","states":{"start":{"top":100,"left":50},"s_1":{"isAccept":true,"top":100,"left":150}},
+            "transitions":[{"stateA":"start","label":"a","stateB":"s_1"},{"stateA":"s_1","label":"ϵ","stateB":"start"},
+            {"stateA":"s_1","label":"b","stateB":"s_1"}]}"#,
+        )
+        .unwrap().0
+        .to_tikz();
+
+        assert_eq!(
+            tikz,
+            r"\begin{tikzpicture}[->, >={Stealth[round]}, shorten >=1pt, auto, semithick]
+  \node[state, initial] (q0) at (1.00, -2.00) {start};
+  \node[state, accepting] (q1) at (3.00, -2.00) {s\_1};
+  \path
+    (q0) edge [bend left] node {a} (q1)
+    (q1) edge [bend left] node {$\epsilon$} (q0)
+    (q1) edge [loop above] node {b} ();
+\end{tikzpicture}"
+        );
+    }
+}
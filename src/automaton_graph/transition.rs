@@ -1,4 +1,3 @@
-use std::cell::Ref;
 use std::cmp::Ordering;
 use std::fmt;
 use std::rc::Rc;
@@ -68,38 +67,14 @@ impl Transition {
         self.pop_symbol
     }
 
-    /// Finds all transitions that can be taken if given
-    /// a particular symbol
+    /// Returns the label of the transition as written on the automaton simulator site
     ///
-    /// # Arguments
-    ///
-    /// * `transitions`: List of transitions
-    /// * `symbol`: The char equivalent of that symbol
-    /// Note to check for transitions on epsilon use [Self::find_epsilon_transitions]
-    pub fn find_transition_by_symbol(
-        transitions: Ref<Vec<Transition>>,
-        symbol: char,
-    ) -> Vec<Transition> {
-        transitions
-            .iter()
-            .filter_map(|transition| {
-                if char::from(transition.symbol) == symbol {
-                    Some(transition.clone())
-                } else {
-                    None
-                }
-            })
-            .collect::<Vec<Transition>>()
-    }
-
-    /// Finds all transitions that can be taken on an
-    /// epsilon transition [Symbol::EPSILON] \ ϵ
-    ///
-    /// # Arguments
-    ///
-    /// * `transitions`: List of transitions
-    pub fn find_epsilon_transitions(transitions: Ref<Vec<Transition>>) -> Vec<Transition> {
-        Transition::find_transition_by_symbol(transitions, char::from(Symbol::EPSILON))
+    /// Ex: "a" for DFAs & NFAs or "a,X,Y" for PDAs
+    pub fn label(&self) -> String {
+        match (self.pop_symbol, self.push_symbol) {
+            (Some(pop), Some(push)) => format!("{},{},{}", self.symbol, pop, push),
+            _ => self.symbol.to_string(),
+        }
     }
 }
 
@@ -0,0 +1,134 @@
+use std::cmp::Ordering;
+use std::fmt::Write;
+
+use crate::automaton_graph::AutomatonType::PDA;
+use crate::automaton_graph::{Automaton, Symbol};
+
+/// Layouts a transition table can be written in
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum TableFormat {
+    Csv,
+    Markdown,
+}
+
+impl Automaton {
+    /// Writes a DFA or NFA as a transition table, with a row for each state and a column for each symbol
+    ///
+    /// The start state is marked with → and accept states with *. Cells hold the state transitioned to,
+    /// or a set such as {q1,q2} when an NFA can go to several states. Returns [None] for PDAs
+    /// as their transitions also depend on the stack
+    ///
+    /// # Arguments
+    ///
+    /// * `format`: Whether to write the table as CSV or a Markdown table
+    pub fn to_transition_table(&self, format: TableFormat) -> Option<String> {
+        if let PDA = self.automaton_type {
+            return None;
+        }
+
+        let mut alphabet: Vec<Symbol> = vec![];
+        for state in &self.all_states {
+            for transition in state.get_transitions().iter() {
+                if !alphabet.contains(&transition.transition_on()) {
+                    alphabet.push(transition.transition_on());
+                }
+            }
+        }
+        // ϵ is sorted after every character
+        alphabet.sort_by(|a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+
+        let mut rows: Vec<Vec<String>> = vec![std::iter::once(String::new())
+            .chain(alphabet.iter().map(|symbol| symbol.to_string()))
+            .collect()];
+
+        for state in &self.all_states {
+            let mut row = vec![format!(
+                "{}{}{}",
+                if state.id == self.start_state.id {
+                    "→"
+                } else {
+                    ""
+                },
+                if state.is_accept_state { "*" } else { "" },
+                state.id
+            )];
+
+            for symbol in &alphabet {
+                let mut to_states: Vec<&str> = vec![];
+                let transitions = state.get_transitions();
+                for transition in transitions.iter() {
+                    if transition.transition_on() == *symbol
+                        && !to_states.contains(&transition.next_state_id().as_str())
+                    {
+                        to_states.push(transition.next_state_id());
+                    }
+                }
+
+                row.push(match to_states[..] {
+                    // Markdown tables are easier to read with something in every cell
+                    [] => match format {
+                        TableFormat::Csv => String::new(),
+                        TableFormat::Markdown => String::from("∅"),
+                    },
+                    [to_state] => String::from(to_state),
+                    _ => format!("{{{}}}", to_states.join(",")),
+                });
+            }
+
+            rows.push(row);
+        }
+
+        Some(match format {
+            TableFormat::Csv => rows
+                .iter()
+                .map(|row| {
+                    row.iter()
+                        .map(|cell| Self::csv_cell(cell))
+                        .collect::<Vec<String>>()
+                        .join(",")
+                        + "\n"
+                })
+                .collect(),
+            TableFormat::Markdown => {
+                let mut table = String::new();
+                for (idx, row) in rows.iter().enumerate() {
+                    // Writing to a String cannot fail
+                    let _ = writeln!(table, "| {} |", row.join(" | "));
+                    if idx == 0 {
+                        let _ = writeln!(table, "|{}", "---|".repeat(row.len()));
+                    }
+                }
+                table
+            }
+        })
+    }
+
+    /// Quotes a CSV cell when it holds a comma or quote
+    fn csv_cell(cell: &str) -> String {
+        if cell.contains([',', '"']) {
+            format!("\"{}\"", cell.replace('"', "\"\""))
+        } else {
+            String::from(cell)
+        }
+    }
+}
+
+#[cfg(test)]
+mod transition_table_tests {
+    use crate::automaton_graph::TableFormat;
+    use crate::parser::Parser;
+
+    #[test]
+    fn test_nfa_transition_table() {
+        let nfa = Parser::parse_dsl("nfa start=q0 accept=q1\nq0 -1,0-> q0; q0 -0,ϵ-> q1").unwrap();
+
+        assert_eq!(
+            nfa.to_transition_table(TableFormat::Csv).unwrap(),
+            ",0,1,ϵ\n→q0,\"{q0,q1}\",q0,q1\n*q1,,,\n"
+        );
+        assert_eq!(
+            nfa.to_transition_table(TableFormat::Markdown).unwrap(),
+            "|  | 0 | 1 | ϵ |\n|---|---|---|---|\n| →q0 | {q0,q1} | q0 | q1 |\n| *q1 | ∅ | ∅ | ∅ |\n"
+        );
+    }
+}
@@ -0,0 +1,290 @@
+use std::fmt::Write;
+use std::time::{Duration, Instant};
+
+use crate::automaton_graph::{Automaton, JsonValue};
+use crate::simulator::Simulator;
+
+/// Result of running one of the strings in the bulkTests of a file
+#[derive(Debug)]
+pub struct TestCase {
+    pub input: String,
+    /// Whether the file expects the string to be accepted
+    pub expected: bool,
+    /// Whether the string was accepted, or why it could not be simulated
+    pub result: Result<bool, String>,
+    pub duration: Duration,
+}
+
+impl TestCase {
+    pub fn passed(&self) -> bool {
+        self.result == Ok(self.expected)
+    }
+
+    /// Names the test after how it is written in the text format, Ex: accepts "ab"
+    pub fn name(&self) -> String {
+        format!(
+            "{} \"{}\"",
+            if self.expected { "accepts" } else { "rejects" },
+            self.input
+        )
+    }
+}
+
+/// Results of running every string in the bulkTests of a file against its automaton
+#[derive(Debug)]
+pub struct BulkTestReport {
+    /// Name of the file the tests come from
+    pub name: String,
+    pub cases: Vec<TestCase>,
+}
+
+impl BulkTestReport {
+    /// Runs the accepting strings and then the rejecting strings saved with the automaton
+    ///
+    /// # Arguments
+    ///
+    /// * `name`: Name of the file the automaton was read from, used to name the test suite
+    /// * `automaton`: Automaton of any type along with its tests
+    pub fn run(name: &str, automaton: Automaton) -> BulkTestReport {
+        let tests = automaton.tests.clone();
+        let simulator = Simulator::new(automaton);
+
+        let cases = tests
+            .accepting_strings
+            .into_iter()
+            .map(|input| (input, true))
+            .chain(
+                tests
+                    .rejecting_strings
+                    .into_iter()
+                    .map(|input| (input, false)),
+            )
+            .map(|(input, expected)| {
+                let start = Instant::now();
+                let result = simulator
+                    .accepts(&input)
+                    .map_err(|err| format!("{:?}", err));
+                TestCase {
+                    input,
+                    expected,
+                    result,
+                    duration: start.elapsed(),
+                }
+            })
+            .collect();
+
+        BulkTestReport {
+            name: String::from(name),
+            cases,
+        }
+    }
+
+    pub fn passed(&self) -> usize {
+        self.cases.iter().filter(|case| case.passed()).count()
+    }
+
+    /// Number of strings that were accepted when they should have been rejected or the other way around
+    pub fn failures(&self) -> usize {
+        self.cases
+            .iter()
+            .filter(|case| matches!(case.result, Ok(accepted) if accepted != case.expected))
+            .count()
+    }
+
+    /// Number of strings that could not be simulated
+    pub fn errors(&self) -> usize {
+        self.cases
+            .iter()
+            .filter(|case| case.result.is_err())
+            .count()
+    }
+
+    pub fn is_success(&self) -> bool {
+        self.passed() == self.cases.len()
+    }
+
+    /// Writes a line for each string saying whether it passed, followed by a summary
+    pub fn to_text(&self) -> String {
+        let mut text = String::new();
+        for case in &self.cases {
+            // Writing to a String cannot fail
+            let _ = match &case.result {
+                Ok(_) if case.passed() => writeln!(text, "PASS  {}", case.name()),
+                Ok(accepted) => writeln!(
+                    text,
+                    "FAIL  {} but it was {}",
+                    case.name(),
+                    if *accepted { "accepted" } else { "rejected" }
+                ),
+                Err(message) => writeln!(text, "ERROR {}: {}", case.name(), message),
+            };
+        }
+        let _ = writeln!(
+            text,
+            "{} passed, {} failed, {} errors",
+            self.passed(),
+            self.failures(),
+            self.errors()
+        );
+
+        text
+    }
+
+    /// Writes the report as a JUnit XML test suite, which CI servers can display
+    pub fn to_junit(&self) -> String {
+        let suite_name = Automaton::escape_xml(&self.name);
+        let total_time = self
+            .cases
+            .iter()
+            .map(|case| case.duration)
+            .sum::<Duration>()
+            .as_secs_f64();
+
+        let mut junit = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        // Writing to a String cannot fail
+        let _ = writeln!(
+            junit,
+            "<testsuites tests=\"{}\" failures=\"{}\" errors=\"{}\" time=\"{:.3}\">",
+            self.cases.len(),
+            self.failures(),
+            self.errors(),
+            total_time
+        );
+        let _ = writeln!(
+            junit,
+            "\t<testsuite name=\"{}\" tests=\"{}\" failures=\"{}\" errors=\"{}\" time=\"{:.3}\">",
+            suite_name,
+            self.cases.len(),
+            self.failures(),
+            self.errors(),
+            total_time
+        );
+
+        for case in &self.cases {
+            let _ = write!(
+                junit,
+                "\t\t<testcase classname=\"{}\" name=\"{}\" time=\"{:.3}\"",
+                suite_name,
+                Automaton::escape_xml(&case.name()),
+                case.duration.as_secs_f64()
+            );
+            match &case.result {
+                Ok(_) if case.passed() => junit.push_str("/>\n"),
+                Ok(accepted) => {
+                    let _ = writeln!(
+                        junit,
+                        ">\n\t\t\t<failure message=\"expected the string to be {} but it was {}\"/>\n\t\t</testcase>",
+                        if case.expected { "accepted" } else { "rejected" },
+                        if *accepted { "accepted" } else { "rejected" }
+                    );
+                }
+                Err(message) => {
+                    let _ = writeln!(
+                        junit,
+                        ">\n\t\t\t<error message=\"{}\"/>\n\t\t</testcase>",
+                        Automaton::escape_xml(message)
+                    );
+                }
+            }
+        }
+
+        junit.push_str("\t</testsuite>\n</testsuites>\n");
+        junit
+    }
+
+    /// Builds the report as json, with the counts followed by the result of each string
+    pub(crate) fn json_value(&self) -> JsonValue {
+        JsonValue::Object(vec![
+            (String::from("name"), JsonValue::String(self.name.clone())),
+            (
+                String::from("passed"),
+                JsonValue::Number(self.passed() as f64),
+            ),
+            (
+                String::from("failed"),
+                JsonValue::Number(self.failures() as f64),
+            ),
+            (
+                String::from("errors"),
+                JsonValue::Number(self.errors() as f64),
+            ),
+            (
+                String::from("results"),
+                JsonValue::Array(
+                    self.cases
+                        .iter()
+                        .map(|case| {
+                            let mut result = vec![
+                                (String::from("input"), JsonValue::String(case.input.clone())),
+                                (String::from("expected"), JsonValue::Bool(case.expected)),
+                            ];
+                            result.push(match &case.result {
+                                Ok(accepted) => {
+                                    (String::from("accepted"), JsonValue::Bool(*accepted))
+                                }
+                                Err(message) => {
+                                    (String::from("error"), JsonValue::String(message.clone()))
+                                }
+                            });
+                            result.push((String::from("passed"), JsonValue::Bool(case.passed())));
+                            JsonValue::Object(result)
+                        })
+                        .collect(),
+                ),
+            ),
+        ])
+    }
+
+    pub fn to_json(&self) -> String {
+        format!("{}\n", self.json_value())
+    }
+}
+
+#[cfg(test)]
+mod bulk_tests_tests {
+    use crate::bulk_tests::BulkTestReport;
+    use crate::parser::Parser;
+
+    #[test]
+    fn test_nfa_bulk_tests_report() {
+        let nfa = Parser::parse_dsl(
+            "nfa start=q0 accept=q1\nq0 -a-> q0; q0 -a-> q1\naccepts \"a\", \"aa\"\nrejects \"\", \"b\", \"a<a\"\n",
+        )
+        .unwrap();
+        let report = BulkTestReport::run("a&b.aut", nfa);
+
+        assert_eq!(report.passed(), 5);
+        assert!(report.is_success());
+        assert!(report.to_text().ends_with("5 passed, 0 failed, 0 errors\n"));
+
+        let junit = report.to_junit();
+        assert!(junit.contains("<testsuite name=\"a&amp;b.aut\" tests=\"5\" failures=\"0\""));
+        assert!(junit.contains("name=\"rejects &quot;a&lt;a&quot;\""));
+    }
+
+    #[test]
+    fn test_empty_bulk_tests_are_not_run() {
+        let (dfa, _) = Parser::parse(include_str!("TESTS/input.txt")).unwrap();
+        let report = BulkTestReport::run("input.txt", dfa);
+
+        assert!(report.is_success());
+        assert_eq!(report.to_text(), "0 passed, 0 failed, 0 errors\n");
+    }
+
+    #[test]
+    fn test_failing_bulk_test() {
+        let dfa =
+            Parser::parse_dsl("dfa start=q0 accept=q0\nq0 -a-> q0\nrejects \"aa\"\n").unwrap();
+        let report = BulkTestReport::run("a.aut", dfa);
+
+        assert_eq!(report.failures(), 1);
+        assert!(!report.is_success());
+        assert!(report.to_junit().contains(
+            "<failure message=\"expected the string to be rejected but it was accepted\"/>"
+        ));
+        assert_eq!(
+            report.to_text(),
+            "FAIL  rejects \"aa\" but it was accepted\n0 passed, 1 failed, 0 errors\n"
+        );
+    }
+}
@@ -0,0 +1,1081 @@
+use std::cmp::Ordering;
+use std::fs;
+use std::io::{self, BufRead, Read};
+use std::path::Path;
+use std::rc::Rc;
+
+use crate::automaton_graph::AutomatonType::{DFA, NFA, PDA};
+use crate::automaton_graph::{Automaton, JsonValue, Layout, Symbol, TableFormat};
+use crate::bulk_tests::BulkTestReport;
+use crate::dfa::{self, ClassNaming};
+use crate::menus;
+use crate::parser::{Parser, ValidationIssue};
+use crate::pda::PDA as PdaSimulator;
+use crate::repl::Repl;
+use crate::simulator::{RunStatus, Simulator};
+use crate::trace::Trace;
+
+/// Every string was accepted and every test passed
+pub const EXIT_SUCCESS: i32 = 0;
+/// A string was rejected or a test failed
+pub const EXIT_FAILURE: i32 = 1;
+/// The arguments or the automaton could not be understood
+pub const EXIT_USAGE: i32 = 2;
+
+pub const USAGE: &str = "Usage: automaton <COMMAND> [OPTIONS] <FILE>
+
+Commands:
+  simulate <FILE> <STRING>...   Runs the automaton on each string
+  reduce <FILE>                 Writes the minimal DFA for a DFA in the site format
+  convert --to <FORMAT> <FILE>  Writes the automaton as site, jff, dot, tikz, svg, mermaid, dsl, csv or md
+  intersect <FILE> <DFA FILE>   Writes the PDA accepting what both a PDA and a DFA accept, in the site format or --to
+  test <FILE>                   Runs the accept and reject strings saved with the automaton
+  info <FILE>                   Describes the states, alphabet and transitions of the automaton
+  menu <FILE>                   Chooses what to do from an interactive menu
+  repl [FILE]...                Loads the files into an interactive session, see help inside it
+
+Options:
+  -o, --output <FILE>    Writes the output to a file instead of stdout
+  --format <text|json>   Writes the results of simulate, reduce, test and info as text or json
+  --format latex         Writes the steps reduce took as LaTeX
+  --format svg           Draws the automaton with the states a run of simulate ended in highlighted
+  --trace                Writes every configuration each run of simulate went through
+  --report <FILE>        Also writes the results of test to a JUnit XML file when it ends in .xml, or json otherwise
+  --naming <NAMING>      Displays the states reduce merges by their class, merged or first state
+  --from <FORMAT>        Reads the automaton as site, jff, dsl or table instead of going by its extension
+  --layout <LAYOUT>      Places the states of convert in layered columns or by a force-directed layout
+  -h, --help             Prints this message
+
+Use - as the file to read the automaton from stdin, or as the only string of simulate to run each line of stdin.
+Exits with 0 on success, 1 when a string is rejected or a test fails and 2 on errors.";
+
+/// What the command line asks to be done with the automaton
+#[derive(Debug, PartialEq)]
+pub enum Subcommand {
+    /// Runs the strings, writing every configuration each run went through when tracing,
+    /// or drawing the run of a single string as an SVG image
+    Simulate {
+        strings: Vec<String>,
+        trace: bool,
+        svg: bool,
+    },
+    /// Reduces a DFA, writing the steps of the reduction as LaTeX when asked to
+    Reduce {
+        latex: bool,
+        naming: ClassNaming,
+    },
+    /// Converts the automaton to a format, placing its states by a layout first when given one
+    Convert {
+        to: ConvertFormat,
+        layout: Option<Layout>,
+    },
+    /// Intersects the PDA with the DFA in another file, writing the product in a format
+    Intersect {
+        dfa_file: String,
+        to: ConvertFormat,
+    },
+    Test,
+    Info,
+    Menu,
+    Repl {
+        files: Vec<String>,
+    },
+    Help,
+}
+
+/// Formats the automaton can be converted to
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum ConvertFormat {
+    Site,
+    Jff,
+    Dot,
+    Tikz,
+    Svg,
+    Mermaid,
+    Dsl,
+    Csv,
+    Markdown,
+}
+
+/// Formats automata can be read from
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum InputFormat {
+    Site,
+    Jff,
+    Dsl,
+    Table,
+}
+
+/// How results are written
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum OutputFormat {
+    Text,
+    Json,
+}
+
+/// A command read from the command line
+#[derive(Debug, PartialEq)]
+pub struct Command {
+    pub subcommand: Subcommand,
+    /// Path to the automaton, or - for stdin
+    pub file: String,
+    pub output: Option<String>,
+    pub format: OutputFormat,
+    pub from: Option<InputFormat>,
+    /// File the results of test are also written to
+    pub report: Option<String>,
+}
+
+/// What a command wrote and the code the program should exit with
+#[derive(Debug)]
+struct Outcome {
+    output: String,
+    exit_code: i32,
+}
+
+impl Command {
+    /// Reads a command from the arguments given to the program, leaving out the program name
+    ///
+    /// Returns a message describing what is wrong with the arguments when they cannot be understood
+    pub fn parse_args(args: &[String]) -> Result<Command, String> {
+        let mut args = args.iter();
+        let mut positional: Vec<String> = vec![];
+        let mut output = None;
+        let mut format = OutputFormat::Text;
+        let mut from = None;
+        let mut report = None;
+        let mut to = None;
+        let mut trace = false;
+        let mut latex = false;
+        let mut svg = false;
+        let mut layout = None;
+        let mut naming = None;
+
+        let option_value = |option: &str, args: &mut std::slice::Iter<String>| {
+            args.next()
+                .cloned()
+                .ok_or_else(|| format!("{} needs a value", option))
+        };
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "-h" | "--help" => {
+                    return Ok(Command {
+                        subcommand: Subcommand::Help,
+                        file: String::new(),
+                        output: None,
+                        format,
+                        from,
+                        report,
+                    })
+                }
+                "-o" | "--output" => output = Some(option_value(arg, &mut args)?),
+                "--trace" => trace = true,
+                "--report" => report = Some(option_value(arg, &mut args)?),
+                "--format" => match option_value(arg, &mut args)?.as_str() {
+                    "text" => format = OutputFormat::Text,
+                    "json" => format = OutputFormat::Json,
+                    // Only the steps of reduce can be written as LaTeX
+                    "latex" => latex = true,
+                    // Only a run of simulate can be drawn
+                    "svg" => svg = true,
+                    other => return Err(format!("Unknown output format '{}'", other)),
+                },
+                "--from" => {
+                    let name = option_value(arg, &mut args)?;
+                    from = Some(
+                        InputFormat::from_name(&name)
+                            .ok_or_else(|| format!("Unknown input format '{}'", name))?,
+                    )
+                }
+                "--naming" => {
+                    let name = option_value(arg, &mut args)?;
+                    naming = Some(
+                        ClassNaming::from_name(&name)
+                            .ok_or_else(|| format!("Unknown naming '{}'", name))?,
+                    )
+                }
+                "--layout" => {
+                    let name = option_value(arg, &mut args)?;
+                    layout = Some(
+                        Layout::from_name(&name)
+                            .ok_or_else(|| format!("Unknown layout '{}'", name))?,
+                    )
+                }
+                "--to" => {
+                    let name = option_value(arg, &mut args)?;
+                    to = Some(
+                        ConvertFormat::from_name(&name)
+                            .ok_or_else(|| format!("Unknown conversion format '{}'", name))?,
+                    )
+                }
+                // A lone - is the stdin file rather than an option
+                option if option.starts_with('-') && option != "-" => {
+                    return Err(format!("Unknown option '{}'", option))
+                }
+                _ => positional.push(arg.clone()),
+            }
+        }
+
+        let mut positional = positional.into_iter();
+        let command = positional
+            .next()
+            .ok_or_else(|| String::from("No command was given"))?;
+        if command == "repl" {
+            return Ok(Command {
+                subcommand: Subcommand::Repl {
+                    files: positional.collect(),
+                },
+                file: String::new(),
+                output,
+                format,
+                from,
+                report,
+            });
+        }
+        let file = positional
+            .next()
+            .ok_or_else(|| format!("{} needs the file of an automaton", command))?;
+        let rest = positional.collect::<Vec<String>>();
+
+        let subcommand = match command.as_str() {
+            "simulate" if rest.is_empty() => {
+                return Err(String::from("simulate needs at least one string to run"))
+            }
+            "simulate" if rest == ["-"] && file == "-" => return Err(String::from(
+                "simulate reads its strings from stdin so the automaton has to come from a file",
+            )),
+            "simulate" if rest == ["-"] && (trace || svg) => {
+                return Err(String::from(
+                    "Strings read from stdin cannot be traced or drawn",
+                ))
+            }
+            "simulate" if svg && rest.len() > 1 => {
+                return Err(String::from(
+                    "--format svg draws the run of a single string",
+                ))
+            }
+            "simulate" => Subcommand::Simulate {
+                strings: rest,
+                trace,
+                svg,
+            },
+            "intersect" => match &rest[..] {
+                [dfa_file] => Subcommand::Intersect {
+                    dfa_file: dfa_file.clone(),
+                    to: to.unwrap_or(ConvertFormat::Site),
+                },
+                _ => {
+                    return Err(String::from(
+                        "intersect needs the file of a PDA followed by the file of a DFA",
+                    ))
+                }
+            },
+            _ if !rest.is_empty() => {
+                return Err(format!("Unexpected argument '{}'", rest[0]));
+            }
+            "reduce" => Subcommand::Reduce {
+                latex,
+                naming: naming.unwrap_or_default(),
+            },
+            "convert" => Subcommand::Convert {
+                to: to.ok_or_else(|| String::from("convert needs --to <FORMAT>"))?,
+                layout,
+            },
+            "test" => Subcommand::Test,
+            "info" => Subcommand::Info,
+            "menu" => Subcommand::Menu,
+            other => return Err(format!("Unknown command '{}'", other)),
+        };
+        if latex && !matches!(subcommand, Subcommand::Reduce { .. }) {
+            return Err(String::from("--format latex can only be used with reduce"));
+        }
+        if svg && !matches!(subcommand, Subcommand::Simulate { .. }) {
+            return Err(String::from("--format svg can only be used with simulate"));
+        }
+        if naming.is_some() && !matches!(subcommand, Subcommand::Reduce { .. }) {
+            return Err(String::from("--naming can only be used with reduce"));
+        }
+        if layout.is_some() && !matches!(subcommand, Subcommand::Convert { .. }) {
+            return Err(String::from("--layout can only be used with convert"));
+        }
+
+        Ok(Command {
+            subcommand,
+            file,
+            output,
+            format,
+            from,
+            report,
+        })
+    }
+
+    /// Runs the command, writing its output and returning the code the program should exit with
+    pub fn run(&self) -> i32 {
+        if self.subcommand == Subcommand::Help {
+            println!("{}", USAGE);
+            return EXIT_SUCCESS;
+        }
+        if let Subcommand::Repl { files } = &self.subcommand {
+            Repl::new().run(files);
+            return EXIT_SUCCESS;
+        }
+        if self.subcommand == Subcommand::Menu && self.file == "-" {
+            eprintln!("menu reads its choices from stdin so the automaton has to come from a file");
+            return EXIT_USAGE;
+        }
+
+        let program = match self.read_program() {
+            Ok(program) => program,
+            Err(message) => {
+                eprintln!("{}", message);
+                return EXIT_USAGE;
+            }
+        };
+
+        let outcome = match self.execute(&program) {
+            Ok(outcome) => outcome,
+            Err(message) => {
+                eprint!("{}", message);
+                return EXIT_USAGE;
+            }
+        };
+
+        match &self.output {
+            Some(output_path) => {
+                if let Err(err) = fs::write(output_path, &outcome.output) {
+                    eprintln!("Could not write to the file {}: {}", output_path, err);
+                    return EXIT_USAGE;
+                }
+            }
+            None => print!("{}", outcome.output),
+        }
+
+        outcome.exit_code
+    }
+
+    /// Reads the automaton from its file or from stdin
+    fn read_program(&self) -> Result<String, String> {
+        if self.file == "-" {
+            let mut program = String::new();
+            io::stdin()
+                .read_to_string(&mut program)
+                .map_err(|err| format!("Could not read the automaton from stdin: {}", err))?;
+            Ok(program)
+        } else {
+            fs::read_to_string(&self.file)
+                .map_err(|err| format!("Could not read the file {}: {}", self.file, err))
+        }
+    }
+
+    /// Name of the automaton file shown in error messages
+    fn file_name(&self) -> &str {
+        if self.file == "-" {
+            "<stdin>"
+        } else {
+            &self.file
+        }
+    }
+
+    /// Parses the automaton, printing any warnings found in it
+    fn load_automaton(&self, program: &str) -> Result<Automaton, String> {
+        let format = self
+            .from
+            .unwrap_or_else(|| InputFormat::detect(&self.file, program));
+        load_automaton(self.file_name(), program, format)
+    }
+
+    /// Runs the command on the text of an automaton
+    fn execute(&self, program: &str) -> Result<Outcome, String> {
+        let automaton = self.load_automaton(program)?;
+
+        match &self.subcommand {
+            Subcommand::Simulate { strings, .. } if strings == &["-"] => {
+                self.simulate_lines(automaton, io::stdin().lock())
+            }
+            Subcommand::Simulate {
+                strings,
+                trace,
+                svg,
+            } => self.simulate(automaton, strings, *trace, *svg),
+            Subcommand::Reduce { latex, naming } => self.reduce(automaton, *latex, *naming),
+            Subcommand::Convert { to, layout } => {
+                let automaton = match layout {
+                    Some(layout) => automaton.with_layout(*layout),
+                    None => automaton,
+                };
+                convert(&automaton, *to).map(|output| Outcome {
+                    output,
+                    exit_code: EXIT_SUCCESS,
+                })
+            }
+            Subcommand::Intersect { dfa_file, to } => {
+                intersect(automaton, dfa_file, *to).map(|output| Outcome {
+                    output,
+                    exit_code: EXIT_SUCCESS,
+                })
+            }
+            Subcommand::Test => self.test(automaton),
+            Subcommand::Info => Ok(self.info(automaton)),
+            Subcommand::Menu => {
+                let automaton_type = automaton.automaton_type;
+                let menu_idx = menus::find_command_from_menu(match automaton_type {
+                    DFA => menus::dfa_menu::list(),
+                    NFA => menus::nfa_menu::list(),
+                    PDA => menus::pda_menu::list(),
+                });
+
+                match automaton_type {
+                    DFA => menus::dfa_menu::table(menu_idx, automaton),
+                    NFA => menus::nfa_menu::table(menu_idx, automaton),
+                    PDA => menus::pda_menu::table(menu_idx, automaton),
+                }
+
+                Ok(Outcome {
+                    output: String::new(),
+                    exit_code: EXIT_SUCCESS,
+                })
+            }
+            Subcommand::Repl { .. } | Subcommand::Help => Ok(Outcome {
+                output: format!("{}\n", USAGE),
+                exit_code: EXIT_SUCCESS,
+            }),
+        }
+    }
+
+    /// Runs the automaton on each string, failing when any of them is rejected
+    ///
+    /// When tracing, each string is written with the configurations its run went through.
+    /// When drawing, the automaton is written as an SVG image highlighting the run of the string
+    fn simulate(
+        &self,
+        automaton: Automaton,
+        strings: &[String],
+        trace: bool,
+        svg: bool,
+    ) -> Result<Outcome, String> {
+        let drawn_automaton = svg.then(|| automaton.clone());
+        let simulator = Simulator::new(automaton);
+
+        // Configurations are only kept when they are shown
+        let (output, all_accepted) = if trace || svg {
+            let traces = strings
+                .iter()
+                .map(|string| {
+                    let trace = simulator.trace(string);
+                    match &trace.result {
+                        Ok(_) => Ok(trace),
+                        Err(message) => Err(format!("{}\n", message)),
+                    }
+                })
+                .collect::<Result<Vec<Trace>, String>>()?;
+
+            let output = if let Some(automaton) = drawn_automaton {
+                // Only a single string is run when drawing
+                automaton.to_svg_highlighted(&traces[0].highlight())
+            } else {
+                match self.format {
+                    OutputFormat::Text => traces.iter().map(Trace::to_text).collect(),
+                    OutputFormat::Json => Self::json_output(JsonValue::Array(
+                        traces.iter().map(Trace::json_value).collect(),
+                    )),
+                }
+            };
+            (output, traces.iter().all(|trace| trace.result == Ok(true)))
+        } else {
+            let results = strings
+                .iter()
+                .map(|string| {
+                    simulator
+                        .accepts(string)
+                        .map(|accepted| (string.as_str(), accepted))
+                        .map_err(|err| format!("{:?}\n", err))
+                })
+                .collect::<Result<Vec<(&str, bool)>, String>>()?;
+            (
+                self.results_output(&results),
+                results.iter().all(|(_, accepted)| *accepted),
+            )
+        };
+
+        Ok(Outcome {
+            output,
+            exit_code: if all_accepted {
+                EXIT_SUCCESS
+            } else {
+                EXIT_FAILURE
+            },
+        })
+    }
+
+    /// Runs the automaton on each line of the input, feeding each line to the same run as it is read
+    fn simulate_lines(&self, automaton: Automaton, input: impl BufRead) -> Result<Outcome, String> {
+        let simulator = Simulator::new(automaton);
+        let mut runner = simulator.runner("");
+
+        let mut results = vec![];
+        for line in input.lines() {
+            let line =
+                line.map_err(|err| format!("Could not read the strings from stdin: {}\n", err))?;
+            runner.reset();
+            for c in line.chars() {
+                runner.feed_char(c);
+            }
+            let accepted = runner.is_accepting();
+            if let RunStatus::Failed(err) = runner.status() {
+                return Err(format!("{:?}\n", err));
+            }
+            results.push((line, accepted));
+        }
+
+        Ok(Outcome {
+            output: self.results_output(
+                &results
+                    .iter()
+                    .map(|(string, accepted)| (string.as_str(), *accepted))
+                    .collect::<Vec<(&str, bool)>>(),
+            ),
+            exit_code: if results.iter().all(|(_, accepted)| *accepted) {
+                EXIT_SUCCESS
+            } else {
+                EXIT_FAILURE
+            },
+        })
+    }
+
+    /// Writes whether each string was accepted
+    fn results_output(&self, results: &[(&str, bool)]) -> String {
+        match self.format {
+            OutputFormat::Text => results
+                .iter()
+                .map(|(string, accepted)| {
+                    format!(
+                        "{}\t\"{}\"\n",
+                        if *accepted { "accept" } else { "reject" },
+                        string
+                    )
+                })
+                .collect(),
+            OutputFormat::Json => Self::json_output(JsonValue::Array(
+                results
+                    .iter()
+                    .map(|(string, accepted)| {
+                        JsonValue::Object(vec![
+                            (String::from("input"), JsonValue::String(string.to_string())),
+                            (String::from("accepted"), JsonValue::Bool(*accepted)),
+                        ])
+                    })
+                    .collect(),
+            )),
+        }
+    }
+
+    /// Reduces a DFA, displaying the merged states as `naming` says, and writing the DFA unchanged
+    /// when it cannot be reduced any further
+    ///
+    /// Writes the steps the reduction took and the table of classes of each iteration instead when writing LaTeX
+    fn reduce(
+        &self,
+        automaton: Automaton,
+        latex: bool,
+        naming: ClassNaming,
+    ) -> Result<Outcome, String> {
+        if !matches!(automaton.automaton_type, DFA) {
+            return Err(String::from("Only DFAs can be reduced\n"));
+        }
+
+        let dfa = dfa::DFA::new(automaton);
+        let steps = dfa.reduce_with_naming(naming);
+
+        let output = match (&steps, self.format) {
+            (Some(steps), _) if latex => steps.to_latex(),
+            (None, _) if latex => String::from("The DFA cannot be reduced any further\n"),
+            (Some(steps), OutputFormat::Text) => steps.reduced_dfa.to_site_json() + "\n",
+            (None, OutputFormat::Text) => dfa.automaton_graph().to_site_json() + "\n",
+            (steps, OutputFormat::Json) => {
+                let mut state_classes = match steps {
+                    Some(steps) => steps
+                        .state_classes
+                        .iter()
+                        .map(|(state_id, class_id)| {
+                            (state_id.clone(), JsonValue::String(class_id.clone()))
+                        })
+                        .collect::<Vec<(String, JsonValue)>>(),
+                    None => vec![],
+                };
+                state_classes.sort_by(|a, b| a.0.cmp(&b.0));
+
+                Self::json_output(JsonValue::Object(vec![
+                    (String::from("reduced"), JsonValue::Bool(steps.is_some())),
+                    (
+                        String::from("stateClasses"),
+                        JsonValue::Object(state_classes),
+                    ),
+                    (
+                        String::from("automaton"),
+                        match steps {
+                            Some(steps) => steps.reduced_dfa.site_json_value(),
+                            None => dfa.automaton_graph().site_json_value(),
+                        },
+                    ),
+                ]))
+            }
+        };
+
+        Ok(Outcome {
+            output,
+            exit_code: EXIT_SUCCESS,
+        })
+    }
+
+    /// Runs the strings saved with the automaton, failing when any of them is not accepted or rejected as expected
+    ///
+    /// The report is also written to the --report file, as JUnit XML when it ends in .xml and as json otherwise
+    fn test(&self, automaton: Automaton) -> Result<Outcome, String> {
+        let report = BulkTestReport::run(self.file_name(), automaton);
+
+        if let Some(report_path) = &self.report {
+            let is_junit = Path::new(report_path)
+                .extension()
+                .is_some_and(|extension| extension == "xml");
+            let contents = if is_junit {
+                report.to_junit()
+            } else {
+                report.to_json()
+            };
+            fs::write(report_path, contents).map_err(|err| {
+                format!(
+                    "Could not write the report to the file {}: {}\n",
+                    report_path, err
+                )
+            })?;
+        }
+
+        Ok(Outcome {
+            output: match self.format {
+                OutputFormat::Text => report.to_text(),
+                OutputFormat::Json => report.to_json(),
+            },
+            exit_code: if report.is_success() {
+                EXIT_SUCCESS
+            } else {
+                EXIT_FAILURE
+            },
+        })
+    }
+
+    /// Describes the states, alphabet and transitions of the automaton
+    fn info(&self, automaton: Automaton) -> Outcome {
+        let mut alphabet: Vec<Symbol> = vec![];
+        let mut transition_count = 0;
+        let mut is_deterministic = true;
+
+        for state in automaton.all_states() {
+            let mut state_symbols: Vec<Symbol> = vec![];
+            for transition in state.get_transitions().iter() {
+                let symbol = transition.transition_on();
+                transition_count += 1;
+                is_deterministic &= symbol != Symbol::EPSILON && !state_symbols.contains(&symbol);
+                state_symbols.push(symbol);
+                if symbol != Symbol::EPSILON && !alphabet.contains(&symbol) {
+                    alphabet.push(symbol);
+                }
+            }
+        }
+        alphabet.sort_by(|a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+
+        let type_name = match automaton.automaton_type {
+            DFA => "DFA",
+            NFA => "NFA",
+            PDA => "PDA",
+        };
+        let state_ids = automaton
+            .all_states()
+            .iter()
+            .map(|state| state.id.clone())
+            .collect::<Vec<String>>();
+        let accept_state_ids = automaton
+            .all_states()
+            .iter()
+            .filter(|state| state.is_accept_state)
+            .map(|state| state.id.clone())
+            .collect::<Vec<String>>();
+        let start_state_id = automaton.get_start_state().id.clone();
+        let alphabet = alphabet
+            .iter()
+            .map(|symbol| symbol.to_string())
+            .collect::<Vec<String>>();
+        let test_count =
+            automaton.tests.accepting_strings.len() + automaton.tests.rejecting_strings.len();
+
+        // PDAs are deterministic when no two transitions can be taken from the same configuration
+        if let PDA = automaton.automaton_type {
+            is_deterministic = PdaSimulator::new(Rc::new(automaton)).is_deterministic();
+        }
+
+        let output = match self.format {
+            OutputFormat::Text => format!(
+                "type: {}\nstates: {}\nstart state: {}\naccept states: {}\nalphabet: {}\ntransitions: {}\ndeterministic: {}\ntests: {}\n",
+                type_name,
+                state_ids.join(", "),
+                start_state_id,
+                accept_state_ids.join(", "),
+                alphabet.join(", "),
+                transition_count,
+                if is_deterministic { "yes" } else { "no" },
+                test_count
+            ),
+            OutputFormat::Json => {
+                let strings = |strings: Vec<String>| {
+                    JsonValue::Array(strings.into_iter().map(JsonValue::String).collect())
+                };
+                Self::json_output(JsonValue::Object(vec![
+                    (String::from("type"), JsonValue::String(String::from(type_name))),
+                    (String::from("states"), strings(state_ids)),
+                    (String::from("startState"), JsonValue::String(start_state_id)),
+                    (String::from("acceptStates"), strings(accept_state_ids)),
+                    (String::from("alphabet"), strings(alphabet)),
+                    (
+                        String::from("transitions"),
+                        JsonValue::Number(transition_count as f64),
+                    ),
+                    (String::from("deterministic"), JsonValue::Bool(is_deterministic)),
+                    (String::from("tests"), JsonValue::Number(test_count as f64)),
+                ]))
+            }
+        };
+
+        Outcome {
+            output,
+            exit_code: EXIT_SUCCESS,
+        }
+    }
+
+    /// Writes json output ending in a new line
+    fn json_output(value: JsonValue) -> String {
+        format!("{}\n", value)
+    }
+}
+
+impl InputFormat {
+    /// Finds the input format with a name given on the command line
+    pub fn from_name(name: &str) -> Option<InputFormat> {
+        match name {
+            "site" | "json" => Some(InputFormat::Site),
+            "jff" => Some(InputFormat::Jff),
+            "dsl" | "aut" => Some(InputFormat::Dsl),
+            "table" | "csv" | "md" => Some(InputFormat::Table),
+            _ => None,
+        }
+    }
+
+    /// Chooses how to read an automaton from the extension of its file, or what it starts with
+    /// when the extension does not say
+    pub fn detect(file: &str, program: &str) -> InputFormat {
+        let extension = Path::new(file)
+            .extension()
+            .and_then(|extension| extension.to_str());
+        match extension {
+            Some("jff") => InputFormat::Jff,
+            Some("aut") => InputFormat::Dsl,
+            Some("csv") | Some("md") => InputFormat::Table,
+            Some("json") | Some("txt") => InputFormat::Site,
+            _ => {
+                let program = program.trim_start();
+                if program.starts_with('{') {
+                    InputFormat::Site
+                } else if program.starts_with('<') {
+                    InputFormat::Jff
+                } else if ["dfa", "nfa", "pda"]
+                    .iter()
+                    .any(|automaton_type| program.starts_with(automaton_type))
+                {
+                    InputFormat::Dsl
+                } else {
+                    InputFormat::Table
+                }
+            }
+        }
+    }
+}
+
+impl ConvertFormat {
+    /// Names the formats are given on the command line
+    pub const NAMES: [&'static str; 9] = [
+        "site", "jff", "dot", "tikz", "svg", "mermaid", "dsl", "csv", "md",
+    ];
+
+    /// Finds the conversion format with a name given on the command line
+    pub fn from_name(name: &str) -> Option<ConvertFormat> {
+        match name {
+            "site" | "json" => Some(ConvertFormat::Site),
+            "jff" => Some(ConvertFormat::Jff),
+            "dot" => Some(ConvertFormat::Dot),
+            "tikz" => Some(ConvertFormat::Tikz),
+            "svg" => Some(ConvertFormat::Svg),
+            "mermaid" => Some(ConvertFormat::Mermaid),
+            "dsl" | "aut" => Some(ConvertFormat::Dsl),
+            "csv" => Some(ConvertFormat::Csv),
+            "md" | "markdown" => Some(ConvertFormat::Markdown),
+            _ => None,
+        }
+    }
+}
+
+/// Parses an automaton, printing any warnings found in it
+///
+/// Returns the rendered errors when the automaton cannot be loaded
+///
+/// # Arguments
+///
+/// * `file_name`: Name of the file shown in errors and warnings
+/// * `program`: Text of the automaton
+/// * `format`: Format the automaton is written in
+pub fn load_automaton(
+    file_name: &str,
+    program: &str,
+    format: InputFormat,
+) -> Result<Automaton, String> {
+    let automaton = match format {
+        InputFormat::Jff => Parser::parse_jff(program),
+        InputFormat::Dsl => Parser::parse_dsl(program),
+        InputFormat::Table => Parser::parse_transition_table(program),
+        InputFormat::Site => {
+            let render = |issues: Vec<ValidationIssue>| {
+                issues
+                    .iter()
+                    .map(|issue| issue.render(file_name, program))
+                    .collect::<String>()
+            };
+
+            return match Parser::parse(program) {
+                Ok((automaton, warnings)) => {
+                    eprint!("{}", render(warnings));
+                    Ok(automaton)
+                }
+                // Every problem is listed so the file can be fixed in one go
+                Err(_) => Err(render(Parser::validate(program))),
+            };
+        }
+    };
+
+    automaton.map_err(|err| err.render(file_name, program))
+}
+
+/// Writes the automaton in another format
+pub fn convert(automaton: &Automaton, to: ConvertFormat) -> Result<String, String> {
+    let converted = match to {
+        ConvertFormat::Site => automaton.to_site_json(),
+        ConvertFormat::Jff => automaton.to_jff(),
+        ConvertFormat::Dot => automaton.to_dot(has_positions(automaton)),
+        // TikZ places states where they are, so states that were never placed are laid out first
+        ConvertFormat::Tikz if !has_positions(automaton) => {
+            automaton.with_layout(Layout::Layered).to_tikz()
+        }
+        ConvertFormat::Tikz => automaton.to_tikz(),
+        ConvertFormat::Svg => automaton.to_svg(),
+        ConvertFormat::Mermaid => automaton.to_mermaid(),
+        ConvertFormat::Dsl => automaton.to_dsl(),
+        ConvertFormat::Csv | ConvertFormat::Markdown => automaton
+            .to_transition_table(if to == ConvertFormat::Csv {
+                TableFormat::Csv
+            } else {
+                TableFormat::Markdown
+            })
+            .ok_or_else(|| String::from("PDAs cannot be written as a transition table\n"))?,
+    };
+
+    // Every format is written ending in a new line
+    Ok(if converted.ends_with('\n') {
+        converted
+    } else {
+        converted + "\n"
+    })
+}
+
+/// Intersects a PDA with the DFA in a file, writing the product PDA in another format
+fn intersect(pda: Automaton, dfa_file: &str, to: ConvertFormat) -> Result<String, String> {
+    if !matches!(pda.automaton_type, PDA) {
+        return Err(String::from("Only PDAs can be intersected with a DFA\n"));
+    }
+
+    let dfa_program = fs::read_to_string(dfa_file)
+        .map_err(|err| format!("Could not read the file {}: {}\n", dfa_file, err))?;
+    let dfa = load_automaton(
+        dfa_file,
+        &dfa_program,
+        InputFormat::detect(dfa_file, &dfa_program),
+    )?;
+    if !matches!(dfa.automaton_type, DFA) {
+        return Err(format!(
+            "{} is not a DFA, PDAs can only be intersected with DFAs\n",
+            dfa_file
+        ));
+    }
+
+    convert(
+        &PdaSimulator::new(Rc::new(pda)).intersect(&dfa::DFA::new(dfa)),
+        to,
+    )
+}
+
+/// Whether the states have been given positions rather than all sitting at the same place
+fn has_positions(automaton: &Automaton) -> bool {
+    automaton.all_states().windows(2).any(|states| {
+        states[0].position.x != states[1].position.x || states[0].position.y != states[1].position.y
+    })
+}
+
+#[cfg(test)]
+mod cli_tests {
+    use crate::automaton_graph::Layout;
+    use crate::cli::{
+        Command, ConvertFormat, OutputFormat, Subcommand, EXIT_FAILURE, EXIT_SUCCESS,
+    };
+    use crate::dfa::ClassNaming;
+    use crate::parser::Parser;
+
+    const DFA_PROGRAM: &str =
+        "dfa start=q0 accept=q1\nq0 -a-> q1; q1 -a-> q0\naccepts \"a\"\nrejects \"aa\", \"b\"\n";
+
+    fn command(args: &[&str]) -> Result<Command, String> {
+        Command::parse_args(
+            &args
+                .iter()
+                .map(|arg| arg.to_string())
+                .collect::<Vec<String>>(),
+        )
+    }
+
+    #[test]
+    fn test_parse_args() {
+        assert_eq!(
+            command(&["convert", "--to", "dot", "-o", "out.dot", "-"]).unwrap(),
+            Command {
+                subcommand: Subcommand::Convert {
+                    to: ConvertFormat::Dot,
+                    layout: None,
+                },
+                file: String::from("-"),
+                output: Some(String::from("out.dot")),
+                format: OutputFormat::Text,
+                from: None,
+                report: None,
+            }
+        );
+        assert!(command(&["simulate", "a.aut"]).is_err());
+        assert!(command(&["convert", "a.aut"]).is_err());
+        assert_eq!(
+            command(&["convert", "--to", "svg", "--layout", "force", "a.aut"])
+                .unwrap()
+                .subcommand,
+            Subcommand::Convert {
+                to: ConvertFormat::Svg,
+                layout: Some(Layout::ForceDirected),
+            }
+        );
+        assert!(command(&["info", "--layout", "force", "a.aut"]).is_err());
+        assert_eq!(
+            command(&["reduce", "--naming", "first", "a.aut"])
+                .unwrap()
+                .subcommand,
+            Subcommand::Reduce {
+                latex: false,
+                naming: ClassNaming::FirstMember,
+            }
+        );
+        assert!(command(&["convert", "--to", "dot", "--naming", "first", "a.aut"]).is_err());
+        assert!(command(&["convert", "--to", "svg", "--layout", "grid", "a.aut"]).is_err());
+        assert!(command(&["info", "a.aut", "--colour"]).is_err());
+    }
+
+    #[test]
+    fn test_simulate_lines_of_stdin() {
+        let outcome = command(&["simulate", "a.aut", "-"])
+            .unwrap()
+            .simulate_lines(
+                Parser::parse_dsl(DFA_PROGRAM).unwrap(),
+                "a\naaa\naa\n".as_bytes(),
+            )
+            .unwrap();
+        assert_eq!(
+            outcome.output,
+            "accept\t\"a\"\naccept\t\"aaa\"\nreject\t\"aa\"\n"
+        );
+        assert_eq!(outcome.exit_code, EXIT_FAILURE);
+
+        assert!(command(&["simulate", "-", "-"]).is_err());
+        assert!(command(&["simulate", "--trace", "a.aut", "-"]).is_err());
+    }
+
+    #[test]
+    fn test_simulate_and_test_exit_codes() {
+        let outcome = command(&["simulate", "-", "a", "aa"])
+            .unwrap()
+            .execute(DFA_PROGRAM)
+            .unwrap();
+        assert_eq!(outcome.output, "accept\t\"a\"\nreject\t\"aa\"\n");
+        assert_eq!(outcome.exit_code, EXIT_FAILURE);
+
+        let outcome = command(&["test", "--format", "json", "-"])
+            .unwrap()
+            .execute(DFA_PROGRAM)
+            .unwrap();
+        assert!(outcome
+            .output
+            .starts_with("{\n  \"name\": \"<stdin>\",\n  \"passed\": 3,\n  \"failed\": 0,"));
+        assert_eq!(outcome.exit_code, EXIT_SUCCESS);
+    }
+
+    #[test]
+    fn test_intersect_with_dfa_file() {
+        let dfa_file = std::env::temp_dir().join("cli_test_even_a.aut");
+        std::fs::write(
+            &dfa_file,
+            "dfa start=p0 accept=p0\np0 -a-> p1; p1 -a-> p0; p0 -b-> p0; p1 -b-> p1\n",
+        )
+        .unwrap();
+
+        let outcome = command(&["intersect", "--to", "dsl", "-", dfa_file.to_str().unwrap()])
+            .unwrap()
+            .execute("pda start=q0 accept=q1\nq0 -a,ϵ/A-> q0; q0 -b,A/ϵ-> q1; q1 -b,A/ϵ-> q1\n")
+            .unwrap();
+        assert!(outcome
+            .output
+            .starts_with("pda start=(q0,p0) accept=(q1,p0)\n"));
+        assert!(outcome.output.contains("(q0,p1) -b,A/ϵ-> (q1,p1)\n"));
+        assert!(command(&["intersect", "-"]).is_err());
+    }
+
+    #[test]
+    fn test_reduce_steps_as_latex() {
+        let outcome = command(&["reduce", "--format", "latex", "-"])
+            .unwrap()
+            .execute("dfa start=q0 accept=q1\nq0 -a-> q1; q1 -a-> q2; q2 -a-> q1\n")
+            .unwrap();
+
+        assert!(outcome
+            .output
+            .starts_with("Number of equivalence classes created: 2\n\n"));
+        assert!(outcome
+            .output
+            .contains("\\begin{tabular}{|l|l|}\n\\hline\nState & a \\\\\n"));
+        assert!(command(&["info", "--format", "latex", "-"]).is_err());
+    }
+
+    #[test]
+    fn test_simulate_draws_run_as_svg() {
+        let outcome = command(&["simulate", "--format", "svg", "-", "a"])
+            .unwrap()
+            .execute(DFA_PROGRAM)
+            .unwrap();
+
+        assert!(outcome.output.starts_with("<svg "));
+        assert!(outcome
+            .output
+            .contains("fill=\"#ffe08a\" stroke=\"#d62728\" stroke-width=\"3\""));
+        assert_eq!(outcome.exit_code, EXIT_SUCCESS);
+        assert!(command(&["simulate", "--format", "svg", "-", "a", "aa"]).is_err());
+        assert!(command(&["info", "--format", "svg", "-"]).is_err());
+    }
+}
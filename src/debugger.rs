@@ -0,0 +1,238 @@
+use std::fmt::Write;
+
+use crate::simulator::{Configuration, RunStatus, Runner, Simulator};
+
+/// Steps through a run of an automaton one move at a time, showing the configurations it is in
+///
+/// Steps already taken are kept so the run can be stepped back through and forward again
+pub struct Debugger {
+    runner: Runner,
+    input: Vec<char>,
+    /// Configurations after each step taken so far, starting with the start configuration
+    steps: Vec<Vec<Configuration>>,
+    /// Index of the step being shown
+    current: usize,
+    /// Ids of the states the run stops at when continued
+    breakpoints: Vec<String>,
+}
+
+impl Debugger {
+    /// Starts a run on a string, showing the start configuration
+    pub fn new(simulator: &Simulator, input: &str) -> Debugger {
+        let mut runner = simulator.runner(input);
+        let start = runner.next().unwrap_or_default();
+
+        Debugger {
+            runner,
+            input: input.chars().collect(),
+            steps: vec![start],
+            current: 0,
+            breakpoints: vec![],
+        }
+    }
+
+    /// Configurations the automaton can be in at the step being shown
+    pub fn current(&self) -> &[Configuration] {
+        &self.steps[self.current]
+    }
+
+    pub fn step_number(&self) -> usize {
+        self.current
+    }
+
+    /// How the run ended, or [RunStatus::Running] when the step being shown is not the last
+    pub fn status(&self) -> &RunStatus {
+        if self.current + 1 == self.steps.len() {
+            self.runner.status()
+        } else {
+            &RunStatus::Running
+        }
+    }
+
+    /// Takes the next step, returning false when the run has already ended
+    pub fn step(&mut self) -> bool {
+        if self.current + 1 == self.steps.len() {
+            match self.runner.next() {
+                Some(configurations) => self.steps.push(configurations),
+                None => return false,
+            }
+        }
+
+        self.current += 1;
+        true
+    }
+
+    /// Goes back to the previous step, returning false when already at the start
+    pub fn back(&mut self) -> bool {
+        if self.current == 0 {
+            return false;
+        }
+
+        self.current -= 1;
+        true
+    }
+
+    /// Steps until a configuration has read up to a position of the input or the run ends
+    ///
+    /// Goes back to the first step that reached the position when it has already been passed
+    pub fn run_to(&mut self, position: usize) {
+        let has_reached = |configurations: &[Configuration]| {
+            configurations
+                .iter()
+                .any(|configuration| configuration.position >= position)
+        };
+
+        if let Some(idx) = self.steps[..=self.current]
+            .iter()
+            .position(|configurations| has_reached(configurations))
+        {
+            self.current = idx;
+            return;
+        }
+        while !has_reached(self.current()) && self.step() {}
+    }
+
+    /// Steps until a configuration is in a state with a breakpoint or the run ends,
+    /// returning the id of the state stopped at
+    pub fn continue_run(&mut self) -> Option<String> {
+        while self.step() {
+            if let Some(configuration) = self
+                .current()
+                .iter()
+                .find(|configuration| self.breakpoints.contains(&configuration.state.id))
+            {
+                return Some(configuration.state.id.clone());
+            }
+        }
+
+        None
+    }
+
+    /// Adds a breakpoint on a state, returning false when it already had one
+    pub fn add_breakpoint(&mut self, state_id: &str) -> bool {
+        if self
+            .breakpoints
+            .iter()
+            .any(|breakpoint| breakpoint == state_id)
+        {
+            return false;
+        }
+
+        self.breakpoints.push(String::from(state_id));
+        true
+    }
+
+    /// Removes the breakpoint on a state, returning false when it had none
+    pub fn remove_breakpoint(&mut self, state_id: &str) -> bool {
+        let breakpoint_count = self.breakpoints.len();
+        self.breakpoints.retain(|breakpoint| breakpoint != state_id);
+        self.breakpoints.len() != breakpoint_count
+    }
+
+    pub fn breakpoints(&self) -> &Vec<String> {
+        &self.breakpoints
+    }
+
+    /// Describes the step being shown, with a line for each configuration giving its state,
+    /// the input split at what has been read and for PDAs the stack with its top first
+    ///
+    ///     step 2
+    ///       q1    ab|ba    stack: XZ
+    ///     *q3     ab|ba    stack: Z
+    ///
+    /// States with a breakpoint are marked with *
+    pub fn render(&self) -> String {
+        let is_pda = matches!(self.runner, Runner::Pda(_));
+        let mut text = format!("step {}\n", self.current);
+
+        for configuration in self.current() {
+            let read = self.input[..configuration.position]
+                .iter()
+                .collect::<String>();
+            let remaining = self.input[configuration.position..]
+                .iter()
+                .collect::<String>();
+            // Writing to a String cannot fail
+            let _ = write!(
+                text,
+                "{}{:<6}{}|{}",
+                if self.breakpoints.contains(&configuration.state.id) {
+                    "*"
+                } else {
+                    " "
+                },
+                configuration.state.alt_id,
+                read,
+                remaining
+            );
+            if is_pda {
+                let stack = configuration
+                    .stack
+                    .iter()
+                    .rev()
+                    .map(|symbol| symbol.to_string())
+                    .collect::<String>();
+                let _ = write!(
+                    text,
+                    "    stack: {}",
+                    if stack.is_empty() { "empty" } else { &stack }
+                );
+            }
+            text.push('\n');
+        }
+
+        match self.status() {
+            RunStatus::Running => {}
+            RunStatus::Accepted => text.push_str("The string is accepted\n"),
+            RunStatus::Rejected => text.push_str("The string is rejected\n"),
+            RunStatus::Failed(err) => {
+                let _ = writeln!(text, "{:?}", err);
+            }
+        }
+
+        text
+    }
+}
+
+#[cfg(test)]
+mod debugger_tests {
+    use crate::debugger::Debugger;
+    use crate::parser::Parser;
+    use crate::simulator::{RunStatus, Simulator};
+
+    #[test]
+    fn test_step_through_pda() {
+        // Strings of a's followed by as many b's
+        let simulator = Simulator::new(
+            Parser::parse_dsl(
+                "pda start=q0 accept=q2\nq0 -a,ϵ/A-> q0; q0 -ϵ,ϵ/ϵ-> q1; q1 -b,A/ϵ-> q1; q1 -ϵ,ϵ/ϵ-> q2",
+            )
+            .unwrap(),
+        );
+        let mut debugger = Debugger::new(&simulator, "aabb");
+
+        assert!(debugger.step());
+        assert!(debugger.step());
+        assert_eq!(
+            debugger.render(),
+            "step 2\n q2    |aabb    stack: empty\n q1    a|abb    stack: A\n q0    aa|bb    stack: AA\n"
+        );
+
+        debugger.add_breakpoint("q1");
+        debugger.back();
+        assert_eq!(debugger.continue_run(), Some(String::from("q1")));
+        assert_eq!(debugger.step_number(), 2);
+
+        debugger.run_to(3);
+        assert!(debugger
+            .current()
+            .iter()
+            .any(|configuration| configuration.position == 3));
+        debugger.run_to(1);
+        assert_eq!(debugger.step_number(), 1);
+
+        while debugger.step() {}
+        assert!(matches!(debugger.status(), RunStatus::Accepted));
+        assert!(debugger.render().ends_with("The string is accepted\n"));
+    }
+}
@@ -1,13 +1,19 @@
+use std::collections::HashMap;
 use std::fmt::{Display, Formatter};
+use std::rc::Rc;
 
-use crate::automaton_graph::Automaton;
+use crate::automaton_graph::{ArenaGraph, Automaton};
 use crate::dfa::dfa_reduction::{Steps, Table};
 
 mod dfa_reduction;
 mod dfa_simulator;
 
+pub use dfa_simulator::DfaRunner;
+
 pub struct DFA {
     automaton_graph: Automaton,
+    /// The automaton stored by index, which runs step through
+    graph: Rc<ArenaGraph>,
 }
 
 impl DFA {
@@ -18,9 +24,42 @@ impl DFA {
         }
 
         DFA {
+            graph: Rc::new(ArenaGraph::new(&automaton)),
             automaton_graph: automaton,
         }
     }
+
+    /// Returns the automaton the DFA runs on
+    pub fn automaton_graph(&self) -> &Automaton {
+        &self.automaton_graph
+    }
+}
+
+/// How the states of a reduced DFA are displayed
+#[derive(Debug, Copy, Clone, Default, PartialEq)]
+pub enum ClassNaming {
+    /// The name of the equivalence class, Ex: "C3"
+    ClassName,
+    /// The display ids of the states merged into the class, Ex: "{q1,q4}"
+    #[default]
+    MergedDisplayIds,
+    /// The display id of the first state merged into the class, Ex: "q1"
+    FirstMember,
+}
+
+impl ClassNaming {
+    /// Names the ways of naming states are given on the command line
+    pub const NAMES: [&'static str; 3] = ["class", "merged", "first"];
+
+    /// Finds the way of naming states with a name given on the command line
+    pub fn from_name(name: &str) -> Option<ClassNaming> {
+        match name {
+            "class" => Some(ClassNaming::ClassName),
+            "merged" => Some(ClassNaming::MergedDisplayIds),
+            "first" => Some(ClassNaming::FirstMember),
+            _ => None,
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -29,11 +68,19 @@ pub struct ReductionSteps {
     pub steps: Steps,
     pub table: Table,
     pub reduced_dfa: Automaton,
+    /// Maps the id of each state of the original DFA to the id of the state it was merged into
+    pub state_classes: HashMap<String, String>,
 }
 
 impl ReductionSteps {
-    fn num_of_classes_created(&self) -> u32 {
-        self.num_of_classes_created
+    /// Writes the steps of the reduction followed by the table of classes of each iteration as LaTeX
+    pub fn to_latex(&self) -> String {
+        format!(
+            "Number of equivalence classes created: {}\n\n{}{}\n",
+            self.num_of_classes_created,
+            self.steps.to_latex(),
+            self.table.to_latex()
+        )
     }
 }
 
@@ -46,7 +93,7 @@ impl Display for ReductionSteps {
         )?;
         writeln!(f, "{}", self.steps)?;
         writeln!(f, "{}", self.table)?;
-        writeln!(f, "{:#?}", self.reduced_dfa)?;
+        writeln!(f, "{}", self.reduced_dfa.to_site_json())?;
         Ok(())
     }
 }
@@ -3,11 +3,11 @@ use std::collections::{HashMap, HashSet};
 use std::rc::Rc;
 
 use crate::automaton_graph::{
-    Automaton, AutomatonType, Position, State, Symbol, Tests, Transition,
+    Automaton, AutomatonType, Layout, Position, State, Symbol, Tests, Transition,
 };
 use crate::dfa::dfa_reduction::dfa_step_renderer::DFAReductionStepsRenderer;
 use crate::dfa::dfa_reduction::equivalence_class::EquivalenceClass;
-use crate::dfa::{ReductionSteps, DFA};
+use crate::dfa::{ClassNaming, ReductionSteps, DFA};
 
 pub use dfa_step_renderer::steps::Steps;
 pub use dfa_step_renderer::table::Table;
@@ -29,7 +29,20 @@ impl DFA {
     ///----         Then create new equivalence classes which are the subdivisions of the current equivalence class
     ///
     /// Pick another state
+    ///
+    /// States of the reduced DFA show the display ids of the states merged into them
     pub fn reduce(&self) -> Option<ReductionSteps> {
+        self.reduce_with_naming(ClassNaming::default())
+    }
+
+    /// Reduces the DFA as [DFA::reduce] does, choosing how the states of the reduced DFA are displayed
+    ///
+    /// # Arguments
+    ///
+    /// * `naming`: What to show as the display id of each reduced state
+    pub fn reduce_with_naming(&self, naming: ClassNaming) -> Option<ReductionSteps> {
+        EquivalenceClass::reset_class_counter();
+
         // Map of sate name to transition names
         let state_map: HashMap<String, Vec<String>> =
             Automaton::get_state_map(&self.automaton_graph);
@@ -121,6 +134,16 @@ impl DFA {
         }
         step_renderer.finish(&equiv_class_list);
 
+        let state_classes = equiv_class_list
+            .iter()
+            .flat_map(|equiv_class| {
+                equiv_class
+                    .state_ids()
+                    .iter()
+                    .map(|state_id| (state_id.clone(), equiv_class.name()))
+            })
+            .collect::<HashMap<String, String>>();
+
         Some(ReductionSteps {
             num_of_classes_created: equiv_class_list.len() as u32,
             table: step_renderer.move_table_steps(),
@@ -129,8 +152,11 @@ impl DFA {
                 equiv_class_list,
                 step_renderer.transitions_alphabets(),
                 self.automaton_graph.all_states(),
+                &self.automaton_graph.get_start_state().id,
                 &state_map,
+                naming,
             ),
+            state_classes,
         })
     }
 
@@ -220,36 +246,68 @@ impl DFA {
     fn class_to_automaton(
         equivalence_classes: Vec<EquivalenceClass>,
         transitions_alphabets: &Vec<Symbol>,
-        all_states: &Vec<Rc<State>>,
+        all_states: &[Rc<State>],
+        start_state_id: &str,
         state_map: &HashMap<String, Vec<String>>,
+        naming: ClassNaming,
     ) -> Automaton {
+        // States that were never placed are all at the same position, so they are laid out instead
+        let has_positions = all_states.windows(2).any(|states| {
+            states[0].position.x != states[1].position.x
+                || states[0].position.y != states[1].position.y
+        });
+
         // Create list of states without transitions
         let new_states = equivalence_classes
             .iter()
             .map(|eq| {
+                // Members in the order they appear in the original automaton
+                let members = all_states
+                    .iter()
+                    .filter(|state| eq.state_ids().contains(&state.id))
+                    .collect::<Vec<&Rc<State>>>();
+
+                let alt_id = match (naming, &members[..]) {
+                    (ClassNaming::ClassName, _) => None,
+                    (ClassNaming::MergedDisplayIds, [member]) => Some(member.alt_id.clone()),
+                    (ClassNaming::MergedDisplayIds, _) => Some(format!(
+                        "{{{}}}",
+                        members
+                            .iter()
+                            .map(|member| member.alt_id.as_str())
+                            .collect::<Vec<&str>>()
+                            .join(",")
+                    )),
+                    (ClassNaming::FirstMember, _) => {
+                        members.first().map(|member| member.alt_id.clone())
+                    }
+                };
+
+                // The centroid of the merged states
+                let position = Position {
+                    x: members.iter().map(|member| member.position.x).sum::<f64>()
+                        / members.len() as f64,
+                    y: members.iter().map(|member| member.position.y).sum::<f64>()
+                        / members.len() as f64,
+                };
+
                 Rc::new(State::new(
                     eq.name(),
-                    None,
-                    Position::default(),
+                    alt_id,
+                    position,
                     // Check if this class contains a final state
-                    eq.state_ids()
-                        .iter()
-                        .find(|ids| {
-                            State::find_state_by_id(all_states, ids)
-                                .expect("Could not find id in the list of states")
-                                .is_accept_state
-                        })
-                        .map_or(false, |_| true),
+                    members.iter().any(|member| member.is_accept_state),
                     RefCell::new(vec![]),
                 ))
             })
             .collect::<Vec<Rc<State>>>();
 
         let err_message = "equivalent_class id not found even after creating new states from equivalent_class list";
+        let new_states_by_id = State::map_by_id(&new_states);
 
         // Add transitions for each state
         for eq in &equivalence_classes {
-            let curr_state = State::find_state_by_id(&new_states, &eq.name()).expect(err_message);
+            let curr_state = new_states_by_id.get(&eq.name()).expect(err_message);
 
             // Find any state in the equivalence list
             let state = eq
@@ -266,7 +324,10 @@ impl DFA {
                 .map(|c| {
                     let class_name =
                         EquivalenceClass::find_equiv_class_name(c, &equivalence_classes);
-                    State::find_state_by_id(&new_states, &class_name).expect(err_message)
+                    new_states_by_id
+                        .get(&class_name)
+                        .expect(err_message)
+                        .clone()
                 })
                 .zip(transitions_alphabets)
                 .for_each(|(state, symbol): (Rc<State>, &Symbol)| {
@@ -274,11 +335,13 @@ impl DFA {
                 })
         }
 
-        let s_state = State::find_state_by_id(
-            &new_states,
-            &EquivalenceClass::find_equiv_class_name("start", &equivalence_classes),
-        )
-        .expect("Cannot find start state when recreating the automaton");
+        let s_state = new_states_by_id
+            .get(&EquivalenceClass::find_equiv_class_name(
+                start_state_id,
+                &equivalence_classes,
+            ))
+            .expect("Cannot find start state when recreating the automaton")
+            .clone();
 
         let accepting_states = new_states
             .iter()
@@ -291,12 +354,65 @@ impl DFA {
             })
             .collect::<Vec<Rc<State>>>();
 
-        Automaton::new(
+        let reduced_dfa = Automaton::new(
             AutomatonType::DFA,
             s_state,
             accepting_states,
             new_states,
             Tests::default(),
-        )
+        );
+
+        if has_positions {
+            reduced_dfa
+        } else {
+            reduced_dfa.with_layout(Layout::Layered)
+        }
+    }
+}
+
+#[cfg(test)]
+mod reduction_tests {
+    use crate::dfa::{ClassNaming, DFA};
+    use crate::parser::Parser;
+
+    /// q1 and q2 both go to q3 on every symbol so they are merged
+    const DFA_PROGRAM: &str = "dfa start=start accept=q3
+start @ 0,100; q1 @ 100,0; q2 @ 100,200; q3 @ 200,100
+start -a-> q1; start -b-> q2
+q1 -a,b-> q3; q2 -a,b-> q3; q3 -a,b-> q3";
+
+    #[test]
+    fn test_reduced_states_keep_display_ids_and_positions() {
+        let dfa = DFA::new(Parser::parse_dsl(DFA_PROGRAM).unwrap());
+        let steps = dfa.reduce().unwrap();
+
+        let merged_class = &steps.state_classes["q1"];
+        assert_eq!(&steps.state_classes["q2"], merged_class);
+        assert_ne!(&steps.state_classes["q3"], merged_class);
+
+        let merged_state = steps
+            .reduced_dfa
+            .all_states()
+            .iter()
+            .find(|state| state.id == *merged_class)
+            .unwrap()
+            .clone();
+        assert_eq!(merged_state.alt_id, "{q1,q2}");
+        assert_eq!(
+            (merged_state.position.x, merged_state.position.y),
+            (100.0, 100.0)
+        );
+        assert_eq!(steps.reduced_dfa.get_start_state().alt_id, "start");
+
+        let steps = dfa.reduce_with_naming(ClassNaming::ClassName).unwrap();
+        for state in steps.reduced_dfa.all_states() {
+            assert_eq!(state.alt_id, state.id);
+        }
+        let steps = dfa.reduce_with_naming(ClassNaming::FirstMember).unwrap();
+        assert!(steps
+            .reduced_dfa
+            .all_states()
+            .iter()
+            .any(|state| state.alt_id == "q1"));
     }
 }
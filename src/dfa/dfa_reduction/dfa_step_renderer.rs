@@ -3,6 +3,8 @@ use std::fmt::{Display, Formatter};
 use std::rc::Rc;
 
 use crate::automaton_graph::{State, Symbol};
+use crate::dfa::dfa_reduction::dfa_step_renderer::class_table::ClassTable;
+use crate::dfa::dfa_reduction::dfa_step_renderer::steps::Step;
 use crate::dfa::dfa_reduction::dfa_step_renderer::table::Table;
 use crate::dfa::dfa_reduction::{EquivalenceClass, Steps};
 use crate::dfa::DFA;
@@ -43,20 +45,26 @@ impl<'a> DFAReductionStepsRenderer<'a> {
             .find(|equivalence_class| *equivalence_class.prefix_name() == first_class)
             .expect(err_message);
 
-        let split_table =
-            Self::render_table_to_string(None, final_and_non_final_classes, state_transition_map);
-
         let transition_alphabets = state
             .get_transitions()
             .iter()
             .map(|transition| transition.transition_on())
             .collect::<Vec<Symbol>>();
 
+        let split_table = Self::render_table(
+            None,
+            final_and_non_final_classes,
+            state_transition_map,
+            &transition_alphabets,
+        );
+
         DFAReductionStepsRenderer {
             table_steps: Table::new(vec![split_table.clone()], &transition_alphabets),
             steps: Steps::new(vec![
-                String::from("Divide the table into final, 'C0' and non-final 'C1' states"),
-                split_table,
+                Step::Text(String::from(
+                    "Divide the table into final, 'C0' and non-final 'C1' states",
+                )),
+                Step::Table(split_table),
             ]),
             state_transition_map,
             transitions_alphabets: transition_alphabets,
@@ -108,37 +116,39 @@ impl<'a> DFAReductionStepsRenderer<'a> {
                     }
                     acc
                 });
-        self.steps.push(format!(
+        self.steps.push(Step::Text(format!(
             "Since all states in {} dont fall into the same equivalence class it is then split into [{}]",
             self.class_name_dividing,
             new_class_names
-        ));
+        )));
         // Show the new tables that are created
-        self.steps.push(String::from("These include:"));
-        self.steps.push(Self::render_table_to_string(
+        self.steps.push(Step::Text(String::from("These include:")));
+        self.steps.push(Step::Table(Self::render_table(
             Some(new_split_classes),
             complete_equiv_class_list,
             self.state_transition_map,
-        ));
+            &self.transitions_alphabets,
+        )));
 
         // Add a new iteration to the tale
-        self.table_steps.push(Self::render_table_to_string(
+        self.table_steps.push(Self::render_table(
             None,
             complete_equiv_class_list,
             self.state_transition_map,
+            &self.transitions_alphabets,
         ))
     }
 
     /// Adds a comment on the progress after checking if a state could be reduced
     pub fn split_conclusion(&mut self) {
-        self.steps.push(if !self.reduction_was_successful {
+        self.steps.push(Step::Text(if !self.reduction_was_successful {
             format!(
                 "Could not split {} as all state transitions fall into the same equivalent classes", self.class_name_dividing
             )
         } else {
             format!("Since splitting {} was successful we have to re-evaluate all classes again to see if any have changed", self.class_name_dividing)
-        });
-        self.steps.push(String::new());
+        }));
+        self.steps.push(Step::Text(String::new()));
     }
 
     /// Adds the name of the class that is being checked for subdivision
@@ -147,12 +157,13 @@ impl<'a> DFAReductionStepsRenderer<'a> {
     ///
     /// * `class_dividing`: The class to be checked for subdivision
     pub fn add_name_of_sub_dividing_class(&mut self, class_dividing: &EquivalenceClass) {
-        self.steps.push(format!("Step {}:", self.step_count));
+        self.steps
+            .push(Step::Text(format!("Step {}:", self.step_count)));
         self.step_count += 1;
-        self.steps.push(format!(
+        self.steps.push(Step::Text(format!(
             "We now pick and try to subdivide the equivalence classes {}",
             class_dividing.prefix_name()
-        ));
+        )));
 
         // Reset the reduction checking
         self.reduction_was_successful = false;
@@ -161,15 +172,10 @@ impl<'a> DFAReductionStepsRenderer<'a> {
 
     /// Adds closing remarks to the steps
     pub fn finish(&mut self, eq_list: &Vec<EquivalenceClass>) {
-        self.steps.push(format!(
+        self.steps.push(Step::Text(format!(
             "All states are now reduced into their equivalent classes giving us a total of {} equivalent classes",
             eq_list.len()
-        ))
-    }
-
-    /// Retrieves the last table generated
-    pub fn last_generated_table(&self) -> Option<&String> {
-        self.table_steps.final_table()
+        )))
     }
 
     /// Tracks states in a class currently being subdivided
@@ -179,12 +185,10 @@ impl<'a> DFAReductionStepsRenderer<'a> {
     /// * `state_id`: A state in a subdividing class
     /// * `class_transition_names`: Names of the classes corresponding to the states in its transitions
     pub fn track_sub_divisions(&mut self, state_id: &str, class_transition_names: &str) {
-        self.steps.push(format!(
-            "{: <padding$}|   {}",
-            state_id,
-            class_transition_names,
-            padding = PADDING_SIZE
-        ));
+        self.steps.push(Step::Division {
+            state_id: String::from(state_id),
+            class_names: String::from(class_transition_names),
+        });
     }
 
     /// Generates a table from a partial_equivalence class if provided else it defaults to
@@ -207,23 +211,25 @@ impl<'a> DFAReductionStepsRenderer<'a> {
     /// * `partial_equivalence_classes`: A slice of the equivalence class you want to generate a table for
     /// * `full_equivalence_classes`: A list of all equivalence classes
     /// * `state_transition_map`: A map of all states and a list of states they transition to
-    fn render_table_to_string(
+    /// * `transitions_alphabets`: The symbols the classes in each row are transitioned to on
+    fn render_table(
         partial_equivalence_classes: Option<&[EquivalenceClass]>,
         full_equivalence_classes: &Vec<EquivalenceClass>,
         state_transition_map: &HashMap<String, Vec<String>>,
-    ) -> String {
-        let mut table = String::new();
+        transitions_alphabets: &[Symbol],
+    ) -> ClassTable {
+        let mut table = ClassTable::new(transitions_alphabets);
         // Chose the class list to use
         let class_list_iterating = partial_equivalence_classes.unwrap_or(full_equivalence_classes);
         // Generate the table
         for equivalence_class in class_list_iterating {
-            Self::render_class_name(&mut table, equivalence_class);
-
-            Self::render_state_names_and_transition_classes(
-                full_equivalence_classes,
-                state_transition_map,
-                &mut table,
-                equivalence_class,
+            table.push_class(
+                equivalence_class.prefix_name().clone(),
+                Self::find_transition_classes(
+                    full_equivalence_classes,
+                    state_transition_map,
+                    equivalence_class,
+                ),
             );
         }
 
@@ -231,69 +237,34 @@ impl<'a> DFAReductionStepsRenderer<'a> {
         table
     }
 
-    /// Renders a class name in this format
-    ///
-    /// |--------------------
-    ///
-    /// |        ClassName
-    ///
-    /// |--------------------
-    ///
-    /// # Arguments
-    ///
-    /// * `renderer_buffer`: A string reference to render into
-    /// * `equivalence_class`: The class for whose name you would want to render
-    fn render_class_name(renderer_buffer: &mut String, equivalence_class: &EquivalenceClass) {
-        let line_separator = "-".repeat(PADDING_SIZE * 2);
-        renderer_buffer.push_str(&format!("|{}", line_separator));
-        renderer_buffer.push('\n');
-        renderer_buffer.push_str(&format!(
-            "|{name: >padding$}",
-            name = equivalence_class.prefix_name(),
-            padding = PADDING_SIZE
-        ));
-        renderer_buffer.push('\n');
-        renderer_buffer.push_str(&format!("|{}", line_separator));
-        renderer_buffer.push('\n');
-    }
-
-    /// Renders a list of transition state names in a class to equivalent class name of each transition
-    ///
-    /// |start| C0 C0
+    /// Finds the equivalent class name of each transition for every state in a class
     ///
-    ///  |s3  | C0 C0
-    ///
-    ///  |s1  | C1 C1
-    ///
-    ///  |s0  | C1 C1
+    /// Ex: [("start", "C0 C0 "), ("s3", "C0 C0 ")]
     ///
     /// # Arguments
     ///
     /// * `equivalence_classes`: This `must` contain a list of all equivalence classes
     /// * `state_transition_map`: A map of all states and a list of states they transition to
-    /// * `renderer_buffer`: A string you would render the transitions into
-    /// * `equivalence_class`: The equivalence class for which you would render transitions
-    fn render_state_names_and_transition_classes(
+    /// * `equivalence_class`: The equivalence class for which you would find transitions
+    fn find_transition_classes(
         equivalence_classes: &Vec<EquivalenceClass>,
         state_transition_map: &HashMap<String, Vec<String>>,
-        renderer_buffer: &mut String,
         equivalence_class: &EquivalenceClass,
-    ) {
-        for class_state_id in equivalence_class.state_ids() {
-            let connecting_points = DFA::find_class_for_transitions(
-                state_transition_map,
-                equivalence_classes,
-                class_state_id,
-            );
-
-            renderer_buffer.push_str(&format!(
-                "|{: <padding$}| {}",
-                class_state_id,
-                connecting_points,
-                padding = PADDING_SIZE
-            ));
-            renderer_buffer.push('\n');
-        }
+    ) -> Vec<(String, String)> {
+        equivalence_class
+            .state_ids()
+            .iter()
+            .map(|class_state_id| {
+                (
+                    class_state_id.clone(),
+                    DFA::find_class_for_transitions(
+                        state_transition_map,
+                        equivalence_classes,
+                        class_state_id,
+                    ),
+                )
+            })
+            .collect()
     }
 }
 
@@ -310,8 +281,43 @@ impl<'a> Display for DFAReductionStepsRenderer<'a> {
 pub mod steps {
     use std::fmt::{Display, Formatter};
 
+    use crate::automaton_graph::escape_latex;
+    use crate::dfa::dfa_reduction::dfa_step_renderer::class_table::ClassTable;
+    use crate::dfa::dfa_reduction::dfa_step_renderer::PADDING_SIZE;
+
+    /// A single line of the solution, or a table of classes
+    #[derive(Debug)]
+    pub enum Step {
+        Text(String),
+        /// The classes a state transitions to while its class is being subdivided
+        Division {
+            state_id: String,
+            class_names: String,
+        },
+        Table(ClassTable),
+    }
+
     #[derive(Debug, Default)]
-    pub struct Steps(Vec<String>);
+    pub struct Steps(Vec<Step>);
+
+    impl Display for Step {
+        fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+            match self {
+                Step::Text(text) => write!(f, "{}", text),
+                Step::Division {
+                    state_id,
+                    class_names,
+                } => write!(
+                    f,
+                    "{: <padding$}|   {}",
+                    state_id,
+                    class_names,
+                    padding = PADDING_SIZE
+                ),
+                Step::Table(table) => write!(f, "{}", table),
+            }
+        }
+    }
 
     impl Display for Steps {
         fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
@@ -330,12 +336,53 @@ Solution Provided by the solver
     }
 
     impl Steps {
-        pub fn new(steps: Vec<String>) -> Steps {
+        pub fn new(steps: Vec<Step>) -> Steps {
             Steps(steps)
         }
-        pub fn push(&mut self, step: String) {
+        pub fn push(&mut self, step: Step) {
             self.0.push(step)
         }
+
+        /// Writes the solution as LaTeX paragraphs, with the classes of each state
+        /// and the tables of classes written as `tabular` environments
+        pub fn to_latex(&self) -> String {
+            let mut latex = String::new();
+            let mut steps = self.0.iter().peekable();
+
+            while let Some(step) = steps.next() {
+                match step {
+                    Step::Text(text) if text.is_empty() => latex.push('\n'),
+                    Step::Text(text) => {
+                        latex.push_str(&escape_latex(text));
+                        latex.push_str("\n\n");
+                    }
+                    Step::Division { .. } => {
+                        // Consecutive divisions are shown in a single table
+                        latex.push_str("\\begin{tabular}{l|l}\n");
+                        let mut division = Some(step);
+                        while let Some(Step::Division {
+                            state_id,
+                            class_names,
+                        }) = division
+                        {
+                            latex.push_str(&format!(
+                                "{} & {} \\\\\n",
+                                escape_latex(state_id),
+                                escape_latex(class_names.trim_end())
+                            ));
+                            division = steps.next_if(|step| matches!(step, Step::Division { .. }));
+                        }
+                        latex.push_str("\\end{tabular}\n\n");
+                    }
+                    Step::Table(table) => {
+                        latex.push_str(&table.to_latex());
+                        latex.push_str("\n\n");
+                    }
+                }
+            }
+
+            latex
+        }
     }
 }
 
@@ -343,11 +390,12 @@ pub mod table {
     use std::fmt::{Display, Formatter};
 
     use crate::automaton_graph::Symbol;
+    use crate::dfa::dfa_reduction::dfa_step_renderer::class_table::ClassTable;
     use crate::dfa::dfa_reduction::dfa_step_renderer::PADDING_SIZE;
 
     #[derive(Debug, Default)]
     pub struct Table {
-        table: Vec<String>,
+        table: Vec<ClassTable>,
         transitions_alphabets: String,
     }
 
@@ -372,7 +420,7 @@ pub mod table {
     }
 
     impl Table {
-        pub fn new(table: Vec<String>, transitions_alphabets: &Vec<Symbol>) -> Table {
+        pub fn new(table: Vec<ClassTable>, transitions_alphabets: &Vec<Symbol>) -> Table {
             Table {
                 table,
                 transitions_alphabets: transitions_alphabets
@@ -383,12 +431,195 @@ pub mod table {
             }
         }
 
-        pub fn push(&mut self, table: String) {
+        pub fn push(&mut self, table: ClassTable) {
             self.table.push(table)
         }
 
-        pub fn final_table(&self) -> Option<&String> {
-            self.table.last()
+        /// Writes every iteration of the reduction as a LaTeX `tabular` environment
+        pub fn to_latex(&self) -> String {
+            self.table
+                .iter()
+                .enumerate()
+                .map(|(iter_count, table)| {
+                    format!(
+                        "\\paragraph{{Iteration {}}}\n\n{}\n",
+                        iter_count + 1,
+                        table.to_latex()
+                    )
+                })
+                .collect::<Vec<String>>()
+                .join("\n")
+        }
+    }
+}
+
+pub mod class_table {
+    use std::fmt::{Display, Formatter};
+
+    use crate::automaton_graph::{escape_latex, Symbol};
+    use crate::dfa::dfa_reduction::dfa_step_renderer::PADDING_SIZE;
+
+    /// The classes each state transitions to, grouped by the class each state belongs to
+    #[derive(Debug, Default, Clone)]
+    pub struct ClassTable {
+        transitions_alphabets: Vec<Symbol>,
+        /// Name of each class along with its states and the classes they transition to
+        classes: Vec<(String, Vec<(String, String)>)>,
+    }
+
+    impl ClassTable {
+        pub fn new(transitions_alphabets: &[Symbol]) -> ClassTable {
+            ClassTable {
+                transitions_alphabets: transitions_alphabets.to_vec(),
+                classes: vec![],
+            }
+        }
+
+        /// Adds a class to the table
+        ///
+        /// # Arguments
+        ///
+        /// * `class_name`: Name of the class
+        /// * `rows`: Each state in the class and the classes of its transitions Ex: ("s3", "C0 C1 ")
+        pub fn push_class(&mut self, class_name: String, rows: Vec<(String, String)>) {
+            self.classes.push((class_name, rows))
         }
+
+        /// Writes the table as a LaTeX `tabular` environment with a column for each symbol
+        ///
+        /// \begin{tabular}{|l|ll|}
+        ///
+        /// \hline
+        ///
+        /// State & a & b \\\\
+        ///
+        /// \hline
+        ///
+        /// \multicolumn{3}{|c|}{C0} \\\\
+        ///
+        /// \hline
+        ///
+        /// s3 & C0 & C1 \\\\
+        ///
+        /// \hline
+        ///
+        /// \end{tabular}
+        pub fn to_latex(&self) -> String {
+            let columns = self.transitions_alphabets.len() + 1;
+            let mut latex = format!(
+                "\\begin{{tabular}}{{|l|{}|}}\n\\hline\nState",
+                "l".repeat(self.transitions_alphabets.len())
+            );
+            for symbol in &self.transitions_alphabets {
+                latex.push_str(&format!(" & {}", escape_latex(&symbol.to_string())));
+            }
+            latex.push_str(" \\\\\n\\hline\n");
+
+            for (class_name, rows) in &self.classes {
+                latex.push_str(&format!(
+                    "\\multicolumn{{{}}}{{|c|}}{{{}}} \\\\\n\\hline\n",
+                    columns,
+                    escape_latex(class_name)
+                ));
+                for (state_id, class_names) in rows {
+                    latex.push_str(&escape_latex(state_id));
+                    for class_name in class_names.split_whitespace() {
+                        latex.push_str(&format!(" & {}", escape_latex(class_name)));
+                    }
+                    latex.push_str(" \\\\\n");
+                }
+                latex.push_str("\\hline\n");
+            }
+
+            latex.push_str("\\end{tabular}");
+            latex
+        }
+    }
+
+    impl Display for ClassTable {
+        /// Renders each class in this format
+        ///
+        /// |--------------------
+        ///
+        /// |        ClassName
+        ///
+        /// |--------------------
+        ///
+        /// |start  | C0 C0
+        fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+            let line_separator = "-".repeat(PADDING_SIZE * 2);
+            for (class_name, rows) in &self.classes {
+                writeln!(f, "|{}", line_separator)?;
+                writeln!(
+                    f,
+                    "|{name: >padding$}",
+                    name = class_name,
+                    padding = PADDING_SIZE
+                )?;
+                writeln!(f, "|{}", line_separator)?;
+                for (state_id, class_names) in rows {
+                    writeln!(
+                        f,
+                        "|{: <padding$}| {}",
+                        state_id,
+                        class_names,
+                        padding = PADDING_SIZE
+                    )?;
+                }
+            }
+
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod latex_tests {
+    use crate::automaton_graph::Symbol;
+    use crate::dfa::dfa_reduction::dfa_step_renderer::class_table::ClassTable;
+    use crate::dfa::dfa_reduction::dfa_step_renderer::steps::Step;
+    use crate::dfa::dfa_reduction::Steps;
+
+    #[test]
+    fn test_reduction_steps_to_latex() {
+        let mut table = ClassTable::new(&[Symbol::CHAR('a'), Symbol::CHAR('b')]);
+        table.push_class(
+            String::from("C0"),
+            vec![(String::from("s_1"), String::from("C0 C1 "))],
+        );
+        let steps = Steps::new(vec![
+            Step::Text(String::from("Step 1:")),
+            Step::Division {
+                state_id: String::from("start"),
+                class_names: String::from("C0 C1 "),
+            },
+            Step::Division {
+                state_id: String::from("s0"),
+                class_names: String::from("C1 C1 "),
+            },
+            Step::Table(table),
+        ]);
+
+        assert_eq!(
+            steps.to_latex(),
+            r"Step 1:
+
+\begin{tabular}{l|l}
+start & C0 C1 \\
+s0 & C1 C1 \\
+\end{tabular}
+
+\begin{tabular}{|l|ll|}
+\hline
+State & a & b \\
+\hline
+\multicolumn{3}{|c|}{C0} \\
+\hline
+s\_1 & C0 & C1 \\
+\hline
+\end{tabular}
+
+"
+        );
     }
 }
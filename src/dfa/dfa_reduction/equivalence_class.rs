@@ -1,10 +1,12 @@
+use std::cell::Cell;
 use std::collections::HashSet;
-use std::sync::atomic::{AtomicU32, Ordering};
 
 /// The number the counter starts from
 const START_COUNT: u8 = 0;
-/// Counter to keep track of the next available class number
-static CLASS_COUNTER: AtomicU32 = AtomicU32::new(START_COUNT as u32);
+thread_local! {
+    /// Counter to keep track of the next available class number
+    static CLASS_COUNTER: Cell<u32> = const { Cell::new(START_COUNT as u32) };
+}
 
 #[derive(Debug)]
 pub struct EquivalenceClass {
@@ -76,7 +78,16 @@ impl EquivalenceClass {
     /// Helper method to get the next available class number
     #[inline]
     fn get_next_class_number() -> u32 {
-        CLASS_COUNTER.fetch_add(1, Ordering::Relaxed)
+        CLASS_COUNTER.with(|counter| {
+            let class_number = counter.get();
+            counter.set(class_number + 1);
+            class_number
+        })
+    }
+
+    /// Starts numbering classes from the start count again, so each reduction names its classes from C0
+    pub fn reset_class_counter() {
+        CLASS_COUNTER.with(|counter| counter.set(START_COUNT as u32))
     }
 
     /// Given a state name it finds the corresponding equivalence class it corresponds to in the list provided
@@ -1,36 +1,178 @@
-use std::fmt::Debug;
+use std::collections::VecDeque;
+use std::rc::Rc;
 
-use crate::automaton_graph::{SimulationError, Symbol, Transition};
+use crate::automaton_graph::{ArenaGraph, Edge, SimulationError, StateId, Symbol};
 use crate::dfa::DFA;
+use crate::simulator::{Configuration, RunStatus, Runner};
+use crate::trace::Trace;
+
+/// Runs a DFA on input which can be fed to it a piece at a time, reading a character on each step
+pub struct DfaRunner {
+    graph: Rc<ArenaGraph>,
+    /// Characters fed to the runner which have not been read yet
+    pending: VecDeque<char>,
+    /// State the DFA is in, [None] once it had no transition for a character
+    state: Option<StateId>,
+    position: usize,
+    /// State left and edge taken to reach the state the DFA is in
+    reached_by: Option<(StateId, Edge)>,
+    is_started: bool,
+    status: RunStatus,
+}
 
 impl DFA {
     pub fn simulate(&self, simulating_string: &str) -> Result<bool, SimulationError> {
-        let mut curr_state = self.automaton_graph.get_start_state();
-
-        for (idx, c) in simulating_string.chars().enumerate() {
-            let transition = Transition::find_transition_by_symbol(curr_state.get_transitions(), c);
-
-            if transition.len() == 0 { return Ok(false); }
-            if transition.len() != 1 {
-                // DFAs cannot have more than one transition on a symbol
-                let error_message = format!(
-                    "Invariant broken for current DFA. Multiple transitions found for character {} at index {} for symbols {:?} on state {}",
-                    c,
-                    idx,
-                    curr_state
-                        .get_transitions()
-                        .iter()
-                        .map(|t| t.transition_on())
-                        .collect::<Vec<Symbol>>(),
-                    &curr_state.alt_id
-                );
-                return Err(SimulationError::MultipleTransitionsFound(error_message));
-            }
+        self.runner(simulating_string).finish()
+    }
+
+    /// Runs the DFA on a string, keeping every configuration it went through
+    pub fn trace(&self, simulating_string: &str) -> Trace {
+        Trace::record(
+            simulating_string,
+            Runner::Dfa(self.runner(simulating_string)),
+        )
+    }
+
+    /// Starts a run on a string which can be stepped through or fed more input
+    pub fn runner(&self, simulating_string: &str) -> DfaRunner {
+        let mut runner = DfaRunner {
+            graph: self.graph.clone(),
+            pending: VecDeque::new(),
+            state: None,
+            position: 0,
+            reached_by: None,
+            is_started: false,
+            status: RunStatus::Running,
+        };
+        runner.reset();
+        runner.pending.extend(simulating_string.chars());
+        runner.update_status();
+        runner
+    }
+}
+
+impl DfaRunner {
+    pub fn status(&self) -> &RunStatus {
+        &self.status
+    }
+
+    /// Reads more input, carrying on from the state the DFA was left in
+    pub fn feed(&mut self, input: &str) {
+        for c in input.chars() {
+            self.feed_char(c);
+        }
+    }
+
+    pub fn feed_char(&mut self, c: char) {
+        self.pending.push_back(c);
+        self.update_status();
+        while self.step() {}
+    }
+
+    /// Whether the input fed so far is accepted, reading any of it not yet read
+    pub fn is_accepting(&mut self) -> bool {
+        while self.step() {}
+        matches!(self.status, RunStatus::Accepted)
+    }
+
+    /// Goes back to the start state, forgetting all of the input fed so far
+    pub fn reset(&mut self) {
+        self.pending.clear();
+        self.state = Some(self.graph.start_state());
+        self.position = 0;
+        self.reached_by = None;
+        self.is_started = false;
+        self.status = RunStatus::Running;
+        self.update_status();
+    }
+
+    /// Reads the rest of the input, returning whether it was accepted
+    pub fn finish(mut self) -> Result<bool, SimulationError> {
+        while self.step() {}
+        self.status.into_result()
+    }
+
+    /// Reads the next character, returning false when there is none or the run cannot go on
+    fn step(&mut self) -> bool {
+        if matches!(self.status, RunStatus::Failed(_)) {
+            return false;
+        }
+        let Some(state) = self.state else {
+            // Once rejected the rest of the input can never be accepted
+            self.pending.clear();
+            return false;
+        };
+        let Some(&c) = self.pending.front() else {
+            return false;
+        };
+
+        let edges = match self.graph.symbol_id(Symbol::CHAR(c)) {
+            Some(symbol_id) => self.graph.edges_on(state, symbol_id),
+            None => &[],
+        };
+        if edges.len() > 1 {
+            // DFAs cannot have more than one transition on a symbol
+            let curr_state = self.graph.state(state);
+            let error_message = format!(
+                "Invariant broken for current DFA. Multiple transitions found for character {} at index {} for symbols {:?} on state {}",
+                c,
+                self.position,
+                curr_state
+                    .get_transitions()
+                    .iter()
+                    .map(|t| t.transition_on())
+                    .collect::<Vec<Symbol>>(),
+                &curr_state.alt_id
+            );
+            self.status =
+                RunStatus::Failed(SimulationError::MultipleTransitionsFound(error_message));
+            return false;
+        }
 
-            curr_state = transition.first().unwrap().to();
+        self.pending.pop_front();
+        self.position += 1;
+        self.state = edges.first().map(|edge| edge.to);
+        self.reached_by = edges.first().map(|edge| (state, *edge));
+        self.update_status();
+        self.state.is_some()
+    }
+
+    /// Accepted or rejected once all of the input fed so far has been read
+    fn update_status(&mut self) {
+        if matches!(self.status, RunStatus::Failed(_)) {
+            return;
         }
 
-        Ok(curr_state.is_accept_state)
+        self.status = match self.state {
+            None => RunStatus::Rejected,
+            Some(_) if !self.pending.is_empty() => RunStatus::Running,
+            Some(state) if self.graph.is_accept_state(state) => RunStatus::Accepted,
+            Some(_) => RunStatus::Rejected,
+        };
+    }
+}
+
+impl Iterator for DfaRunner {
+    type Item = Vec<Configuration>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.is_started && !self.step() {
+            return None;
+        }
+        self.is_started = true;
+
+        let state = self.state?;
+        Some(vec![Configuration {
+            state: self.graph.state(state).clone(),
+            position: self.position,
+            stack: vec![],
+            reached_by: self.reached_by.map(|(from, edge)| {
+                (
+                    self.graph.state(from).clone(),
+                    self.graph.transition(&edge).clone(),
+                )
+            }),
+        }])
     }
 }
 
@@ -50,10 +192,14 @@ mod simulation_tests {
     #[test]
     fn test_simulation_should_accept() {
         for test_string in TEST_STRINGS {
-            let automaton = Parser::parse(test_string);
+            let automaton = Parser::parse(test_string).unwrap().0;
             let dfa = DFA::new(automaton);
             for accepting_str in &dfa.automaton_graph.tests.accepting_strings {
-                assert_eq!(dfa.simulate(accepting_str).expect(&format!("Simulation failed for string {}", accepting_str)), true);
+                assert_eq!(
+                    dfa.simulate(accepting_str)
+                        .expect(&format!("Simulation failed for string {}", accepting_str)),
+                    true
+                );
             }
         }
     }
@@ -61,7 +207,7 @@ mod simulation_tests {
     #[test]
     fn test_simulation_should_reject() {
         for test_string in TEST_STRINGS {
-            let automaton = Parser::parse(test_string);
+            let automaton = Parser::parse(test_string).unwrap().0;
             let dfa = DFA::new(automaton);
             for rejecting_str in &dfa.automaton_graph.tests.rejecting_strings {
                 assert_eq!(dfa.simulate(rejecting_str).expect("Simulation Expected to fail gracefully without errors for rejecting strings"), false);
@@ -47,6 +47,8 @@ pub enum MenuOptions {
 
     // PDA
     SimulatePDA,
+    CheckPDADeterminism,
+    CheckPDALanguage,
     GenerateCorrespondingGrammar,
 
     // NFA
@@ -64,8 +66,10 @@ pub mod pda_menu {
     use crate::menus::{read_simulating_string, MenuOptions};
     use crate::pda::PDA;
 
-    const MENU_OPTIONS: [MenuOptions; 2] = [
+    const MENU_OPTIONS: [MenuOptions; 4] = [
         MenuOptions::SimulatePDA,
+        MenuOptions::CheckPDADeterminism,
+        MenuOptions::CheckPDALanguage,
         MenuOptions::GenerateCorrespondingGrammar,
     ];
 
@@ -88,6 +92,30 @@ pub mod pda_menu {
                     }
                 )
             }
+            MenuOptions::CheckPDADeterminism => {
+                if pda.is_deterministic() {
+                    println!("The PDA is deterministic");
+                } else {
+                    println!("The PDA is not deterministic:");
+                    pda.determinism_conflicts()
+                        .iter()
+                        .for_each(|conflict| println!("\t{}", conflict));
+                }
+            }
+            MenuOptions::CheckPDALanguage => {
+                match pda.shortest_accepted_string() {
+                    Some(witness) => println!("The PDA accepts strings such as \"{}\"", witness),
+                    None => println!("The PDA does not accept any string"),
+                }
+                println!(
+                    "The language of the PDA is {}",
+                    if pda.is_language_finite() {
+                        "finite"
+                    } else {
+                        "infinite"
+                    }
+                )
+            }
             _ => panic!("{:?} not available for PDAs", menu_option),
         }
     }
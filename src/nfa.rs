@@ -0,0 +1,20 @@
+use std::rc::Rc;
+
+use crate::automaton_graph::{ArenaGraph, Automaton};
+
+mod nfa_simulator;
+
+pub use nfa_simulator::NfaRunner;
+
+pub struct NFA {
+    /// The automaton stored by index, which runs step through
+    graph: Rc<ArenaGraph>,
+}
+
+impl NFA {
+    pub fn new(automaton: Automaton) -> Self {
+        NFA {
+            graph: Rc::new(ArenaGraph::new(&automaton)),
+        }
+    }
+}
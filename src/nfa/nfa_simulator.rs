@@ -0,0 +1,236 @@
+use std::collections::VecDeque;
+use std::mem;
+use std::rc::Rc;
+
+use crate::automaton_graph::{ArenaGraph, SimulationError, StateId, Symbol, SymbolId};
+use crate::nfa::NFA;
+use crate::simulator::{Configuration, RunStatus, Runner};
+use crate::trace::Trace;
+
+/// Runs an NFA on input which can be fed to it a piece at a time, reading a character on each step
+/// and following every transition that can be taken at once
+pub struct NfaRunner {
+    graph: Rc<ArenaGraph>,
+    /// Characters fed to the runner which have not been read yet
+    pending: VecDeque<char>,
+    /// States the NFA can be in, including those reached on ϵ transitions
+    curr_states: StateSet,
+    /// Filled with the states of the next step, kept between steps so stepping does not allocate
+    next_states: StateSet,
+    position: usize,
+    is_started: bool,
+    status: RunStatus,
+}
+
+/// States without repeats, which can be emptied and filled again without allocating
+struct StateSet {
+    states: Vec<StateId>,
+    /// Generation each state was last inserted in, so whether a state is in the set is found
+    /// without searching it
+    inserted_in: Vec<usize>,
+    generation: usize,
+}
+
+impl NFA {
+    /// Runs the NFA on a string, keeping every configuration it went through
+    pub fn trace(&self, simulating_string: &str) -> Trace {
+        Trace::record(
+            simulating_string,
+            Runner::Nfa(self.runner(simulating_string)),
+        )
+    }
+
+    /// Starts a run on a string which can be stepped through or fed more input, following every
+    /// transition that can be taken at once
+    ///
+    /// The string is accepted when any of the states reached after reading it is an accept state
+    pub fn runner(&self, simulating_string: &str) -> NfaRunner {
+        let state_count = self.graph.state_count();
+        let mut runner = NfaRunner {
+            graph: self.graph.clone(),
+            pending: VecDeque::new(),
+            curr_states: StateSet::new(state_count),
+            next_states: StateSet::new(state_count),
+            position: 0,
+            is_started: false,
+            status: RunStatus::Running,
+        };
+        runner.reset();
+        runner.pending.extend(simulating_string.chars());
+        runner.update_status();
+        runner
+    }
+}
+
+impl NfaRunner {
+    pub fn status(&self) -> &RunStatus {
+        &self.status
+    }
+
+    /// Reads more input, carrying on from the states the NFA was left in
+    pub fn feed(&mut self, input: &str) {
+        for c in input.chars() {
+            self.feed_char(c);
+        }
+    }
+
+    pub fn feed_char(&mut self, c: char) {
+        self.pending.push_back(c);
+        self.update_status();
+        while self.step() {}
+    }
+
+    /// Whether the input fed so far is accepted, reading any of it not yet read
+    pub fn is_accepting(&mut self) -> bool {
+        while self.step() {}
+        matches!(self.status, RunStatus::Accepted)
+    }
+
+    /// Goes back to the start state, forgetting all of the input fed so far
+    pub fn reset(&mut self) {
+        self.pending.clear();
+        self.curr_states.clear();
+        self.curr_states.insert(self.graph.start_state());
+        self.curr_states.close_over_epsilon(&self.graph);
+        self.position = 0;
+        self.is_started = false;
+        self.status = RunStatus::Running;
+        self.update_status();
+    }
+
+    /// Reads the rest of the input, returning whether it was accepted
+    pub fn finish(mut self) -> Result<bool, SimulationError> {
+        while self.step() {}
+        self.status.into_result()
+    }
+
+    /// Reads the next character, returning false when there is none or no state is left
+    fn step(&mut self) -> bool {
+        if self.curr_states.states.is_empty() {
+            // Once rejected the rest of the input can never be accepted
+            self.pending.clear();
+            return false;
+        }
+        let Some(c) = self.pending.pop_front() else {
+            return false;
+        };
+
+        self.next_states.clear();
+        if let Some(symbol_id) = self.graph.symbol_id(Symbol::CHAR(c)) {
+            for state in &self.curr_states.states {
+                for edge in self.graph.edges_on(*state, symbol_id) {
+                    self.next_states.insert(edge.to);
+                }
+            }
+        }
+        self.next_states.close_over_epsilon(&self.graph);
+        mem::swap(&mut self.curr_states, &mut self.next_states);
+        self.position += 1;
+        self.update_status();
+
+        !self.curr_states.states.is_empty()
+    }
+
+    /// Accepted or rejected once all of the input fed so far has been read
+    fn update_status(&mut self) {
+        self.status = if self.curr_states.states.is_empty() {
+            RunStatus::Rejected
+        } else if !self.pending.is_empty() {
+            RunStatus::Running
+        } else if self
+            .curr_states
+            .states
+            .iter()
+            .any(|state| self.graph.is_accept_state(*state))
+        {
+            RunStatus::Accepted
+        } else {
+            RunStatus::Rejected
+        };
+    }
+}
+
+impl StateSet {
+    fn new(state_count: usize) -> StateSet {
+        StateSet {
+            states: vec![],
+            inserted_in: vec![0; state_count],
+            generation: 1,
+        }
+    }
+
+    fn clear(&mut self) {
+        self.states.clear();
+        self.generation += 1;
+    }
+
+    fn insert(&mut self, state: StateId) {
+        let inserted_in = &mut self.inserted_in[state.0 as usize];
+        if *inserted_in != self.generation {
+            *inserted_in = self.generation;
+            self.states.push(state);
+        }
+    }
+
+    /// Adds every state that can be reached from the states on ϵ transitions alone
+    fn close_over_epsilon(&mut self, graph: &ArenaGraph) {
+        let mut idx = 0;
+        while idx < self.states.len() {
+            for edge in graph.edges_on(self.states[idx], SymbolId::EPSILON) {
+                self.insert(edge.to);
+            }
+            idx += 1;
+        }
+    }
+}
+
+impl Iterator for NfaRunner {
+    type Item = Vec<Configuration>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.is_started && !self.step() {
+            return None;
+        }
+        self.is_started = true;
+
+        Some(
+            self.curr_states
+                .states
+                .iter()
+                .map(|state| Configuration {
+                    state: self.graph.state(*state).clone(),
+                    position: self.position,
+                    stack: vec![],
+                    reached_by: None,
+                })
+                .collect(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod nfa_simulator_tests {
+    use crate::nfa::NFA;
+    use crate::parser::Parser;
+
+    #[test]
+    fn test_simulate_with_epsilon_transitions() {
+        // Any number of a's, where the last one can be followed by a b
+        let nfa = NFA::new(
+            Parser::parse_dsl(
+                "nfa start=q0 accept=q2\nq0 -a-> q0; q0 -a-> q1; q1 -b-> q2; q0 -ϵ-> q2",
+            )
+            .unwrap(),
+        );
+
+        for (string, accepted) in [
+            ("", true),
+            ("aaa", true),
+            ("aab", true),
+            ("b", false),
+            ("aba", false),
+        ] {
+            assert_eq!(nfa.runner(string).finish().unwrap(), accepted, "{}", string);
+        }
+    }
+}
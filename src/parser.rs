@@ -1,28 +1,99 @@
+mod dsl;
+mod jflap;
+mod lexer;
 pub mod parser;
+mod transition_table;
 mod utils;
+mod validator;
 
 pub struct Parser {
-    program_iter: String,
-    cursor: u32,
+    tokens: Vec<Token>,
+    cursor: usize,
+    /// Where the tokens being parsed end, used to report errors once all tokens are consumed
+    end_span: Span,
 }
 
-/// Represents errors that could occur in a parser
+/// Represents errors that could occur in a parser along with where in the file they were found
 #[derive(Debug)]
-pub enum ParserError {
-    OutOfInput(String),
-    NoObjName(String),
-    ObjNameSyntaxErr(String),
-    ObjNameNotFound(String),
-    ObjNameOverFlow(String),
-    ObjNameMismatch(String),
-    MissingObjSeparator(String),
-    ScopeError(String),
-    UnknownTransitionName(String),
+pub struct ParserError {
+    pub kind: ParserErrorKind,
+    pub message: String,
+    pub span: Span,
+}
+
+/// Represents the kinds of errors that could occur in a parser
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum ParserErrorKind {
+    OutOfInput,
+    NoObjName,
+    ObjNameSyntaxErr,
+    ObjNameNotFound,
+    DuplicateObjName,
+    MissingObjSeparator,
+    ScopeError,
+    UnknownTransitionName,
+    UnknownState,
+    InvalidLabel,
+    InvalidToken,
+    NotDeterministic,
+}
+
+/// Describes how serious a problem found by [Parser::validate] is
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Severity {
+    /// The file cannot be loaded or does not describe the automaton it claims to
+    Error,
+    /// The file can be loaded but likely contains a mistake
+    Warning,
+}
+
+/// A problem found in an automaton file along with where in the file it was found
+#[derive(Debug)]
+pub struct ValidationIssue {
+    pub severity: Severity,
+    pub message: String,
+    pub span: Span,
+}
+
+/// Every problem found while parsing a program, along with the first error so
+/// [Parser::parse] can report it
+#[derive(Debug, Default)]
+pub(in crate::parser) struct Issues {
+    issues: Vec<ValidationIssue>,
+    first_error: Option<ParserError>,
+}
+
+/// A location in the original program, lines and columns start from 1
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Span {
+    pub line: u32,
+    pub column: u32,
+    /// Number of characters covered on the line
+    pub len: u32,
+}
+
+/// A piece of the json program along with where it is found in the file
+#[derive(Debug, Clone)]
+pub(in crate::parser) struct Token {
+    kind: TokenKind,
+    span: Span,
+}
+
+/// Describes the kinds of values that make up the json program
+#[derive(Debug, Clone, PartialEq)]
+pub(in crate::parser) enum TokenKind {
+    OpenScope(Scope),
+    CloseScope(Scope),
+    Separator(Separator),
+    String(String),
+    Number(f64),
+    Bool(bool),
+    Null,
 }
 
 /// Describes a separator used in the parser
 #[repr(u8)]
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq)]
 pub(in crate::parser) enum Separator {
     COMMA = b',',
     COLUMN = b':',
@@ -31,7 +102,7 @@ pub(in crate::parser) enum Separator {
 /// Represents an opening scope
 /// represented by "{" OR "["
 #[repr(u8)]
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq)]
 pub(in crate::parser) enum Scope {
     BoxBracket = b'[',
     CurlyBracket = b'{',
@@ -39,7 +110,7 @@ pub(in crate::parser) enum Scope {
 
 /// Represents the names that should all automatons share in the json
 /// format from the automaton simulator site
-#[derive(Debug, PartialOrd, PartialEq)]
+#[derive(Debug, Copy, Clone, PartialOrd, PartialEq, Eq, Hash)]
 pub(in crate::parser) enum SkeletonState {
     Type,
     AutomatonType,
@@ -49,3 +120,11 @@ pub(in crate::parser) enum SkeletonState {
     States,
     BulkTests,
 }
+
+/// The start and accept states given in the "dfa" / "nfa" / "pda" object
+/// along with where each state id is found in the file
+#[derive(Debug, Default)]
+pub(in crate::parser) struct AutomatonSection {
+    start_state: Option<(String, Span)>,
+    accept_states: Option<Vec<(String, Span)>>,
+}
@@ -0,0 +1,632 @@
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+use std::iter::Peekable;
+use std::rc::Rc;
+use std::str::Chars;
+
+use crate::automaton_graph::AutomatonType::{DFA, NFA, PDA};
+use crate::automaton_graph::{
+    Automaton, AutomatonType, Position, State, Symbol, Tests, Transition,
+};
+use crate::parser::ParserErrorKind::{
+    DuplicateObjName, InvalidLabel, InvalidToken, ObjNameSyntaxErr, OutOfInput, ScopeError,
+    UnknownState,
+};
+use crate::parser::{Parser, ParserError, ParserErrorKind, Span};
+
+/// Reads the text format one character at a time keeping track of where it is in the program
+struct DslReader<'a> {
+    program_iter: Peekable<Chars<'a>>,
+    line: u32,
+    column: u32,
+}
+
+/// The symbol read by a transition along with the symbols it pops and pushes
+pub(crate) type DslSymbols = (Symbol, Option<Symbol>, Option<Symbol>);
+
+/// The steps a single transition of a label is taken in, with a new state between each step
+pub(crate) type DslTransition = Vec<DslSymbols>;
+
+/// A state gathered from the program before the automaton is built
+struct DslState {
+    id: String,
+    alt_id: Option<String>,
+    position: Position,
+    is_declared: bool,
+}
+
+impl Parser {
+    /// Parses an automaton written in the compact text format
+    ///
+    ///     dfa start=q0 accept=q2
+    ///     q0 -0-> q1; q1 -1,0-> q2
+    ///     q2 "q<2>" @ 300,100
+    ///     accepts "01", "00"
+    ///     rejects "1"
+    ///
+    /// The first statement gives the type of the automaton along with its start state,
+    /// which defaults to the first state mentioned, and its accept states. Statements after it
+    /// are separated by new lines or semicolons and are one of:
+    ///
+    /// * `a -0,1-> b`: Transitions from a to b on each symbol, with `|` also separating symbols.
+    ///   PDA transitions are written as `a,X/Y` to read a, pop X and push Y, while `a,X/YZ`
+    ///   pushes Z and then Y through a new state named after a with a `'` added
+    /// * `a "display id" @ x,y`: Declares a state, optionally giving its display id and [Position]
+    /// * `accepts "..."` and `rejects "..."`: Strings the automaton is tested on
+    ///
+    /// `#` starts a comment running to the end of the line and ϵ is written as `ϵ`
+    ///
+    /// Returns a [ParserError] pointing to where in the program the first problem was found
+    pub fn parse_dsl(program: &str) -> Result<Automaton, ParserError> {
+        let mut reader = DslReader {
+            program_iter: program.chars().peekable(),
+            line: 1,
+            column: 1,
+        };
+
+        if !reader.skip_separators() {
+            return Err(reader.error(
+                OutOfInput,
+                String::from("Expected the automaton type dfa, nfa or pda"),
+            ));
+        }
+
+        let type_span = reader.span();
+        let automaton_type = match reader.read_word()?.as_str() {
+            "dfa" => DFA,
+            "nfa" => NFA,
+            "pda" => PDA,
+            other => {
+                return Err(ParserError::new(
+                    ObjNameSyntaxErr,
+                    format!(
+                        "Expected the automaton type dfa, nfa or pda but found '{}'",
+                        other
+                    ),
+                    type_span,
+                ))
+            }
+        };
+
+        let mut start_state: Option<(String, Span)> = None;
+        let mut accept_states: Vec<(String, Span)> = vec![];
+
+        while !reader.at_statement_end() {
+            let option_span = reader.span();
+            let option = reader.read_word()?;
+            reader.expect('=')?;
+
+            match option.as_str() {
+                "start" => {
+                    let state_span = reader.span();
+                    start_state = Some((reader.read_word()?, state_span));
+                }
+                "accept" => loop {
+                    let state_span = reader.span();
+                    accept_states.push((reader.read_word()?, state_span));
+                    if !reader.consume(',') {
+                        break;
+                    }
+                },
+                _ => {
+                    return Err(ParserError::new(
+                        ObjNameSyntaxErr,
+                        format!("Unknown option '{}', expected start or accept", option),
+                        option_span,
+                    ))
+                }
+            }
+        }
+
+        let mut states: Vec<DslState> = vec![];
+        // Where each state is in the list of states
+        let mut state_idxs: HashMap<String, usize> = HashMap::new();
+        let mut transitions: Vec<(String, String, DslTransition)> = vec![];
+        let mut tests = Tests::default();
+
+        while reader.skip_separators() {
+            let statement_span = reader.span();
+            let name = reader.read_word()?;
+
+            match name.as_str() {
+                "accepts" => tests.accepting_strings.extend(reader.read_string_list()?),
+                "rejects" => tests.rejecting_strings.extend(reader.read_string_list()?),
+                _ if reader.consume('-') => {
+                    let label_span = reader.span();
+                    let label = reader.read_label()?;
+                    let to = reader.read_word()?;
+
+                    Self::mention_dsl_state(&mut states, &mut state_idxs, &name);
+                    Self::mention_dsl_state(&mut states, &mut state_idxs, &to);
+                    for steps in Self::parse_dsl_label(automaton_type, &label, label_span)? {
+                        transitions.push((name.clone(), to.clone(), steps));
+                    }
+                }
+                _ => {
+                    let state_idx = Self::mention_dsl_state(&mut states, &mut state_idxs, &name);
+                    if states[state_idx].is_declared {
+                        return Err(ParserError::new(
+                            DuplicateObjName,
+                            format!("State '{}' is declared more than once", name),
+                            statement_span,
+                        ));
+                    }
+                    states[state_idx].is_declared = true;
+
+                    reader.skip_blank();
+                    if reader.program_iter.peek() == Some(&'"') {
+                        states[state_idx].alt_id = Some(reader.read_string()?);
+                    }
+                    if reader.consume('@') {
+                        let x = reader.read_number()?;
+                        reader.expect(',')?;
+                        let y = reader.read_number()?;
+                        states[state_idx].position = Position { x, y };
+                    }
+                }
+            }
+
+            if !reader.at_statement_end() {
+                return Err(reader.error(
+                    InvalidToken,
+                    String::from("Expected a new line or ';' after the statement"),
+                ));
+            }
+        }
+
+        // Transitions taken in several steps go through new states, which are only named once
+        // every state in the program is known so they cannot clash with one
+        let mut steps: Vec<(String, String, DslSymbols)> = vec![];
+        for (from, to, transition) in transitions {
+            let mut step_from = from;
+            for (step_idx, symbols) in transition.iter().enumerate() {
+                let step_to = if step_idx + 1 == transition.len() {
+                    to.clone()
+                } else {
+                    let mut id = format!("{}'", step_from);
+                    while state_idxs.contains_key(&id) {
+                        id.push('\'');
+                    }
+                    Self::mention_dsl_state(&mut states, &mut state_idxs, &id);
+                    id
+                };
+                steps.push((step_from, step_to.clone(), *symbols));
+                step_from = step_to;
+            }
+        }
+
+        // The start and accept states have to be used by the rest of the program
+        let find_state = |(state_id, state_span): &(String, Span), role: &str| {
+            if state_idxs.contains_key(state_id) {
+                Ok(state_id.clone())
+            } else {
+                Err(ParserError::new(
+                    UnknownState,
+                    format!("Cannot find {} state '{}'", role, state_id),
+                    *state_span,
+                ))
+            }
+        };
+        let start_state_id = match &start_state {
+            Some(start_state) => find_state(start_state, "start")?,
+            None => states
+                .first()
+                .map(|state| state.id.clone())
+                .ok_or_else(|| {
+                    ParserError::new(OutOfInput, String::from("No states were given"), type_span)
+                })?,
+        };
+        let accept_state_ids = accept_states
+            .iter()
+            .map(|accept_state| find_state(accept_state, "accept"))
+            .collect::<Result<HashSet<String>, ParserError>>()?;
+
+        let state_list = states
+            .into_iter()
+            .map(|state| {
+                Rc::new(State::new(
+                    state.id.clone(),
+                    state.alt_id,
+                    state.position,
+                    accept_state_ids.contains(state.id.as_str()),
+                    RefCell::new(vec![]),
+                ))
+            })
+            .collect::<Vec<Rc<State>>>();
+
+        let states_by_id = State::map_by_id(&state_list);
+        for (from, to, (symbol, pop, push)) in steps {
+            // Every state in a transition was mentioned so it is always found
+            if let (Some(from), Some(to)) = (states_by_id.get(&from), states_by_id.get(&to)) {
+                from.add_transition(Transition::new(to.clone(), symbol, pop, push));
+            }
+        }
+
+        let start_state = states_by_id[&start_state_id].clone();
+
+        Ok(Automaton::new(
+            automaton_type,
+            start_state,
+            state_list
+                .iter()
+                .filter(|state| state.is_accept_state)
+                .cloned()
+                .collect::<Vec<Rc<State>>>(),
+            state_list,
+            tests,
+        ))
+    }
+
+    /// Adds a state the first time it is mentioned, returning where it is in the list of states
+    fn mention_dsl_state(
+        states: &mut Vec<DslState>,
+        state_idxs: &mut HashMap<String, usize>,
+        id: &str,
+    ) -> usize {
+        if let Some(state_idx) = state_idxs.get(id) {
+            return *state_idx;
+        }
+
+        states.push(DslState {
+            id: String::from(id),
+            alt_id: None,
+            position: Position::default(),
+            is_declared: false,
+        });
+        state_idxs.insert(String::from(id), states.len() - 1);
+        states.len() - 1
+    }
+
+    /// Parses the label of an edge into the transitions it stands for
+    ///
+    /// Eg: 0,1 for a DFA or NFA and a,X/Y | b,Y/ϵ for a PDA. A PDA transition pushing several
+    /// symbols, such as a,X/YZ, is taken in steps that push Z and then ϵ,ϵ/Y so Y ends on top
+    pub(crate) fn parse_dsl_label(
+        automaton_type: AutomatonType,
+        label: &str,
+        label_span: Span,
+    ) -> Result<Vec<DslTransition>, ParserError> {
+        let symbol = |text: &str| {
+            let mut chars = text.trim().chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), None) => Ok(Symbol::from(c)),
+                _ => Err(ParserError::new(
+                    InvalidLabel,
+                    format!(
+                        "'{}' in label '{}' is not a single symbol",
+                        text.trim(),
+                        label
+                    ),
+                    label_span,
+                )),
+            }
+        };
+
+        if !matches!(automaton_type, PDA) {
+            return label
+                .split([',', '|'])
+                .map(|text| Ok(vec![(symbol(text)?, None, None)]))
+                .collect();
+        }
+
+        label
+            .split('|')
+            .map(|text| {
+                let (read_pop, push) = text
+                    .split_once('/')
+                    .and_then(|(read_pop, push)| Some((read_pop.split_once(',')?, push)))
+                    .ok_or_else(|| {
+                        ParserError::new(
+                            InvalidLabel,
+                            format!(
+                                "PDA transition '{}' should be written as a,X/Y",
+                                text.trim()
+                            ),
+                            label_span,
+                        )
+                    })?;
+
+                // The leftmost symbol pushed ends on top of the stack so it is pushed last
+                let mut pushes = push
+                    .trim()
+                    .chars()
+                    .rev()
+                    .map(|c| symbol(&c.to_string()))
+                    .collect::<Result<Vec<Symbol>, ParserError>>()?;
+                if pushes.is_empty() {
+                    pushes.push(symbol(push)?);
+                }
+
+                let mut steps = vec![(
+                    symbol(read_pop.0)?,
+                    Some(symbol(read_pop.1)?),
+                    Some(pushes[0]),
+                )];
+                for push in &pushes[1..] {
+                    steps.push((Symbol::EPSILON, Some(Symbol::EPSILON), Some(*push)));
+                }
+                Ok(steps)
+            })
+            .collect()
+    }
+}
+
+impl<'a> DslReader<'a> {
+    /// Skips new lines and semicolons between statements, returning whether any input is left
+    fn skip_separators(&mut self) -> bool {
+        loop {
+            self.skip_blank();
+            match self.program_iter.peek() {
+                Some('\n') | Some(';') => self.advance(),
+                Some(_) => return true,
+                None => return false,
+            }
+        }
+    }
+
+    /// Skips spaces and comments within a statement
+    fn skip_blank(&mut self) {
+        while let Some(&c) = self.program_iter.peek() {
+            match c {
+                '#' => {
+                    while !matches!(self.program_iter.peek(), Some('\n') | None) {
+                        self.advance();
+                    }
+                }
+                c if c.is_whitespace() && c != '\n' => self.advance(),
+                _ => break,
+            }
+        }
+    }
+
+    /// Whether the current statement has been fully read
+    fn at_statement_end(&mut self) -> bool {
+        self.skip_blank();
+        matches!(self.program_iter.peek(), Some('\n') | Some(';') | None)
+    }
+
+    /// Reads a keyword or state name
+    fn read_word(&mut self) -> Result<String, ParserError> {
+        self.skip_blank();
+        let mut word = String::new();
+
+        while let Some(&c) = self.program_iter.peek() {
+            if c.is_whitespace() || ";=,@\"#-".contains(c) {
+                break;
+            }
+            word.push(c);
+            self.advance();
+        }
+
+        if word.is_empty() {
+            return Err(match self.program_iter.peek() {
+                Some(&c) if c != '\n' => {
+                    self.error(InvalidToken, format!("Expected a name but found '{}'", c))
+                }
+                _ => self.error(
+                    OutOfInput,
+                    String::from("Expected a name but the statement ended"),
+                ),
+            });
+        }
+
+        Ok(word)
+    }
+
+    /// Reads the label of an edge up to and including the `->` that ends it
+    ///
+    /// Labels are never empty, so the first character is always part of the label
+    /// allowing `-` and `>` to be used as symbols
+    fn read_label(&mut self) -> Result<String, ParserError> {
+        let mut label = String::new();
+
+        loop {
+            match self.program_iter.peek() {
+                Some('-') if !label.is_empty() => {
+                    let mut lookahead = self.program_iter.clone();
+                    lookahead.next();
+                    if lookahead.next() == Some('>') {
+                        self.advance();
+                        self.advance();
+                        break;
+                    }
+                }
+                Some('\n') | None => {
+                    return Err(self.error(
+                        ScopeError,
+                        String::from("Expected '->' to end the label of the transition"),
+                    ))
+                }
+                _ => {}
+            }
+
+            if let Some(c) = self.program_iter.peek() {
+                label.push(*c);
+            }
+            self.advance();
+        }
+
+        Ok(label)
+    }
+
+    /// Reads a string surrounded by double quotes, where \ escapes the next character
+    fn read_string(&mut self) -> Result<String, ParserError> {
+        self.expect('"')?;
+        let mut string = String::new();
+
+        loop {
+            match self.program_iter.next() {
+                Some('"') => {
+                    self.column += 1;
+                    return Ok(string);
+                }
+                Some('\\') if !matches!(self.program_iter.peek(), Some('\n') | None) => {
+                    self.column += 1;
+                    if let Some(c) = self.program_iter.next() {
+                        string.push(c);
+                        self.column += 1;
+                    }
+                }
+                Some(c) if c != '\n' => {
+                    string.push(c);
+                    self.column += 1;
+                }
+                _ => {
+                    return Err(self.error(
+                        ScopeError,
+                        String::from("No closing '\"' was found for the string"),
+                    ))
+                }
+            }
+        }
+    }
+
+    /// Reads one or more strings separated by commas
+    fn read_string_list(&mut self) -> Result<Vec<String>, ParserError> {
+        let mut strings = vec![];
+        loop {
+            self.skip_blank();
+            strings.push(self.read_string()?);
+            if !self.consume(',') {
+                return Ok(strings);
+            }
+        }
+    }
+
+    /// Reads a number used in the position of a state
+    fn read_number(&mut self) -> Result<f64, ParserError> {
+        self.skip_blank();
+        let span = self.span();
+        let mut number = String::new();
+
+        while let Some(&c) = self.program_iter.peek() {
+            if !(c.is_ascii_digit() || c == '.' || c == '-') {
+                break;
+            }
+            number.push(c);
+            self.advance();
+        }
+
+        number.parse::<f64>().map_err(|_| {
+            ParserError::new(
+                InvalidToken,
+                format!("Expected a number but found '{}'", number),
+                span,
+            )
+        })
+    }
+
+    /// Consumes a character if it comes next, skipping any spaces before it
+    fn consume(&mut self, expected: char) -> bool {
+        self.skip_blank();
+        if self.program_iter.peek() == Some(&expected) {
+            self.advance();
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Consumes a character that has to come next
+    fn expect(&mut self, expected: char) -> Result<(), ParserError> {
+        if self.consume(expected) {
+            Ok(())
+        } else {
+            Err(self.error(InvalidToken, format!("Expected '{}'", expected)))
+        }
+    }
+
+    /// Moves past the next character keeping track of the line and column
+    fn advance(&mut self) {
+        match self.program_iter.next() {
+            Some('\n') => {
+                self.line += 1;
+                self.column = 1;
+            }
+            Some(_) => self.column += 1,
+            None => {}
+        }
+    }
+
+    /// Location of the next character
+    fn span(&self) -> Span {
+        Span {
+            line: self.line,
+            column: self.column,
+            len: 1,
+        }
+    }
+
+    fn error(&self, kind: ParserErrorKind, message: String) -> ParserError {
+        ParserError::new(kind, message, self.span())
+    }
+}
+
+#[cfg(test)]
+mod dsl_tests {
+    use std::rc::Rc;
+
+    use crate::automaton_graph::Symbol;
+    use crate::parser::{Parser, ParserErrorKind, Span};
+    use crate::pda::PDA;
+
+    #[test]
+    fn test_dsl_matches_site_json() {
+        let from_dsl =
+            Parser::parse_dsl("dfa start=q0 accept=q2; q0 -0-> q1; q1 -1,0-> q2 # comment\n")
+                .unwrap();
+        let from_json = Parser::parse(
+            r#"{"type":"DFA","dfa":{"startState":"q0","acceptStates":["q2"]},"states":{"q0":{},"q1":{},"q2":{"isAccept":true}},
+            "transitions":[{"stateA":"q0","label":"0","stateB":"q1"},{"stateA":"q1","label":"1","stateB":"q2"},{"stateA":"q1","label":"0","stateB":"q2"}]}"#,
+        )
+        .unwrap().0;
+
+        assert_eq!(from_dsl.to_site_json(), from_json.to_site_json());
+    }
+
+    #[test]
+    fn test_dsl_pda_labels() {
+        let automaton = Parser::parse_dsl("pda accept=q1\nq0 -a,ϵ/A | b,A/ϵ-> q1").unwrap();
+        let start_state = automaton.get_start_state();
+        let transitions = start_state.get_transitions();
+
+        assert_eq!(transitions.len(), 2);
+        assert!(transitions[0].transition_on() == Symbol::CHAR('a'));
+        assert!(transitions[0].pop_symbol() == Some(Symbol::EPSILON));
+        assert!(transitions[1].push_symbol() == Some(Symbol::EPSILON));
+
+        let error = Parser::parse_dsl("pda\nq0 -a,XY/Z-> q1").unwrap_err();
+        assert_eq!(error.kind, ParserErrorKind::InvalidLabel);
+        assert_eq!(
+            error.span,
+            Span {
+                line: 2,
+                column: 5,
+                len: 1
+            }
+        );
+    }
+
+    #[test]
+    fn test_dsl_pda_pushes_several_symbols_through_new_states() {
+        let automaton =
+            Parser::parse_dsl("pda start=q0 accept=q2\nq0' @ 0,0\nq0 -a,ϵ/AB-> q1\nq1 -b,A/ϵ-> q2")
+                .unwrap();
+        let start_state = automaton.get_start_state();
+        let transitions = start_state.get_transitions();
+
+        assert_eq!(transitions.len(), 1);
+        assert!(transitions[0].push_symbol() == Some(Symbol::CHAR('B')));
+        let step_state = transitions[0].to();
+        assert_eq!(step_state.id, "q0''");
+        let step_transitions = step_state.get_transitions();
+        assert!(step_transitions[0].transition_on() == Symbol::EPSILON);
+        assert!(step_transitions[0].push_symbol() == Some(Symbol::CHAR('A')));
+        assert_eq!(step_transitions[0].next_state_id(), "q1");
+
+        // A ends on top of the stack so b can pop it
+        assert!(PDA::new(Rc::new(automaton))
+            .simulate(String::from("ab"))
+            .unwrap());
+    }
+}
@@ -0,0 +1,726 @@
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+use std::iter::Peekable;
+use std::rc::Rc;
+use std::str::Chars;
+
+use crate::automaton_graph::AutomatonType::{DFA, NFA, PDA};
+use crate::automaton_graph::{Automaton, Position, State, Symbol, Tests, Transition};
+use crate::parser::ParserErrorKind::{
+    InvalidToken, ObjNameNotFound, ObjNameSyntaxErr, ScopeError, UnknownState,
+};
+use crate::parser::{Parser, ParserError, ParserErrorKind, Span};
+
+/// An element of an xml document along with where its opening tag is found
+#[derive(Debug)]
+struct XmlElement {
+    name: String,
+    attributes: Vec<(String, String)>,
+    children: Vec<XmlElement>,
+    text: String,
+    span: Span,
+}
+
+/// Reads the small subset of xml that JFLAP writes: elements, attributes, text,
+/// comments and the xml declaration
+struct XmlReader<'a> {
+    program_iter: Peekable<Chars<'a>>,
+    line: u32,
+    column: u32,
+}
+
+impl Parser {
+    /// Parses a finite automaton or pushdown automaton saved by [JFLAP](https://www.jflap.org/)
+    ///
+    ///     <structure>
+    ///         <type>fa</type>
+    ///         <automaton>
+    ///             <state id="0" name="q0"><x>60.0</x><y>80.0</y><initial/></state>
+    ///             <state id="1" name="q1"><x>160.0</x><y>80.0</y><final/></state>
+    ///             <transition><from>0</from><to>1</to><read>a</read></transition>
+    ///         </automaton>
+    ///     </structure>
+    ///
+    /// State ids become the id of each [State] and state names their display id.
+    /// Finite automata are loaded as a DFA when they have no choice of transitions and as an NFA otherwise.
+    /// Empty read, pop and push elements are taken as ϵ, and elements holding more than one symbol
+    /// are split into a chain of transitions through new states
+    ///
+    /// Returns a [ParserError] pointing to where in the file the first problem was found
+    pub fn parse_jff(program: &str) -> Result<Automaton, ParserError> {
+        let structure = XmlReader::read_document(program)?;
+        if structure.name != "structure" {
+            return Err(ParserError::new(
+                ObjNameNotFound,
+                format!(
+                    "Expected a <structure> element but found <{}>",
+                    structure.name
+                ),
+                structure.span,
+            ));
+        }
+
+        let type_element = structure.child("type")?;
+        let is_pda = match type_element.text.trim() {
+            "fa" => false,
+            "pda" => true,
+            other => {
+                return Err(ParserError::new(
+                    ObjNameSyntaxErr,
+                    format!("JFLAP automaton type '{}' is not supported", other),
+                    type_element.span,
+                ))
+            }
+        };
+
+        // Older versions of JFLAP list states and transitions directly in the structure
+        let automaton_element = structure
+            .children
+            .iter()
+            .find(|child| child.name == "automaton")
+            .unwrap_or(&structure);
+
+        let mut state_list: Vec<Rc<State>> = vec![];
+        let mut start_state: Option<Rc<State>> = None;
+
+        for state_element in automaton_element.children_named("state") {
+            let id = state_element.attribute("id")?;
+            let position = Position {
+                x: state_element.child("x")?.number()?,
+                y: state_element.child("y")?.number()?,
+            };
+
+            let state = Rc::new(State::new(
+                String::from(id),
+                state_element
+                    .attributes
+                    .iter()
+                    .find(|(name, _)| name == "name")
+                    .map(|(_, name)| name.clone()),
+                position,
+                state_element.children_named("final").next().is_some(),
+                RefCell::new(vec![]),
+            ));
+
+            if state_element.children_named("initial").next().is_some() {
+                start_state = Some(state.clone());
+            }
+            state_list.push(state);
+        }
+
+        // Symbols read by each state, used to tell a DFA apart from an NFA
+        let mut state_symbols: HashMap<String, Vec<Symbol>> = HashMap::new();
+        let mut is_deterministic = true;
+        let mut pops_initial_stack_symbol = false;
+
+        let states_by_id = State::map_by_id(&state_list);
+        let mut used_ids = states_by_id.keys().cloned().collect::<HashSet<String>>();
+        // JFLAP state ids are numbers so the states added here are given ids it never uses
+        let mut new_state = |id: String, position: Position| {
+            let mut id = id;
+            while !used_ids.insert(id.clone()) {
+                id.push('\'');
+            }
+            Rc::new(State::new(id, None, position, false, RefCell::new(vec![])))
+        };
+
+        for (transition_idx, transition_element) in
+            automaton_element.children_named("transition").enumerate()
+        {
+            let find_state = |name: &str| {
+                let state_element = transition_element.child(name)?;
+                states_by_id
+                    .get(state_element.text.trim())
+                    .cloned()
+                    .ok_or_else(|| {
+                        ParserError::new(
+                            UnknownState,
+                            format!(
+                                "Cannot find state '{}' referenced by <{}>",
+                                state_element.text.trim(),
+                                name
+                            ),
+                            state_element.span,
+                        )
+                    })
+            };
+            let from = find_state("from")?;
+            let to = find_state("to")?;
+
+            let reads = transition_element.child("read")?.symbols();
+            let (pops, pushes) = if is_pda {
+                (
+                    transition_element.child("pop")?.symbols(),
+                    transition_element.child("push")?.symbols(),
+                )
+            } else {
+                (vec![], vec![])
+            };
+            pops_initial_stack_symbol |= pops.contains(&Symbol::CHAR('Z'));
+
+            // Transitions on more than one symbol go through a state for each extra step
+            let steps = Self::jff_steps(&reads, &pops, &pushes);
+            let mut step_from = from.clone();
+            for (step, &(symbol, pop_symbol, push_symbol)) in steps.iter().enumerate() {
+                let step_to = if step + 1 == steps.len() {
+                    to.clone()
+                } else {
+                    let progress = (step + 1) as f64 / steps.len() as f64;
+                    let state = new_state(
+                        format!("t{}_{}", transition_idx, step + 1),
+                        Position {
+                            x: from.position.x + (to.position.x - from.position.x) * progress,
+                            y: from.position.y + (to.position.y - from.position.y) * progress,
+                        },
+                    );
+                    state_list.push(state.clone());
+                    state
+                };
+
+                let transition = if is_pda {
+                    Transition::new(step_to.clone(), symbol, Some(pop_symbol), Some(push_symbol))
+                } else {
+                    let symbols = state_symbols.entry(step_from.id.clone()).or_default();
+                    is_deterministic &= symbol != Symbol::EPSILON && !symbols.contains(&symbol);
+                    symbols.push(symbol);
+
+                    Transition::new(step_to.clone(), symbol, None, None)
+                };
+
+                step_from.add_transition(transition);
+                step_from = step_to;
+            }
+        }
+
+        let mut start_state = start_state.ok_or_else(|| {
+            ParserError::new(
+                ObjNameNotFound,
+                String::from("No state is marked <initial/>"),
+                automaton_element.span,
+            )
+        })?;
+
+        // JFLAP starts a PDA with Z on its stack, which is pushed by a new start state
+        // whenever a transition can pop it
+        if pops_initial_stack_symbol {
+            let initial_state = new_state(
+                String::from("init"),
+                Position {
+                    x: start_state.position.x - 100.0,
+                    y: start_state.position.y,
+                },
+            );
+            initial_state.add_transition(Transition::new(
+                start_state,
+                Symbol::EPSILON,
+                Some(Symbol::EPSILON),
+                Some(Symbol::CHAR('Z')),
+            ));
+            state_list.insert(0, initial_state.clone());
+            start_state = initial_state;
+        }
+
+        Ok(Automaton::new(
+            match (is_pda, is_deterministic) {
+                (true, _) => PDA,
+                (false, true) => DFA,
+                (false, false) => NFA,
+            },
+            start_state,
+            state_list
+                .iter()
+                .filter(|state| state.is_accept_state)
+                .cloned()
+                .collect::<Vec<Rc<State>>>(),
+            state_list,
+            Tests::default(),
+        ))
+    }
+}
+
+impl Parser {
+    /// Splits a JFLAP transition into steps that each read, pop and push at most one symbol,
+    /// returned as the symbol read followed by the pop and push symbols
+    ///
+    /// JFLAP pops and pushes strings with their first symbol on top, so every symbol is popped
+    /// before the pushed string is pushed last symbol first. Eg: reading "ab", popping "A" and
+    /// pushing "BC" gives a,A/C then b,ϵ/B
+    fn jff_steps(
+        reads: &[Symbol],
+        pops: &[Symbol],
+        pushes: &[Symbol],
+    ) -> Vec<(Symbol, Symbol, Symbol)> {
+        let mut stack_steps = pops
+            .iter()
+            .map(|&pop_symbol| (pop_symbol, Symbol::EPSILON))
+            .collect::<Vec<(Symbol, Symbol)>>();
+        let mut pushes = pushes.iter().rev().copied();
+
+        // A step pops before it pushes, so the last pop can share its step with the first push
+        if let Some(last_pop) = stack_steps.last_mut() {
+            if let Some(push_symbol) = pushes.next() {
+                last_pop.1 = push_symbol;
+            }
+        }
+        stack_steps.extend(pushes.map(|push_symbol| (Symbol::EPSILON, push_symbol)));
+
+        (0..reads.len().max(stack_steps.len()).max(1))
+            .map(|step| {
+                let (pop_symbol, push_symbol) = stack_steps
+                    .get(step)
+                    .copied()
+                    .unwrap_or((Symbol::EPSILON, Symbol::EPSILON));
+                (
+                    reads.get(step).copied().unwrap_or(Symbol::EPSILON),
+                    pop_symbol,
+                    push_symbol,
+                )
+            })
+            .collect()
+    }
+}
+
+impl XmlElement {
+    /// Finds the first child element with a given name
+    fn child(&self, name: &str) -> Result<&XmlElement, ParserError> {
+        self.children
+            .iter()
+            .find(|child| child.name == name)
+            .ok_or_else(|| {
+                ParserError::new(
+                    ObjNameNotFound,
+                    format!("<{}> is missing a <{}> element", self.name, name),
+                    self.span,
+                )
+            })
+    }
+
+    /// Iterates over every child element with a given name
+    fn children_named<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a XmlElement> + 'a {
+        self.children.iter().filter(move |child| child.name == name)
+    }
+
+    /// Finds the value of an attribute
+    fn attribute(&self, name: &str) -> Result<&str, ParserError> {
+        self.attributes
+            .iter()
+            .find(|(attribute, _)| attribute == name)
+            .map(|(_, value)| value.as_str())
+            .ok_or_else(|| {
+                ParserError::new(
+                    ObjNameNotFound,
+                    format!("<{}> is missing the attribute '{}'", self.name, name),
+                    self.span,
+                )
+            })
+    }
+
+    /// Reads the text of the element as a number
+    fn number(&self) -> Result<f64, ParserError> {
+        self.text.trim().parse::<f64>().map_err(|_| {
+            ParserError::new(
+                ObjNameSyntaxErr,
+                format!(
+                    "<{}> should hold a number but holds '{}'",
+                    self.name, self.text
+                ),
+                self.span,
+            )
+        })
+    }
+
+    /// Reads the text of the element as symbols, where no text is ϵ
+    fn symbols(&self) -> Vec<Symbol> {
+        self.text.chars().map(Symbol::from).collect()
+    }
+}
+
+impl<'a> XmlReader<'a> {
+    /// Reads the root element of a document skipping the declaration and any comments around it
+    fn read_document(program: &'a str) -> Result<XmlElement, ParserError> {
+        let mut reader = XmlReader {
+            program_iter: program.chars().peekable(),
+            line: 1,
+            column: 1,
+        };
+
+        reader.skip_misc()?;
+        let root = reader.read_element()?;
+        reader.skip_misc()?;
+
+        if reader.program_iter.peek().is_some() {
+            return Err(reader.error(
+                ScopeError,
+                String::from("Unexpected input after the root element"),
+            ));
+        }
+
+        Ok(root)
+    }
+
+    /// Reads an element starting at its opening tag up to and including its closing tag
+    fn read_element(&mut self) -> Result<XmlElement, ParserError> {
+        let span = self.span();
+        self.expect('<')?;
+
+        let name = self.read_name()?;
+        let mut attributes = vec![];
+
+        // Attributes up to the end of the opening tag
+        loop {
+            self.skip_whitespace();
+            match self.program_iter.peek() {
+                Some('/') => {
+                    self.advance();
+                    self.expect('>')?;
+                    return Ok(XmlElement {
+                        name,
+                        attributes,
+                        children: vec![],
+                        text: String::new(),
+                        span,
+                    });
+                }
+                Some('>') => {
+                    self.advance();
+                    break;
+                }
+                _ => {
+                    let attribute = self.read_name()?;
+                    self.skip_whitespace();
+                    self.expect('=')?;
+                    self.skip_whitespace();
+                    attributes.push((attribute, self.read_attribute_value()?));
+                }
+            }
+        }
+
+        let mut children = vec![];
+        let mut text = String::new();
+
+        // Content up to the closing tag
+        loop {
+            match self.program_iter.peek() {
+                None => {
+                    return Err(ParserError::new(
+                        ScopeError,
+                        format!("No closing tag was found for <{}>", name),
+                        span,
+                    ))
+                }
+                Some('<') => {
+                    let tag_span = self.span();
+                    let mut lookahead = self.program_iter.clone();
+                    lookahead.next();
+                    match lookahead.next() {
+                        Some('/') => {
+                            self.advance();
+                            self.advance();
+                            let closing_name = self.read_name()?;
+                            self.skip_whitespace();
+                            self.expect('>')?;
+
+                            if closing_name != name {
+                                return Err(ParserError::new(
+                                    ScopeError,
+                                    format!(
+                                        "Expected closing tag </{}> but found </{}>",
+                                        name, closing_name
+                                    ),
+                                    tag_span,
+                                ));
+                            }
+
+                            return Ok(XmlElement {
+                                name,
+                                attributes,
+                                children,
+                                text,
+                                span,
+                            });
+                        }
+                        Some('!') | Some('?') => self.skip_misc()?,
+                        _ => children.push(self.read_element()?),
+                    }
+                }
+                Some('&') => text.push(self.read_entity()?),
+                Some(_) => text.extend(self.advance()),
+            }
+        }
+    }
+
+    /// Skips whitespace, comments and processing instructions such as the xml declaration
+    fn skip_misc(&mut self) -> Result<(), ParserError> {
+        loop {
+            self.skip_whitespace();
+
+            let mut lookahead = self.program_iter.clone();
+            let (terminator, len) = match (lookahead.next(), lookahead.next()) {
+                (Some('<'), Some('?')) => ("?>", 2),
+                (Some('<'), Some('!')) => ("-->", 4),
+                _ => return Ok(()),
+            };
+
+            let span = self.span();
+            for _ in 0..len {
+                self.advance();
+            }
+
+            let mut skipped = String::new();
+            while !skipped.ends_with(terminator) {
+                skipped.push(self.advance().ok_or_else(|| {
+                    ParserError::new(
+                        ScopeError,
+                        format!("No closing '{}' was found", terminator),
+                        span,
+                    )
+                })?);
+            }
+        }
+    }
+
+    /// Reads the name of an element or attribute
+    fn read_name(&mut self) -> Result<String, ParserError> {
+        let mut name = String::new();
+        while let Some(c) = self
+            .program_iter
+            .peek()
+            .filter(|c| c.is_alphanumeric() || matches!(c, '_' | '-' | '.' | ':'))
+        {
+            name.push(*c);
+            self.advance();
+        }
+
+        if name.is_empty() {
+            return Err(self.error(InvalidToken, String::from("Expected a name")));
+        }
+        Ok(name)
+    }
+
+    /// Reads an attribute value in single or double quotation marks
+    fn read_attribute_value(&mut self) -> Result<String, ParserError> {
+        let quote = match self.program_iter.peek() {
+            Some(c @ ('"' | '\'')) => *c,
+            _ => {
+                return Err(self.error(
+                    InvalidToken,
+                    String::from("Expected an attribute value in quotation marks"),
+                ))
+            }
+        };
+        let span = self.span();
+        self.advance();
+
+        let mut value = String::new();
+        loop {
+            match self.program_iter.peek() {
+                None => {
+                    return Err(ParserError::new(
+                        InvalidToken,
+                        String::from("Attribute value is missing its closing quotation"),
+                        span,
+                    ))
+                }
+                Some(c) if *c == quote => {
+                    self.advance();
+                    return Ok(value);
+                }
+                Some('&') => value.push(self.read_entity()?),
+                Some(_) => value.extend(self.advance()),
+            }
+        }
+    }
+
+    /// Reads an escaped character such as &amp; or &#949;
+    fn read_entity(&mut self) -> Result<char, ParserError> {
+        let span = self.span();
+        let mut entity = String::new();
+        while let Some(c) = self.advance() {
+            entity.push(c);
+            if c == ';' || entity.len() > 10 {
+                break;
+            }
+        }
+
+        let resolved = match entity.as_str() {
+            "&lt;" => Some('<'),
+            "&gt;" => Some('>'),
+            "&amp;" => Some('&'),
+            "&quot;" => Some('"'),
+            "&apos;" => Some('\''),
+            _ => entity
+                .strip_prefix("&#")
+                .and_then(|code| code.strip_suffix(';'))
+                .and_then(|code| match code.strip_prefix('x') {
+                    Some(hex) => u32::from_str_radix(hex, 16).ok(),
+                    None => code.parse::<u32>().ok(),
+                })
+                .and_then(char::from_u32),
+        };
+
+        resolved.ok_or_else(|| {
+            ParserError::new(
+                InvalidToken,
+                format!("Unknown entity '{}'", entity),
+                Span {
+                    len: entity.chars().count() as u32,
+                    ..span
+                },
+            )
+        })
+    }
+
+    /// Consumes a character that has to come next
+    fn expect(&mut self, expected: char) -> Result<(), ParserError> {
+        if self.program_iter.peek() == Some(&expected) {
+            self.advance();
+            Ok(())
+        } else {
+            Err(self.error(InvalidToken, format!("Expected '{}'", expected)))
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.program_iter.peek().is_some_and(|c| c.is_whitespace()) {
+            self.advance();
+        }
+    }
+
+    /// Consumes a single character keeping the line and column up to date
+    fn advance(&mut self) -> Option<char> {
+        let c = self.program_iter.next()?;
+        if c == '\n' {
+            self.line += 1;
+            self.column = 1;
+        } else {
+            self.column += 1;
+        }
+        Some(c)
+    }
+
+    /// Where the next character is found
+    fn span(&self) -> Span {
+        Span {
+            line: self.line,
+            column: self.column,
+            len: 1,
+        }
+    }
+
+    /// Creates an error found at the next character
+    fn error(&self, kind: ParserErrorKind, message: String) -> ParserError {
+        ParserError::new(kind, message, self.span())
+    }
+}
+
+#[cfg(test)]
+mod jflap_tests {
+    use crate::automaton_graph::AutomatonType::{NFA, PDA};
+    use crate::automaton_graph::Symbol;
+    use crate::parser::Parser;
+    use crate::parser::ParserErrorKind::UnknownState;
+    use crate::simulator::Simulator;
+
+    const JFLAP_NFA: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="no"?><!--Created with JFLAP 7.1.--><structure>
+	<type>fa</type>
+	<automaton>
+		<!--The list of states.-->
+		<state id="0" name="q&lt;0&gt;">
+			<x>69.0</x>
+			<y>129.0</y>
+			<initial/>
+		</state>
+		<state id="1" name="q1">
+			<x>200.5</x>
+			<y>129.0</y>
+			<final/>
+		</state>
+		<!--The list of transitions.-->
+		<transition>
+			<from>0</from>
+			<to>1</to>
+			<read>a</read>
+		</transition>
+		<transition>
+			<from>1</from>
+			<to>0</to>
+			<read/>
+		</transition>
+	</automaton>
+</structure>"#;
+
+    #[test]
+    fn test_jff_finite_automaton() {
+        let automaton = Parser::parse_jff(JFLAP_NFA).unwrap();
+        let start_state = automaton.get_start_state();
+        let accept_state = &automaton.all_states()[1];
+
+        assert!(matches!(automaton.automaton_type, NFA));
+        assert_eq!(start_state.alt_id, "q<0>");
+        assert_eq!(accept_state.position.x, 200.5);
+        assert!(accept_state.is_accept_state);
+        assert_eq!(
+            start_state.get_transitions()[0].transition_on(),
+            Symbol::CHAR('a')
+        );
+        assert_eq!(
+            accept_state.get_transitions()[0].transition_on(),
+            Symbol::EPSILON
+        );
+    }
+
+    #[test]
+    fn test_jff_pda() {
+        let automaton = Parser::parse_jff(
+            r#"<structure><type>pda</type><automaton>
+            <state id="0"><x>0</x><y>0</y><initial/><final/></state>
+            <transition><from>0</from><to>0</to><read>a</read><pop/><push>A</push></transition>
+            </automaton></structure>"#,
+        )
+        .unwrap();
+        let start_state = automaton.get_start_state();
+        let transition = &start_state.get_transitions()[0];
+
+        assert!(matches!(automaton.automaton_type, PDA));
+        assert_eq!(transition.pop_symbol(), Some(Symbol::EPSILON));
+        assert_eq!(transition.push_symbol(), Some(Symbol::CHAR('A')));
+    }
+
+    #[test]
+    fn test_jff_pda_with_strings_and_initial_stack_symbol() {
+        // a^n b^n for n >= 1, or "cd", where Z marks the bottom of the stack
+        let automaton = Parser::parse_jff(
+            r#"<structure><type>pda</type><automaton>
+            <state id="0"><x>0</x><y>0</y><initial/></state>
+            <state id="1"><x>100</x><y>0</y></state>
+            <state id="2"><x>200</x><y>0</y><final/></state>
+            <transition><from>0</from><to>0</to><read>a</read><pop>Z</pop><push>AZ</push></transition>
+            <transition><from>0</from><to>0</to><read>a</read><pop>A</pop><push>AA</push></transition>
+            <transition><from>0</from><to>1</to><read>b</read><pop>A</pop><push/></transition>
+            <transition><from>1</from><to>1</to><read>b</read><pop>A</pop><push/></transition>
+            <transition><from>1</from><to>2</to><read/><pop>Z</pop><push>Z</push></transition>
+            <transition><from>0</from><to>2</to><read>cd</read><pop>Z</pop><push/></transition>
+            </automaton></structure>"#,
+        )
+        .unwrap();
+
+        assert_eq!(automaton.get_start_state().id, "init");
+        assert_eq!(automaton.all_states().len(), 7);
+
+        let simulator = Simulator::new(automaton);
+        for accepted in ["ab", "aabb", "cd"] {
+            assert!(simulator.accepts(accepted).unwrap(), "{}", accepted);
+        }
+        for rejected in ["", "aab", "abb", "c", "acd"] {
+            assert!(!simulator.accepts(rejected).unwrap(), "{}", rejected);
+        }
+    }
+
+    #[test]
+    fn test_jff_errors_point_to_their_source() {
+        let err = Parser::parse_jff(&JFLAP_NFA.replace("<to>0</to>", "<to>7</to>")).unwrap_err();
+
+        assert_eq!(err.kind, UnknownState);
+        assert_eq!((err.span.line, err.span.column), (23, 4));
+    }
+}
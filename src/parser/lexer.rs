@@ -0,0 +1,226 @@
+use std::iter::Peekable;
+use std::str::Chars;
+
+use crate::parser::ParserErrorKind::InvalidToken;
+use crate::parser::{ParserError, Scope, Separator, Span, Token, TokenKind};
+
+/// Breaks the json describing an automaton into [Token]s, keeping track
+/// of where in the file each token starts
+pub(in crate::parser) struct Lexer<'a> {
+    program_iter: Peekable<Chars<'a>>,
+    line: u32,
+    column: u32,
+}
+
+impl<'a> Lexer<'a> {
+    /// Converts a whole program into a list of tokens
+    pub fn tokenize(program: &'a str) -> Result<Vec<Token>, ParserError> {
+        let mut lexer = Lexer {
+            program_iter: program.chars().peekable(),
+            line: 1,
+            column: 1,
+        };
+
+        let mut tokens = vec![];
+        while let Some(token) = lexer.next_token()? {
+            tokens.push(token);
+        }
+
+        Ok(tokens)
+    }
+
+    /// Reads the next token skipping any whitespace before it
+    fn next_token(&mut self) -> Result<Option<Token>, ParserError> {
+        while self.program_iter.peek().is_some_and(|c| c.is_whitespace()) {
+            self.advance();
+        }
+
+        let (line, column) = (self.line, self.column);
+        let c = match self.program_iter.peek() {
+            Some(c) => *c,
+            None => return Ok(None),
+        };
+
+        let kind = match c {
+            '{' | '[' | '}' | ']' | ',' | ':' => {
+                self.advance();
+                match c {
+                    '{' => TokenKind::OpenScope(Scope::CurlyBracket),
+                    '[' => TokenKind::OpenScope(Scope::BoxBracket),
+                    '}' => TokenKind::CloseScope(Scope::CurlyBracket),
+                    ']' => TokenKind::CloseScope(Scope::BoxBracket),
+                    ',' => TokenKind::Separator(Separator::COMMA),
+                    _ => TokenKind::Separator(Separator::COLUMN),
+                }
+            }
+            '"' => TokenKind::String(self.read_string()?),
+            '-' | '0'..='9' => TokenKind::Number(self.read_number()?),
+            c if c.is_alphabetic() => self.read_literal()?,
+            c => {
+                return Err(ParserError::new(
+                    InvalidToken,
+                    format!("Unexpected character '{}'", c),
+                    self.span_from(line, column),
+                ))
+            }
+        };
+
+        Ok(Some(Token {
+            kind,
+            span: self.span_from(line, column),
+        }))
+    }
+
+    /// Creates a span from a starting point up to the current character.
+    /// Spans that go over multiple lines only cover their first character
+    fn span_from(&self, line: u32, column: u32) -> Span {
+        Span {
+            line,
+            column,
+            len: if line == self.line {
+                (self.column - column).max(1)
+            } else {
+                1
+            },
+        }
+    }
+
+    /// Consumes a single character keeping the line and column up to date
+    fn advance(&mut self) -> Option<char> {
+        let c = self.program_iter.next()?;
+        if c == '\n' {
+            self.line += 1;
+            self.column = 1;
+        } else {
+            self.column += 1;
+        }
+        Some(c)
+    }
+
+    /// Reads a string in quotation marks resolving any escaped characters in it
+    ///
+    /// Eg: "q\n0" is read as q followed by a new line and 0
+    fn read_string(&mut self) -> Result<String, ParserError> {
+        let (line, column) = (self.line, self.column);
+        let unterminated = || {
+            ParserError::new(
+                InvalidToken,
+                String::from("String is missing its closing quotation"),
+                Span {
+                    line,
+                    column,
+                    len: 1,
+                },
+            )
+        };
+
+        // Opening quotation
+        self.advance();
+
+        let mut string = String::new();
+        loop {
+            match self.advance().ok_or_else(unterminated)? {
+                '"' => return Ok(string),
+                '\\' => string.push(self.read_escape()?),
+                c => string.push(c),
+            }
+        }
+    }
+
+    /// Reads the character following a backslash in a string
+    fn read_escape(&mut self) -> Result<char, ParserError> {
+        // Include the backslash before the escaped character
+        let (line, column) = (self.line, self.column - 1);
+        let invalid_escape = |escape: &str| {
+            ParserError::new(
+                InvalidToken,
+                format!("Invalid escape sequence '\\{}'", escape),
+                Span {
+                    line,
+                    column,
+                    len: escape.chars().count() as u32 + 1,
+                },
+            )
+        };
+
+        match self.advance().ok_or_else(|| invalid_escape(""))? {
+            '"' => Ok('"'),
+            '\\' => Ok('\\'),
+            '/' => Ok('/'),
+            'b' => Ok('\u{8}'),
+            'f' => Ok('\u{c}'),
+            'n' => Ok('\n'),
+            'r' => Ok('\r'),
+            't' => Ok('\t'),
+            'u' => {
+                let high = self.read_code_unit().ok_or_else(|| invalid_escape("u"))?;
+                // Characters outside the basic plane are written as a surrogate pair
+                let code_point = if (0xD800..0xDC00).contains(&high) {
+                    let low = match (self.advance(), self.advance()) {
+                        (Some('\\'), Some('u')) => self.read_code_unit(),
+                        _ => None,
+                    }
+                    .filter(|low| (0xDC00..0xE000).contains(low))
+                    .ok_or_else(|| invalid_escape("u"))?;
+                    0x10000 + ((high - 0xD800) << 10) + (low - 0xDC00)
+                } else {
+                    high
+                };
+                char::from_u32(code_point).ok_or_else(|| invalid_escape("u"))
+            }
+            c => Err(invalid_escape(&c.to_string())),
+        }
+    }
+
+    /// Reads the 4 hex digits of a unicode escape
+    fn read_code_unit(&mut self) -> Option<u32> {
+        let digits = (0..4).filter_map(|_| self.advance()).collect::<String>();
+        if digits.len() != 4 {
+            return None;
+        }
+        u32::from_str_radix(&digits, 16).ok()
+    }
+
+    /// Reads a number such as -12.5e3
+    fn read_number(&mut self) -> Result<f64, ParserError> {
+        let (line, column) = (self.line, self.column);
+        let mut number = String::new();
+        while let Some(c) = self
+            .program_iter
+            .peek()
+            .filter(|c| c.is_ascii_digit() || matches!(c, '-' | '+' | '.' | 'e' | 'E'))
+        {
+            number.push(*c);
+            self.advance();
+        }
+
+        number.parse::<f64>().map_err(|_| {
+            ParserError::new(
+                InvalidToken,
+                format!("Invalid number '{}'", number),
+                self.span_from(line, column),
+            )
+        })
+    }
+
+    /// Reads one of the literals true, false or null
+    fn read_literal(&mut self) -> Result<TokenKind, ParserError> {
+        let (line, column) = (self.line, self.column);
+        let mut literal = String::new();
+        while let Some(c) = self.program_iter.peek().filter(|c| c.is_alphanumeric()) {
+            literal.push(*c);
+            self.advance();
+        }
+
+        match literal.as_str() {
+            "true" => Ok(TokenKind::Bool(true)),
+            "false" => Ok(TokenKind::Bool(false)),
+            "null" => Ok(TokenKind::Null),
+            _ => Err(ParserError::new(
+                InvalidToken,
+                format!("Unknown literal '{}'", literal),
+                self.span_from(line, column),
+            )),
+        }
+    }
+}
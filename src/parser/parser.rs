@@ -1,20 +1,25 @@
 use std::cell::RefCell;
+use std::collections::hash_map::Entry;
+use std::collections::{BTreeSet, HashMap, HashSet};
 use std::rc::Rc;
-use std::slice::Iter;
-
-use regex::Regex;
 
 use automaton_graph::State;
 
 use crate::automaton_graph;
 use crate::automaton_graph::AutomatonType::{DFA, NFA, PDA};
 use crate::automaton_graph::{Automaton, AutomatonType, Position, Symbol, Tests, Transition};
-use crate::parser::parser::ParserError::{
-    MissingObjSeparator, NoObjName, ObjNameMismatch, ObjNameNotFound, ObjNameOverFlow,
-    ObjNameSyntaxErr,
+use crate::parser::lexer::Lexer;
+use crate::parser::ParserErrorKind::{
+    DuplicateObjName, InvalidLabel, MissingObjSeparator, NoObjName, NotDeterministic,
+    ObjNameNotFound, ObjNameSyntaxErr, OutOfInput, ScopeError, UnknownState, UnknownTransitionName,
+};
+use crate::parser::{
+    AutomatonSection, Issues, Parser, ParserError, ParserErrorKind, Scope, Separator,
+    SkeletonState, Span, Token, TokenKind, ValidationIssue,
 };
-use crate::parser::ParserError::{OutOfInput, ScopeError};
-use crate::parser::{Parser, ParserError, Scope, Separator, SkeletonState};
+
+/// The symbol read along with the pop and push symbols of one step of a transition
+type LabelStep = (Symbol, Option<Symbol>, Option<Symbol>);
 
 impl Parser {
     /// Parses the file that describes the automaton whose skeleton
@@ -48,425 +53,754 @@ impl Parser {
     ///
     ///     }
     ///
-    pub fn parse(program: &str) -> Automaton {
+    /// The objects can be given in any order. Unrecognised objects are skipped
+    /// and the "dfa" / "nfa" / "pda" and "bulkTests" objects can be left out
+    ///
+    /// Returns the automaton along with warnings about parts of the program that were ignored or
+    /// likely mistakes, or a [ParserError] pointing to where in the program the first problem was
+    /// found, [Parser::validate] lists every problem
+    pub fn parse(program: &str) -> Result<(Automaton, Vec<ValidationIssue>), ParserError> {
+        let (automaton, issues) = Self::parse_program(program);
+        match issues.first_error {
+            Some(err) => Err(err),
+            None => Ok((
+                automaton.expect("an automaton is built whenever no error is found"),
+                issues.issues,
+            )),
+        }
+    }
+
+    /// Reads the whole program, building the automaton when no errors are found in it
+    ///
+    /// A syntax error stops the section it is found in from being read any further,
+    /// but the other sections are still read so every problem is found in one pass
+    pub(in crate::parser) fn parse_program(program: &str) -> (Option<Automaton>, Issues) {
+        let mut issues = Issues::default();
+        let result = Self::parse_sections(program, &mut issues);
+        let automaton = issues.record(result).flatten();
+
+        (automaton.filter(|_| !issues.has_errors()), issues)
+    }
+
+    /// Splits the program into its sections and parses them in the order they depend on each other
+    ///
+    /// Returns [None] when a section the automaton needs could not be read
+    fn parse_sections(
+        program: &str,
+        issues: &mut Issues,
+    ) -> Result<Option<Automaton>, ParserError> {
         // Creates a parser for to parse the skeleton of the program
-        let mut skeleton_parser = Self::new(Self::prog_preprocessor(program)).set_counter(1); // Read an opening scope at the beginning
+        let mut program_parser = Self::prog_preprocessor(program)?;
+
+        // Sections of the skeleton can come in any order so each one is set aside
+        // and then parsed in the order they depend on each other
+        let mut skeleton_sections: HashMap<SkeletonState, Parser> = HashMap::new();
+        // Name of the "dfa" / "nfa" / "pda" object so it can be compared with the type
+        let mut automaton_section_name: Option<(String, Span)> = None;
+
+        while program_parser.can_consume() {
+            // Get the name of the skeleton section passed in
+            let name_span = program_parser.span();
+            let skeleton_state_name = program_parser.try_consume_name()?;
 
-        // Represents the order it expects the files
-        // skeleton should be in when parsed
-        let mut skeleton_states = [
+            // Read the separator
+            program_parser.try_consume_separator(Separator::COLUMN)?;
+
+            let section_parser = program_parser.try_consume_value()?;
+
+            match SkeletonState::from_string(&skeleton_state_name) {
+                Some(skeleton_state) if skeleton_state.is_top_level() => {
+                    match skeleton_sections.entry(skeleton_state) {
+                        Entry::Occupied(_) => issues.error(ParserError::new(
+                            DuplicateObjName,
+                            format!("Object name '{}' was already given", skeleton_state_name),
+                            name_span,
+                        )),
+                        Entry::Vacant(entry) => {
+                            entry.insert(section_parser);
+                        }
+                    }
+
+                    if skeleton_state == SkeletonState::AutomatonType {
+                        automaton_section_name = Some((skeleton_state_name, name_span));
+                    }
+                }
+                _ => issues.warning(
+                    format!(
+                        "Unrecognised object name '{}' is ignored",
+                        skeleton_state_name
+                    ),
+                    name_span,
+                ),
+            }
+
+            program_parser.try_consume_list_separator()?;
+        }
+
+        // Sections the file cannot leave out
+        for skeleton_state in [
             SkeletonState::Type,
-            SkeletonState::AutomatonType,
             SkeletonState::States,
             SkeletonState::Transitions,
-            SkeletonState::BulkTests,
-        ]
-        .iter();
+        ] {
+            if !skeleton_sections.contains_key(&skeleton_state) {
+                issues.error(ParserError::new(
+                    ObjNameNotFound,
+                    format!("The file is missing the {:?} object", skeleton_state),
+                    program_parser.end_span,
+                ));
+            }
+        }
 
-        // Vars used to build the automaton
-        let mut automaton_type: Option<AutomatonType> = None;
-        let mut state_list: Vec<Rc<State>> = vec![];
-        let mut accepting_strings: Vec<String> = vec![];
-        let mut rejecting_strings: Vec<String> = vec![];
+        let automaton_type = skeleton_sections
+            .remove(&SkeletonState::Type)
+            .and_then(|type_parser| issues.record(Self::parse_type(type_parser)));
 
-        // Start the parser
-        while skeleton_parser.can_consume() {
-            // Get the name of the skeleton section passed in
-            let skeleton_state =
-                Self::extract_skeleton_state_name(&mut skeleton_parser, &mut skeleton_states);
+        if let (Some(automaton_type), Some((section_name, section_name_span))) =
+            (automaton_type, automaton_section_name)
+        {
+            if !section_name.eq_ignore_ascii_case(&format!("{:?}", automaton_type)) {
+                issues.warning(
+                    format!(
+                        "Object '{}' does not match the type {:?} of the automaton",
+                        section_name, automaton_type
+                    ),
+                    section_name_span,
+                );
+            }
+        }
 
-            // Read the separator
-            skeleton_parser
-                .try_consume_separator(Separator::COLUMN)
-                .unwrap();
-
-            // Check the state and act accordingly
-            match skeleton_state {
-                SkeletonState::Type => {
-                    // Read the name of the automaton and match accordingly
-                    let automaton = skeleton_parser
-                        .try_consume_name()
-                        .unwrap()
-                        .to_ascii_uppercase();
-                    automaton_type = Some(match automaton.as_str() {
-                        "DFA" => DFA,
-                        "NFA" => NFA,
-                        "PDA" => PDA,
-                        _ => panic!("Could not figure out the name of the automaton being used"),
-                    });
+        let automaton_section = match skeleton_sections.remove(&SkeletonState::AutomatonType) {
+            Some(section_parser) => issues
+                .record(Self::parse_automaton_section(section_parser))
+                .unwrap_or_default(),
+            None => AutomatonSection::default(),
+        };
+
+        // Sections are parsed in this order as transitions can
+        // only be created after the states they connect
+        let state_list =
+            skeleton_sections
+                .remove(&SkeletonState::States)
+                .and_then(|states_parser| {
+                    let result = Self::parse_states(states_parser, &automaton_section, issues);
+                    issues.record(result)
+                });
+        let states_by_id = state_list.as_deref().map(State::map_by_id);
+
+        // States added between the steps of transitions which push more than one symbol
+        let mut step_states = vec![];
+        let alphabet = skeleton_sections
+            .remove(&SkeletonState::Transitions)
+            .and_then(|transitions_parser| {
+                let result = Self::parse_transitions(
+                    transitions_parser,
+                    automaton_type,
+                    states_by_id.as_ref(),
+                    &mut step_states,
+                    issues,
+                );
+                issues.record(result)
+            });
+        let state_list = state_list.map(|mut state_list| {
+            state_list.extend(step_states);
+            state_list
+        });
+
+        let tests = match skeleton_sections.remove(&SkeletonState::BulkTests) {
+            Some(bulk_test_parser) => {
+                let result = Self::parse_bulk_tests(bulk_test_parser, alphabet.as_ref(), issues);
+                issues.record(result).unwrap_or_default()
+            }
+            None => Tests::default(),
+        };
+
+        let (Some(automaton_type), Some(state_list), Some(states_by_id)) =
+            (automaton_type, state_list, states_by_id)
+        else {
+            return Ok(None);
+        };
+
+        // Files that leave out the start state use the id the site gives it,
+        // a start state that cannot be found was reported along with the accept states
+        let start_state_id = automaton_section.start_state.map_or_else(
+            || String::from("start"),
+            |(start_state_id, _)| start_state_id,
+        );
+        let Some(start_state) = states_by_id.get(&start_state_id).cloned() else {
+            return Ok(None);
+        };
+
+        // Build the final automaton
+        Ok(Some(Automaton::new(
+            automaton_type,
+            start_state,
+            state_list // Create a list of all accepting states
+                .iter()
+                .filter(|node| node.is_accept_state)
+                .cloned()
+                .collect::<Vec<Rc<State>>>(),
+            state_list,
+            tests,
+        )))
+    }
+
+    /// Parses the name of the automaton
+    ///
+    /// Eg: "type": "DFA"
+    pub(in crate::parser) fn parse_type(
+        mut type_parser: Parser,
+    ) -> Result<AutomatonType, ParserError> {
+        let type_span = type_parser.span();
+
+        // Read the name of the automaton and match accordingly
+        let automaton = type_parser.try_consume_name()?;
+        match automaton.to_ascii_uppercase().as_str() {
+            "DFA" => Ok(DFA),
+            "NFA" => Ok(NFA),
+            "PDA" => Ok(PDA),
+            _ => Err(ParserError::new(
+                ObjNameSyntaxErr,
+                format!(
+                    "Could not figure out the name of the automaton being used '{}'",
+                    automaton
+                ),
+                type_span,
+            )),
+        }
+    }
+
+    /// Parses the start and accept states from the object named after the automaton type
+    ///
+    /// Eg: "dfa": { "transitions": {}, "startState": "start", "acceptStates": ["s0"] }
+    ///
+    /// The transitions in this object are skipped as they are read from the outer "transitions" object
+    pub(in crate::parser) fn parse_automaton_section(
+        mut skeleton_parser: Parser,
+    ) -> Result<AutomatonSection, ParserError> {
+        let mut section_parser = skeleton_parser.try_consume_scope(Scope::CurlyBracket)?;
+        let mut automaton_section = AutomatonSection::default();
+
+        while section_parser.can_consume() {
+            let name = section_parser.try_consume_name()?;
+
+            section_parser.try_consume_separator(Separator::COLUMN)?;
+
+            match SkeletonState::from_string(&name) {
+                Some(SkeletonState::StartState) => {
+                    let start_state_span = section_parser.span();
+                    automaton_section.start_state =
+                        Some((section_parser.try_consume_name()?, start_state_span));
+                }
+                Some(SkeletonState::AcceptStates) => {
+                    let mut accept_parser = section_parser.try_consume_scope(Scope::BoxBracket)?;
+                    let mut accept_states = vec![];
+
+                    while accept_parser.can_consume() {
+                        let accept_state_span = accept_parser.span();
+                        accept_states.push((accept_parser.try_consume_name()?, accept_state_span));
+                        accept_parser.try_consume_list_separator()?;
+                    }
+
+                    automaton_section.accept_states = Some(accept_states);
                 }
-                SkeletonState::AutomatonType => {
-                    let _ = skeleton_parser
-                        .try_consume_scope(Scope::CurlyBracket)
-                        .unwrap();
+                _ => {
+                    section_parser.try_consume_value()?;
                 }
-                SkeletonState::States => {
-                    let mut state_parser = skeleton_parser
-                        .try_consume_scope(Scope::CurlyBracket)
-                        .unwrap();
-
-                    // Process each state
-                    let mut iter_count = 0;
-                    while state_parser.can_consume() {
-                        iter_count += 1;
-                        let state_name = state_parser.try_consume_name().unwrap();
-
-                        let mut position = Position { x: 0.0, y: 0.0 };
-                        let mut is_accept_state = false;
-                        let mut alt_name: Option<String> = None;
-
-                        state_parser
-                            .try_consume_separator(Separator::COLUMN)
-                            .unwrap();
-
-                        // Parse the state info
-
-                        let state_scope_parser =
-                            state_parser.try_consume_scope(Scope::CurlyBracket).unwrap();
-
-                        let mut display_id_cursor = state_scope_parser.cursor + 1;
-                        state_scope_parser
-                            .program_iter
-                            .split(',')
-                            .for_each(|line: &str| {
-                                let info: Vec<&str> = line.split(':').collect::<Vec<&str>>();
-
-                                /*  This is for a start states that is not an accepting state as
-                                 *  it has an empty scope
-                                 *  Eg: "start": {},
-                                 */
-                                if state_name != "start" {
-                                    assert_eq!(info.len(), 2, "Info for the node is not a pair for iter count {}", iter_count);
-                                    // Column + comma
-                                    display_id_cursor += 2 + (info[0].len() + info[1].len()) as u32;
-                                }
-
-
-                                match info[0] {
-                                    "\"isAccept\"" => is_accept_state = (*info[1]) == *"true",
-                                    "\"top\"" => position.y = (*info[1]).parse::<f64>().unwrap(),
-                                    "\"left\"" => position.x = (*info[1]).parse::<f64>().unwrap(),
-                                    "\"displayId\"" => alt_name = Some(Self::new(info[1].to_string())
-                                        // -1 used as there is no comma at the end
-                                        .set_counter(display_id_cursor - 1 - info[1].len() as u32)
-                                        .try_consume_name()
-                                        .map_err(|parser_error: ParserError| {
-                                            format!(
-                                                "Cannot parse name for displayId resulted in an error {}",
-                                                parser_error
-                                            )
-                                        })
-                                        .unwrap()),
-                                    _ => {
-                                        if state_name != "start" || !info[0].is_empty() {
-                                            panic!(
-                                                "Unrecognized state property '{}' for state_name '{}'",
-                                                info[0], state_name
-                                            )
-                                        }
-                                    }
-                                }
-                            });
-
-                        // Create the nodes and add them to a node list
-                        state_list.push(Rc::new(State::new(
-                            state_name.clone(),
-                            alt_name,
-                            position,
-                            is_accept_state,
-                            RefCell::new(vec![]),
-                        )));
-
-                        let _ = state_parser.try_consume_separator(Separator::COMMA);
+            }
+
+            section_parser.try_consume_list_separator()?;
+        }
+
+        Ok(automaton_section)
+    }
+
+    /// Parses each state and its info
+    ///
+    /// Eg: "states": { "start": {}, "s0": { "isAccept": true, "top": 12.0, "left": 8.5, "displayId": "q0" } }
+    ///
+    /// # Arguments
+    ///
+    /// * `skeleton_parser`: Parser over the states object
+    /// * `automaton_section`: The start and accept states listed in the "dfa" / "nfa" / "pda" object,
+    ///   when accept states are given they decide which states accept instead of each state's "isAccept"
+    /// * `issues`: Where problems found are added
+    fn parse_states(
+        mut skeleton_parser: Parser,
+        automaton_section: &AutomatonSection,
+        issues: &mut Issues,
+    ) -> Result<Vec<Rc<State>>, ParserError> {
+        let states_span = skeleton_parser.span();
+        let mut state_parser = skeleton_parser.try_consume_scope(Scope::CurlyBracket)?;
+        let mut state_list: Vec<Rc<State>> = vec![];
+        let mut marked_states: HashMap<String, (bool, Span)> = HashMap::new();
+
+        // Process each state
+        while state_parser.can_consume() {
+            let state_span = state_parser.span();
+            let state_name = state_parser.try_consume_name()?;
+
+            let is_duplicate = marked_states.contains_key(&state_name);
+            if is_duplicate {
+                issues.error(ParserError::new(
+                    DuplicateObjName,
+                    format!("State id '{}' is given more than once", state_name),
+                    state_span,
+                ));
+            }
+
+            let mut position = Position { x: 0.0, y: 0.0 };
+            let mut is_accept_state = false;
+            let mut alt_name: Option<String> = None;
+
+            state_parser.try_consume_separator(Separator::COLUMN)?;
+
+            // Parse the state info
+            /*  A start state that is not an accepting state has an empty scope
+             *  Eg: "start": {},
+             */
+            let mut state_info_parser = state_parser.try_consume_scope(Scope::CurlyBracket)?;
+
+            while state_info_parser.can_consume() {
+                let property_span = state_info_parser.span();
+                let property = state_info_parser.try_consume_name()?;
+
+                state_info_parser.try_consume_separator(Separator::COLUMN)?;
+
+                match property.as_str() {
+                    "isAccept" => is_accept_state = state_info_parser.try_consume_bool()?,
+                    "top" => position.y = state_info_parser.try_consume_number()?,
+                    "left" => position.x = state_info_parser.try_consume_number()?,
+                    "displayId" => alt_name = Some(state_info_parser.try_consume_name()?),
+                    _ => {
+                        issues.warning(
+                            format!(
+                                "Unrecognised property '{}' of state '{}' is ignored",
+                                property, state_name
+                            ),
+                            property_span,
+                        );
+                        state_info_parser.try_consume_value()?;
                     }
                 }
-                SkeletonState::Transitions => {
-                    let mut transition_scope_parser = skeleton_parser
-                        .try_consume_scope(Scope::BoxBracket)
-                        .unwrap();
-
-                    let mut state_a: Rc<State>;
-                    let mut state_b: Rc<State>;
-                    let mut label: Vec<char>;
-
-                    let mut transition_iter_count = 1;
-                    // Parse each transition
-                    while transition_scope_parser.can_consume() {
-                        // Get the info of the transition parsing
-                        let mut transition_parser = transition_scope_parser
-                            .try_consume_scope(Scope::CurlyBracket)
-                            .unwrap();
-
-                        let transition = transition_parser.try_consume_name().unwrap();
-                        if "stateA" == transition // PDA name 
-                            || "state_a" == transition
-                        // DFA | NFA name
-                        {
-                            transition_parser
-                                .try_consume_separator(Separator::COLUMN)
-                                .unwrap();
-
-                            let state_a_name = transition_parser.try_consume_name().unwrap();
-
-                            // Find the state specified in the transition from the list of states
-                            state_a = State::find_state_by_id(&state_list, state_a_name.as_str())
-                                .expect(&format!(
-                                    "Cannot find state_a referenced in transition {}",
-                                    state_a_name.as_str()
-                                ))
-                        } else {
-                            panic!(
-                                "Missing state_a transition name for transition. On iteration count {}",
-                                transition_iter_count
-                            )
-                        }
 
-                        let _ = transition_parser.try_consume_separator(Separator::COMMA);
-
-                        if "label" == transition_parser.try_consume_name().unwrap().as_str() {
-                            transition_parser
-                                .try_consume_separator(Separator::COLUMN)
-                                .unwrap();
-
-                            // Parse the separator used
-                            label = transition_parser
-                                .try_consume_name()
-                                .unwrap()
-                                .split(',')
-                                .into_iter()
-                                .map(|str| {
-                                    str.chars().nth(0).expect(&format!(
-                                        "Cannot have an empty transition. On iteration count {}",
-                                        transition_iter_count
-                                    ))
-                                })
-                                .collect::<Vec<char>>();
-
-                            // Check if the label is either a (DFA|NFA) Or a PDA
-                            assert!(
-                                label.len() == 1 || label.len() == 3,
-                                "Cannot recognise transition label used. On iteration count {}",
-                                transition_iter_count
-                            );
-                        } else {
-                            panic!(
-                                "Missing label transition name for transition. On iteration count {}",
-                                transition_iter_count
-                            )
-                        }
+                state_info_parser.try_consume_list_separator()?;
+            }
+
+            if !is_duplicate {
+                marked_states.insert(state_name.clone(), (is_accept_state, state_span));
+
+                if let Some(accept_states) = &automaton_section.accept_states {
+                    is_accept_state = accept_states
+                        .iter()
+                        .any(|(accept_state, _)| *accept_state == state_name);
+                }
 
-                        let _ = transition_parser.try_consume_separator(Separator::COMMA);
+                // Create the nodes and add them to a node list
+                state_list.push(Rc::new(State::new(
+                    state_name,
+                    alt_name,
+                    position,
+                    is_accept_state,
+                    RefCell::new(vec![]),
+                )));
+            }
+
+            state_parser.try_consume_list_separator()?;
+        }
+
+        Self::validate_start_and_accept_states(
+            automaton_section,
+            &marked_states,
+            states_span,
+            issues,
+        );
+
+        Ok(state_list)
+    }
 
-                        let transition = transition_parser.try_consume_name().unwrap();
-                        if "stateB" == transition  // PDA name  
-                            || "state_b" == transition
-                        // NFA | DFA name
+    /// Parses each transition adding it to the state it leaves from, and checks that a DFA
+    /// never has a choice of transitions
+    ///
+    /// Eg: "transitions": [ { "stateA": "start", "label": "a", "stateB": "s0" } ]
+    ///
+    /// Returns the alphabet of input symbols used by the transitions
+    ///
+    /// # Arguments
+    ///
+    /// * `skeleton_parser`: Parser over the list of transitions
+    /// * `automaton_type`: The type the file declares, if it could be read
+    /// * `states_by_id`: All states that transitions can reference, if they could be read
+    /// * `issues`: Where problems found are added
+    fn parse_transitions(
+        mut skeleton_parser: Parser,
+        automaton_type: Option<AutomatonType>,
+        states_by_id: Option<&HashMap<String, Rc<State>>>,
+        step_states: &mut Vec<Rc<State>>,
+        issues: &mut Issues,
+    ) -> Result<HashSet<char>, ParserError> {
+        let mut transition_scope_parser = skeleton_parser.try_consume_scope(Scope::BoxBracket)?;
+        let mut alphabet = HashSet::new();
+        // Input symbols each state of a DFA already has a transition on
+        let mut dfa_transitions: HashSet<(String, Symbol)> = HashSet::new();
+
+        // Parse each transition
+        while transition_scope_parser.can_consume() {
+            // Get the info of the transition parsing
+            let transition_span = transition_scope_parser.span();
+            let mut transition_parser =
+                transition_scope_parser.try_consume_scope(Scope::CurlyBracket)?;
+
+            let mut state_a: Option<String> = None;
+            let mut state_b: Option<String> = None;
+            let mut label: Option<(String, Span)> = None;
+
+            while transition_parser.can_consume() {
+                let property_span = transition_parser.span();
+                let transition = transition_parser.try_consume_name()?;
+
+                transition_parser.try_consume_separator(Separator::COLUMN)?;
+
+                match transition.as_str() {
+                    // PDA name | DFA & NFA name
+                    "stateA" | "state_a" | "stateB" | "state_b" => {
+                        let state_span = transition_parser.span();
+                        let state_name = transition_parser.try_consume_name()?;
+
+                        // Find the state specified in the transition from the list of states
+                        if states_by_id
+                            .is_some_and(|states_by_id| !states_by_id.contains_key(&state_name))
                         {
-                            transition_parser
-                                .try_consume_separator(Separator::COLUMN)
-                                .unwrap();
-
-                            let state_b_name = transition_parser.try_consume_name().unwrap();
-
-                            // Find the state specified in the transition from the list of states
-                            state_b = State::find_state_by_id(&state_list, state_b_name.as_str())
-                                .expect(&format!(
-                                    "Cannot find state_b referenced in transition {}. On iteration count {}",
-                                    state_b_name.as_str(), transition_iter_count
-                                ))
-                        } else {
-                            panic!(
-                                "Missing state_b transition name for transition. On iteration count {}",
-                                transition_iter_count
-                            )
+                            issues.error(ParserError::new(
+                                UnknownState,
+                                format!(
+                                    "Cannot find state '{}' referenced by {} in transition",
+                                    state_name, transition
+                                ),
+                                state_span,
+                            ));
                         }
 
-                        // Resolve label symbols
-                        let mut push: Option<Symbol> = None;
-                        let mut symbol: Symbol;
-                        let mut pop: Option<Symbol> = None;
+                        if transition.ends_with('A') || transition.ends_with('a') {
+                            state_a = Some(state_name);
+                        } else {
+                            state_b = Some(state_name);
+                        }
+                    }
+                    "label" => {
+                        let label_span = transition_parser.span();
+                        label = Some((transition_parser.try_consume_name()?, label_span));
+                    }
+                    _ => {
+                        issues.warning(
+                            format!(
+                                "Unrecognised transition property '{}' is ignored",
+                                transition
+                            ),
+                            property_span,
+                        );
+                        transition_parser.try_consume_value()?;
+                    }
+                }
 
-                        // Applicable for NFAs, DFAs & PDAs
+                transition_parser.try_consume_list_separator()?;
+            }
 
-                        symbol = if label[0] == char::from(Symbol::EPSILON) {
-                            Symbol::EPSILON
-                        } else {
-                            Symbol::CHAR(label[0])
-                        };
+            for (property, is_missing) in [
+                ("state_a", state_a.is_none()),
+                ("label", label.is_none()),
+                ("state_b", state_b.is_none()),
+            ] {
+                if is_missing {
+                    issues.error(ParserError::new(
+                        UnknownTransitionName,
+                        format!("Missing {} transition name for transition", property),
+                        transition_span,
+                    ));
+                }
+            }
 
-                        // For PDAs only
-                        if label.len() == 3 {
-                            symbol = if label[0] == char::from(Symbol::EPSILON) {
-                                Symbol::EPSILON
-                            } else {
-                                Symbol::CHAR(label[0])
-                            };
-                            pop = Some(if label[1] == char::from(Symbol::EPSILON) {
-                                Symbol::EPSILON
-                            } else {
-                                Symbol::CHAR(label[1])
-                            });
-                            push = Some(if label[2] == char::from(Symbol::EPSILON) {
-                                Symbol::EPSILON
-                            } else {
-                                Symbol::CHAR(label[2])
-                            });
+            let label = match label {
+                Some((label, label_span)) => {
+                    match Self::parse_label(&label, label_span, automaton_type) {
+                        Ok(symbols) => Some((symbols, label_span)),
+                        Err(err) => {
+                            issues.error(err);
+                            None
                         }
+                    }
+                }
+                None => None,
+            };
+
+            if let Some((steps, label_span)) = &label {
+                // Only the first step of a transition reads a symbol
+                let symbol = steps[0].0;
+                if let Symbol::CHAR(c) = symbol {
+                    alphabet.insert(c);
+                }
 
-                        // Create the transition
-                        state_a.add_transition(Transition::new(state_b, symbol, pop, push));
-
-                        let _ = transition_scope_parser.try_consume_separator(Separator::COMMA);
-                        transition_iter_count += 1;
+                if matches!(automaton_type, Some(DFA)) {
+                    if symbol == Symbol::EPSILON {
+                        issues.error(ParserError::new(
+                            InvalidLabel,
+                            String::from("A DFA cannot have a transition on ϵ"),
+                            *label_span,
+                        ));
+                    } else if let Some(state_a) = &state_a {
+                        if !dfa_transitions.insert((state_a.clone(), symbol)) {
+                            issues.error(ParserError::new(
+                                NotDeterministic,
+                                format!(
+                                    "State '{}' has more than one transition on '{}' so the DFA is not deterministic",
+                                    state_a, symbol
+                                ),
+                                *label_span,
+                            ));
+                        }
                     }
                 }
-                SkeletonState::BulkTests => {
-                    let mut bulk_test_parser = skeleton_parser
-                        .try_consume_scope(Scope::CurlyBracket)
-                        .unwrap();
-
-                    // Find all accepting strings
-                    accepting_strings =
-                        if "accept" == bulk_test_parser.try_consume_name().unwrap().as_str() {
-                            bulk_test_parser
-                                .try_consume_separator(Separator::COLUMN)
-                                .unwrap();
-
-                            // Parse accepting strings
-                            bulk_test_parser
-                                .try_consume_name()
-                                .unwrap()
-                                .split("\\n")
-                                .map(|accepting_strings| String::from(accepting_strings))
-                                .collect::<Vec<String>>()
-                        } else {
-                            panic!("No Accepting strings found")
-                        };
+            }
 
-                    let _ = bulk_test_parser.try_consume_separator(Separator::COMMA);
-
-                    // Find all rejecting strings
-                    rejecting_strings =
-                        if "reject" == bulk_test_parser.try_consume_name().unwrap().as_str() {
-                            bulk_test_parser
-                                .try_consume_separator(Separator::COLUMN)
-                                .unwrap();
-
-                            // Parse rejecting strings
-                            bulk_test_parser
-                                .try_consume_name()
-                                .unwrap()
-                                .split("\\n")
-                                .map(|rejecting_strings| String::from(rejecting_strings))
-                                .collect::<Vec<String>>()
+            // Create the transition, going through a new state for each extra step
+            if let (Some(state_a), Some(state_b), Some((steps, _)), Some(states)) =
+                (state_a, state_b, label, states_by_id)
+            {
+                if let (Some(from), Some(to)) = (states.get(&state_a), states.get(&state_b)) {
+                    let mut step_from = from.clone();
+                    for (step, &(symbol, pop, push)) in steps.iter().enumerate() {
+                        let step_to = if step + 1 == steps.len() {
+                            to.clone()
                         } else {
-                            panic!("No rejecting strings found")
+                            let mut id = format!("{}'", step_from.id);
+                            while states.contains_key(&id)
+                                || step_states.iter().any(|state| state.id == id)
+                            {
+                                id.push('\'');
+                            }
+                            let progress = (step + 1) as f64 / steps.len() as f64;
+                            let state = Rc::new(State::new(
+                                id,
+                                None,
+                                Position {
+                                    x: from.position.x
+                                        + (to.position.x - from.position.x) * progress,
+                                    y: from.position.y
+                                        + (to.position.y - from.position.y) * progress,
+                                },
+                                false,
+                                RefCell::new(vec![]),
+                            ));
+                            step_states.push(state.clone());
+                            state
                         };
+
+                        step_from.add_transition(Transition::new(
+                            step_to.clone(),
+                            symbol,
+                            pop,
+                            push,
+                        ));
+                        step_from = step_to;
+                    }
                 }
-                _ => panic!("User state can't be matched for {:?}", skeleton_state),
             }
 
-            let _ = skeleton_parser.try_consume_separator(Separator::COMMA);
+            transition_scope_parser.try_consume_list_separator()?;
         }
 
-        // Final checks
-        {
-            assert_eq!(
-                skeleton_parser.program_iter.len(),
-                0,
-                "Parser was not empty after reading the program"
-            );
-
-            assert_eq!(
-                skeleton_states.next(),
-                None,
-                "Not all fields were provided in the file"
-            );
+        Ok(alphabet)
+    }
+
+    /// Resolves the symbols in a transition label returning the symbol transitioned on
+    /// along with the pop and push symbols for PDAs, for each step the transition is taken in
+    ///
+    /// Eg: "a" for DFAs & NFAs or "a,ϵ,X" for PDAs. A PDA label pushing several symbols, such as
+    /// "a,ϵ,XY", is taken in steps that push Y and then ϵ,ϵ,X so X ends on top
+    ///
+    /// # Arguments
+    ///
+    /// * `label`: The label to resolve
+    /// * `label_span`: Where the label is found in the program
+    /// * `automaton_type`: The type the label belongs to, when unknown either kind of label is read
+    pub(in crate::parser) fn parse_label(
+        label: &str,
+        label_span: Span,
+        automaton_type: Option<AutomatonType>,
+    ) -> Result<Vec<LabelStep>, ParserError> {
+        // Parse the separator used
+        let symbols = label.split(',').collect::<Vec<&str>>();
+
+        if symbols.iter().any(|symbol| symbol.is_empty()) {
+            return Err(ParserError::new(
+                InvalidLabel,
+                format!("Cannot have an empty transition in label '{}'", label),
+                label_span,
+            ));
         }
 
-        if false {
-            println!();
-            dbg!(&accepting_strings);
-            dbg!(&rejecting_strings);
-            dbg!(&state_list);
-            dbg!(skeleton_parser.cursor);
-            dbg!(state_list.len());
+        // Check if the label is either a (DFA|NFA) Or a PDA
+        match automaton_type {
+            Some(automaton_type) => {
+                let expected_symbols = if matches!(automaton_type, PDA) { 3 } else { 1 };
+                if symbols.len() != expected_symbols {
+                    return Err(ParserError::new(
+                        InvalidLabel,
+                        format!(
+                            "Label '{}' has {} symbols but a {:?} label needs {}",
+                            label,
+                            symbols.len(),
+                            automaton_type,
+                            expected_symbols
+                        ),
+                        label_span,
+                    ));
+                }
+            }
+            None if symbols.len() != 1 && symbols.len() != 3 => {
+                return Err(ParserError::new(
+                    InvalidLabel,
+                    format!("Cannot recognise transition label used '{}'", label),
+                    label_span,
+                ))
+            }
+            None => {}
         }
 
-        // Build the final automaton
+        // Every symbol is a single character apart from the symbols a PDA pushes
+        let single_symbol = |symbol: &str| {
+            let mut chars = symbol.chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), None) => Ok(Symbol::from(c)),
+                _ => Err(ParserError::new(
+                    InvalidLabel,
+                    format!(
+                        "Symbols are single characters, cannot use '{}' in label '{}'",
+                        symbol, label
+                    ),
+                    label_span,
+                )),
+            }
+        };
+
+        Ok(match symbols[..] {
+            [symbol, pop, push] => {
+                // The leftmost symbol pushed ends on top of the stack so it is pushed last
+                let mut pushes = push.chars().rev().map(Symbol::from);
+                let mut steps = vec![(
+                    single_symbol(symbol)?,
+                    Some(single_symbol(pop)?),
+                    pushes.next(),
+                )];
+                steps.extend(pushes.map(|push_symbol| {
+                    (Symbol::EPSILON, Some(Symbol::EPSILON), Some(push_symbol))
+                }));
+                steps
+            }
+            _ => vec![(single_symbol(symbols[0])?, None, None)],
+        })
+    }
 
-        Automaton::new(
-            // This should never fail as long as the skeleton_sate is correctly implemented
-            // and the match on sate_type is also correct
-            automaton_type.expect("Automaton type was never set"),
-            State::find_state_by_id(&state_list, "start").expect("No Start state found"),
-            state_list // Create a list of all accepting states
-                .iter()
-                .filter(|node| node.is_accept_state)
-                .cloned()
-                .collect::<Vec<Rc<State>>>(),
-            state_list,
-            Tests {
-                accepting_strings,
-                rejecting_strings,
-            },
-        )
+    /// Parses the strings the automaton is expected to accept and reject
+    ///
+    /// Eg: "bulkTests": { "accept": "a\naa", "reject": "b" }
+    ///
+    /// # Arguments
+    ///
+    /// * `skeleton_parser`: Parser over the bulk tests object
+    /// * `alphabet`: The input symbols of the automaton, test strings are only
+    ///   checked against it when every transition could be read
+    /// * `issues`: Where problems found are added
+    fn parse_bulk_tests(
+        mut skeleton_parser: Parser,
+        alphabet: Option<&HashSet<char>>,
+        issues: &mut Issues,
+    ) -> Result<Tests, ParserError> {
+        let mut bulk_test_parser = skeleton_parser.try_consume_scope(Scope::CurlyBracket)?;
+        let mut tests = Tests::default();
+
+        while bulk_test_parser.can_consume() {
+            let test_kind_span = bulk_test_parser.span();
+            let test_kind = bulk_test_parser.try_consume_name()?;
+
+            bulk_test_parser.try_consume_separator(Separator::COLUMN)?;
+
+            match test_kind.as_str() {
+                // Find all accepting | rejecting strings
+                "accept" | "reject" => {
+                    let tests_span = bulk_test_parser.span();
+                    let test_strings = bulk_test_parser.try_consume_test_strings()?;
+
+                    for test_string in test_strings.iter() {
+                        let unknown_symbols = test_string
+                            .chars()
+                            .filter(|c| alphabet.is_some_and(|alphabet| !alphabet.contains(c)))
+                            .collect::<BTreeSet<char>>();
+
+                        if !unknown_symbols.is_empty() {
+                            issues.warning(
+                                format!(
+                                    "Test string '{}' uses symbols {:?} that are not in the alphabet",
+                                    test_string, unknown_symbols
+                                ),
+                                tests_span,
+                            );
+                        }
+                    }
+
+                    if test_kind == "accept" {
+                        tests.accepting_strings = test_strings;
+                    } else {
+                        tests.rejecting_strings = test_strings;
+                    }
+                }
+                _ => {
+                    issues.warning(
+                        format!("Unrecognised bulk test '{}' is ignored", test_kind),
+                        test_kind_span,
+                    );
+                    bulk_test_parser.try_consume_value()?;
+                }
+            }
+
+            bulk_test_parser.try_consume_list_separator()?;
+        }
+
+        Ok(tests)
     }
 
-    /// Creates a new Parser from a program string
-    fn new(program: String) -> Parser {
+    /// Creates a new Parser over a list of tokens
+    ///
+    /// # Arguments
+    ///
+    /// * `tokens`: Tokens to parse
+    /// * `end_span`: Where the tokens end in the program
+    pub(in crate::parser) fn new(tokens: Vec<Token>, end_span: Span) -> Parser {
         Parser {
-            program_iter: program,
+            tokens,
             cursor: 0,
+            end_span,
         }
     }
 
-    fn set_counter(mut self, val: u32) -> Self {
-        self.cursor = val;
-        self
+    /// Checks if the parser can still be used
+    pub(in crate::parser) fn can_consume(&self) -> bool {
+        self.cursor < self.tokens.len()
     }
 
-    /// Checks if the parser can still be used
-    fn can_consume(&self) -> bool {
-        self.program_iter.len() != 0
+    /// Returns the next token without consuming it
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.cursor)
     }
 
-    /// Extracts object name from the parser
-    fn extract_skeleton_state_name(
-        parser: &mut Parser,
-        obj_name_state: &mut Iter<SkeletonState>,
-    ) -> SkeletonState {
-        let skeleton_state_name = parser.try_consume_name().unwrap();
-
-        // Find the current state to parse from the input
-        let skeleton_state = SkeletonState::from_string(&skeleton_state_name).expect(&format!(
-            "{}",
-            ObjNameNotFound(format!(
-                "Object name found is not a recognisable. Object name found is '{}'",
-                skeleton_state_name
-            ))
-        ));
-
-        // Find the expected state to parse
-        let parsers_expected_state = obj_name_state.next()
-            .expect(&format!("{}", ObjNameOverFlow(format!(
-                "Tried to parse object name but has now exceeded number of states parsable state tried parsing was {}", skeleton_state_name
-            ))));
-
-        // Makes sure states match
-        assert_eq!(
-            skeleton_state,
-            *parsers_expected_state,
-            "{}",
-            ObjNameMismatch(format!(
-                "Object name trying to parse differs from the expected\n\
-Expected : {:?}\n\
-Found: {:?}",
-                *parsers_expected_state, skeleton_state
-            ))
-        );
+    /// Returns where the next token to be read is found in the program
+    pub(in crate::parser) fn span(&self) -> Span {
+        self.peek().map_or(self.end_span, |token| token.span)
+    }
 
-        skeleton_state
+    /// Creates an error found at the next token to be read
+    fn error(&self, kind: ParserErrorKind, message: String) -> ParserError {
+        ParserError::new(kind, message, self.span())
     }
 
     /// Try to consume the name of the objet to be parsed
@@ -475,166 +809,462 @@ Found: {:?}",
     /// Eg: "type": "PDA",
     ///
     /// "type" in this case is the object name
-    fn try_consume_name(&mut self) -> Result<String, ParserError> {
-        let quotation_marks = '"';
-        let mut prog_iter = self.program_iter.chars().peekable();
-
-        // Tries to consume the starting quotation marks
-        let first_char = prog_iter
-            .next()
-            .ok_or(OutOfInput(
-                "Tried to read object name but no input is found".to_string(),
-            ))
-            .unwrap();
-
-        // Validate that you can start reading the name of the object
-        if first_char != quotation_marks {
-            return Err(NoObjName(format!(
-                "Expected to parse a quotation but found \"{}\" at index {}",
-                first_char, self.cursor
-            )));
+    pub(in crate::parser) fn try_consume_name(&mut self) -> Result<String, ParserError> {
+        let token = self.peek().ok_or_else(|| {
+            self.error(
+                OutOfInput,
+                String::from("Tried to read object name but no input is found"),
+            )
+        })?;
+
+        match &token.kind {
+            TokenKind::String(name) => {
+                let name = name.clone();
+                self.cursor += 1;
+                Ok(name)
+            }
+            TokenKind::OpenScope(_) | TokenKind::CloseScope(_) | TokenKind::Separator(_) => {
+                Err(self.error(
+                    NoObjName,
+                    format!("Expected to parse a quotation but found {:?}", token.kind),
+                ))
+            }
+            _ => Err(self.error(
+                ObjNameSyntaxErr,
+                format!(
+                    "Expected a name in quotation marks but found {:?}",
+                    token.kind
+                ),
+            )),
         }
+    }
 
-        // Len of the remaining prog to parse
-        let remaining_prog_len = self.program_iter.len() - 1;
-
-        // Create object name and consumes the closing quotes
-        // reason why? refer to
-        // https://www.reddit.com/r/rust/comments/x05yn5/make_take_while_not_consume_last_element_where_it/
-        let string_retrieved = prog_iter
-            .by_ref()
-            .take_while(|&c| c != quotation_marks)
-            .collect::<String>();
-
-        // + 2 for the open and closing quotation
-        let next_cursor_pos = self.cursor + string_retrieved.len() as u32 + 2;
-
-        // No closing quotation was found
-        if string_retrieved.len() == remaining_prog_len {
-            Err(ObjNameSyntaxErr(format!(
-                "Missing object name where expected. Error at prog index range {:?} But found {}",
-                [(self.cursor)..(next_cursor_pos)],
-                string_retrieved
-            )))
-        } else {
-            // If parsing succeeded
-            // Replace the string with the leftover strings in the iterator
-            self.program_iter = prog_iter.collect::<String>();
-
-            // Advance the cursor read
-            self.cursor = next_cursor_pos;
-
-            Ok(string_retrieved)
+    /// Consumes a string of test strings separated by new lines, where an empty field has none
+    ///
+    /// Eg: "a\naa\n" gives "a", "aa" and "" while "" gives no test strings
+    fn try_consume_test_strings(&mut self) -> Result<Vec<String>, ParserError> {
+        let test_strings = self.try_consume_name()?;
+        if test_strings.is_empty() {
+            return Ok(vec![]);
         }
+
+        Ok(test_strings
+            .split('\n')
+            .map(String::from)
+            .collect::<Vec<String>>())
     }
 
-    /// Consumes a [Separator] from the input
-    fn try_consume_separator(&mut self, separator: Separator) -> Result<(), ParserError> {
-        let mut prog_iter = self.program_iter.chars();
+    /// Consumes a number from the input
+    ///
+    /// Eg: "top": 238.333333
+    pub(in crate::parser) fn try_consume_number(&mut self) -> Result<f64, ParserError> {
+        match self.peek().map(|token| &token.kind) {
+            Some(TokenKind::Number(number)) => {
+                let number = *number;
+                self.cursor += 1;
+                Ok(number)
+            }
+            kind => Err(self.error(
+                ObjNameSyntaxErr,
+                format!("Expected a number but found {:?}", kind),
+            )),
+        }
+    }
+
+    /// Consumes a true or false value from the input
+    ///
+    /// Eg: "isAccept": true
+    pub(in crate::parser) fn try_consume_bool(&mut self) -> Result<bool, ParserError> {
+        match self.peek().map(|token| &token.kind) {
+            Some(TokenKind::Bool(value)) => {
+                let value = *value;
+                self.cursor += 1;
+                Ok(value)
+            }
+            kind => Err(self.error(
+                ObjNameSyntaxErr,
+                format!("Expected true or false but found {:?}", kind),
+            )),
+        }
+    }
 
-        if let Some(c) = prog_iter.next() {
-            if c == separator.into() {
-                self.program_iter = prog_iter.collect::<String>();
+    /// Consumes a [Separator] from the input
+    pub(in crate::parser) fn try_consume_separator(
+        &mut self,
+        separator: Separator,
+    ) -> Result<(), ParserError> {
+        if let Some(TokenKind::Separator(found)) = self.peek().map(|token| &token.kind) {
+            if *found == separator {
                 self.cursor += 1;
                 return Ok(());
             }
         }
 
-        Err(MissingObjSeparator(format!(
-            "Missing separator {:?} found at prog_index {}",
-            separator, self.cursor
-        )))
+        Err(self.error(
+            MissingObjSeparator,
+            format!("Missing separator '{}'", Into::<char>::into(separator)),
+        ))
+    }
+
+    /// Consumes the comma between two items of a scope if there are still items left to read
+    pub(in crate::parser) fn try_consume_list_separator(&mut self) -> Result<(), ParserError> {
+        if self.can_consume() {
+            self.try_consume_separator(Separator::COMMA)?;
+        }
+        Ok(())
     }
 
     /// Consumes a scope returning a parser that iterates over its contents
     /// Scope is anything withing the brackets described in the [Scope] struct
-    fn try_consume_scope(&mut self, scope: Scope) -> Result<Parser, ParserError> {
-        let mut prog_iter = self.program_iter.chars().peekable();
-
-        // Tries to consume the starting quotation marks
-        let opening_scope_char = prog_iter
-            .next()
-            .ok_or(ScopeError(
-                "Tried to parse scope, but no input was found".to_string(),
-            ))
-            .unwrap();
-
-        // Validate that you can start reading the name of the object
-        if opening_scope_char != scope.into() {
-            return Err(ScopeError(format!(
-                "Expected to parse an opening scope {:?} but found '{}' at index {}",
-                scope, opening_scope_char, self.cursor
-            )));
+    pub(in crate::parser) fn try_consume_scope(
+        &mut self,
+        scope: Scope,
+    ) -> Result<Parser, ParserError> {
+        let opening_scope = self.peek().ok_or_else(|| {
+            self.error(
+                ScopeError,
+                format!(
+                    "Tried to parse scope '{}', but no input was found",
+                    Into::<char>::into(scope)
+                ),
+            )
+        })?;
+
+        // Validate that you can start reading the scope
+        if opening_scope.kind != TokenKind::OpenScope(scope) {
+            return Err(self.error(
+                ScopeError,
+                format!(
+                    "Expected to parse an opening scope '{}' but found {:?}",
+                    Into::<char>::into(scope),
+                    opening_scope.kind
+                ),
+            ));
         }
 
-        // Len of the remaining prog to parse
-        let remaining_prog_len = self.program_iter.len() - 1;
-
-        let mut scope_counter = 1; // Initialized at 1 because the opening scope has been read
-                                   // Collects scope contents
-        let inner_scope_content = prog_iter
-            .by_ref()
-            .take_while(|&c| {
-                if c == scope.into() {
-                    scope_counter += 1;
-                    true // Cannot close scope here
-                } else if c == scope.closing() {
-                    scope_counter -= 1;
-                    // Possible that this can be a final closing bracket
-                    scope_counter != 0
-                } else {
-                    true // Other characters
+        let start_scope_cursor = self.cursor + 1;
+        let end_scope_cursor = self.find_scope_end()?;
+
+        // Move past the closing scope
+        self.cursor = end_scope_cursor;
+
+        Ok(Parser::new(
+            self.tokens[start_scope_cursor..end_scope_cursor - 1].to_vec(),
+            self.tokens[end_scope_cursor - 1].span,
+        ))
+    }
+
+    /// Consumes any single value, returning a parser over it
+    ///
+    /// Eg: "PDA", 12.5, true or a whole scope such as {"accept": ""}
+    pub(in crate::parser) fn try_consume_value(&mut self) -> Result<Parser, ParserError> {
+        let token = self.peek().ok_or_else(|| {
+            self.error(
+                OutOfInput,
+                String::from("Tried to read a value but no input is found"),
+            )
+        })?;
+
+        let end_value_cursor = match token.kind {
+            TokenKind::OpenScope(_) => self.find_scope_end()?,
+            TokenKind::CloseScope(_) | TokenKind::Separator(_) => {
+                return Err(self.error(
+                    ObjNameSyntaxErr,
+                    format!("Expected a value but found {:?}", token.kind),
+                ))
+            }
+            _ => self.cursor + 1,
+        };
+
+        let value = Parser::new(
+            self.tokens[self.cursor..end_value_cursor].to_vec(),
+            self.tokens[end_value_cursor - 1].span,
+        );
+        self.cursor = end_value_cursor;
+
+        Ok(value)
+    }
+
+    /// Finds the index right after the token closing the scope that opens at the cursor
+    fn find_scope_end(&self) -> Result<usize, ParserError> {
+        // Keeps track of the scopes opened inside this scope so that they are closed in order
+        let mut open_scopes: Vec<(Scope, Span)> = vec![];
+        let mut end_scope_cursor = self.cursor;
+
+        loop {
+            let token = match self.tokens.get(end_scope_cursor) {
+                Some(token) => token,
+                None => {
+                    let (scope, scope_span) = open_scopes
+                        .pop()
+                        .unwrap_or((Scope::CurlyBracket, self.span()));
+                    return Err(ParserError::new(
+                        ScopeError,
+                        format!(
+                            "No closing scope '{}' was found for this scope",
+                            scope.closing()
+                        ),
+                        scope_span,
+                    ));
+                }
+            };
+            end_scope_cursor += 1;
+
+            match token.kind {
+                TokenKind::OpenScope(inner_scope) => open_scopes.push((inner_scope, token.span)),
+                TokenKind::CloseScope(closing_scope) => {
+                    let (expected_scope, _) = open_scopes.pop().ok_or_else(|| {
+                        ParserError::new(
+                            ScopeError,
+                            format!("Unexpected closing scope '{}'", closing_scope.closing()),
+                            token.span,
+                        )
+                    })?;
+                    if closing_scope != expected_scope {
+                        return Err(ParserError::new(
+                            ScopeError,
+                            format!(
+                                "Expected closing scope '{}' but found '{}'",
+                                expected_scope.closing(),
+                                closing_scope.closing()
+                            ),
+                            token.span,
+                        ));
+                    }
                 }
-            })
-            .collect::<String>();
+                _ => {}
+            }
 
-        // A snapshot of the previous cursor position before reading the scope
-        let start_scope_cursor = self.cursor;
+            if open_scopes.is_empty() {
+                return Ok(end_scope_cursor);
+            }
+        }
+    }
 
-        // Retrieve the remaining program after the scope
-        self.program_iter = prog_iter.collect::<String>();
+    /// Breaks the program into tokens returning a parser over the contents of its outermost scope
+    pub(in crate::parser) fn prog_preprocessor(program: &str) -> Result<Parser, ParserError> {
+        let tokens = Lexer::tokenize(program)?;
 
-        // + 2 for the open and closing scope
-        self.cursor += inner_scope_content.len() as u32 + 2;
+        // Errors past the last token point right after it
+        let end_span = tokens.last().map_or(
+            Span {
+                line: 1,
+                column: 1,
+                len: 1,
+            },
+            |token| Span {
+                column: token.span.column + token.span.len,
+                len: 1,
+                ..token.span
+            },
+        );
+
+        let mut program_parser = Parser::new(tokens, end_span);
+        let new_prog = program_parser.try_consume_scope(Scope::CurlyBracket)?;
+
+        if program_parser.can_consume() {
+            return Err(program_parser.error(
+                ScopeError,
+                String::from("Unexpected input after the closing curly braces"),
+            ));
+        }
 
-        // Missing end scope
-        if inner_scope_content.len() == remaining_prog_len {
-            return Err(ScopeError("No closing scope was found".parse().unwrap()));
+        if !new_prog.can_consume() {
+            return Err(new_prog.error(
+                OutOfInput,
+                String::from("Invalid program as Program given is empty"),
+            ));
         }
 
-        Ok(Parser::new(inner_scope_content).set_counter(start_scope_cursor + 1))
+        Ok(new_prog)
     }
+}
 
-    /// Performs a full cleanup on the input program to remove new lines and spaces
-    fn prog_preprocessor(program: &str) -> String {
-        // Remove new lines and spaces
-        let cleaned_up_string = Regex::new(r"[\r\n\s*]")
-            .expect("Failed to create program preprocessor regex")
-            .replace_all(&program, "")
-            .to_string();
+#[cfg(test)]
+mod parser_tests {
+    use std::rc::Rc;
+
+    use crate::automaton_graph::Symbol;
+    use crate::parser::ParserErrorKind::{InvalidLabel, InvalidToken, UnknownState};
+    use crate::parser::{Parser, Severity, Span};
+    use crate::pda::PDA;
+
+    const SPACED_DFA: &str = r#"{
+        "type": "DFA",
+        "dfa": {"transitions": {}, "startState": "start", "acceptStates": ["s0"]},
+        "states": {
+            "start": {},
+            "s0": {"isAccept": true, "top": 10.5, "left": -2e1, "displayId": "q 0\tfinal"}
+        },
+        "transitions": [
+            {"stateA": "start", "label": "*", "stateB": "s0"},
+            {"stateA": "s0", "label": " ", "stateB": "s0"}
+        ],
+        "bulkTests": {"accept": "*\n* \n", "reject": "\"quoted\""}
+    }"#;
+
+    #[test]
+    fn test_string_contents_are_preserved() {
+        let automaton = Parser::parse(SPACED_DFA).unwrap().0;
+        let s0 = &automaton.all_states()[1];
+
+        assert_eq!(s0.alt_id, "q 0\tfinal");
+        assert_eq!(s0.position.y, 10.5);
+        assert_eq!(s0.position.x, -20.0);
+        assert_eq!(
+            automaton.get_start_state().get_transitions()[0].transition_on(),
+            Symbol::CHAR('*')
+        );
+        assert_eq!(s0.get_transitions()[0].transition_on(), Symbol::CHAR(' '));
+        assert_eq!(automaton.tests.accepting_strings, vec!["*", "* ", ""]);
+        assert_eq!(automaton.tests.rejecting_strings, vec!["\"quoted\""]);
+    }
 
-        let mut prog = cleaned_up_string.chars();
+    #[test]
+    fn test_objects_in_any_order() {
+        let automaton = Parser::parse(
+            r#"{
+            "transitions": [{"label": "a", "stateB": "s0", "stateA": "start", "color": "red"}],
+            "states": {"s0": {"displayId": "s0", "isAccept": true}, "start": {}},
+            "editor": {"zoom": [1, 2, {"x": null}]},
+            "type": "NFA"
+        }"#,
+        )
+        .unwrap()
+        .0;
 
+        assert_eq!(automaton.all_states().len(), 2);
         assert_eq!(
-            prog.nth(0),
-            Some('{'),
-            "Program doesnt start with opening curly braces"
+            automaton.get_start_state().get_transitions()[0].next_state_id(),
+            "s0"
         );
+        assert!(automaton.tests.accepting_strings.is_empty());
+        assert!(automaton.tests.rejecting_strings.is_empty());
+    }
+
+    #[test]
+    fn test_unknown_objects_are_warned_about() {
+        let (_, warnings) = Parser::parse(
+            r#"{
+            "type": "NFA",
+            "states": {"start": {"colour": "red"}},
+            "editor": {"zoom": 2},
+            "transitions": [{"stateA": "start", "label": "a", "stateB": "start", "curve": 1}]
+        }"#,
+        )
+        .unwrap();
+        let messages = warnings
+            .iter()
+            .map(|warning| (warning.severity, warning.message.as_str()))
+            .collect::<Vec<(Severity, &str)>>();
+
         assert_eq!(
-            prog.last(),
-            Some('}'),
-            "Program doesnt emd with closing curly braces"
+            messages,
+            vec![
+                (
+                    Severity::Warning,
+                    "Unrecognised object name 'editor' is ignored"
+                ),
+                (
+                    Severity::Warning,
+                    "Unrecognised property 'colour' of state 'start' is ignored"
+                ),
+                (
+                    Severity::Warning,
+                    "Unrecognised transition property 'curve' is ignored"
+                ),
+            ]
         );
+        assert_eq!(warnings[0].span.line, 4);
+    }
 
-        // Remove the open and closing curly
-        let new_prog = cleaned_up_string[1..(cleaned_up_string.len() - 1)].to_string();
+    #[test]
+    fn test_errors_point_to_their_source() {
+        let program = "{\n  \"type\": \"DFA\",\n  \"states\": {\"start\": {}},\n  \"transitions\": [{\"stateA\": \"start\", \"label\": \"a\", \"stateB\": \"s9\"}]\n}";
+        let err = Parser::parse(program).unwrap_err();
 
-        assert_ne!(
-            new_prog.len(),
-            0,
-            "Invalid program as Program given is empty"
+        assert_eq!(err.kind, UnknownState);
+        assert_eq!(
+            err.span,
+            Span {
+                line: 4,
+                column: 63,
+                len: 4
+            }
         );
+        assert!(err
+            .render("input.txt", program)
+            .ends_with(" --> input.txt:4:63\n  |\n4 |   \"transitions\": [{\"stateA\": \"start\", \"label\": \"a\", \"stateB\": \"s9\"}]\n  |                                                               ^^^^\n"));
+
+        let err = Parser::parse(
+            "{\"type\": \"NFA\", \"states\": {\"start\": {}},\n\"transitions\": [{\"stateA\": \"start\", \"label\": \"ab\", \"stateB\": \"start\"}]}",
+        )
+        .unwrap_err();
+        assert_eq!(err.kind, InvalidLabel);
+        assert_eq!((err.span.line, err.span.column), (2, 46));
 
-        new_prog
+        let err = Parser::parse("{\"type\": @}").unwrap_err();
+        assert_eq!(err.kind, InvalidToken);
+        assert_eq!(err.span.column, 10);
+    }
+
+    #[test]
+    fn test_start_and_accept_states_come_from_automaton_section() {
+        let automaton = Parser::parse(
+            r#"{
+            "type": "NFA",
+            "nfa": {"transitions": {}, "startState": "q0", "acceptStates": ["q1"]},
+            "states": {"q0": {"isAccept": true}, "q1": {}},
+            "transitions": [{"stateA": "q0", "label": "a", "stateB": "q1"}]
+        }"#,
+        )
+        .unwrap()
+        .0;
+
+        assert_eq!(automaton.get_start_state().id, "q0");
+        assert!(!automaton.all_states()[0].is_accept_state);
+        assert!(automaton.all_states()[1].is_accept_state);
+
+        let err = Parser::parse(
+            r#"{"type": "NFA", "nfa": {"startState": "q9"}, "states": {"q0": {}}, "transitions": []}"#,
+        )
+        .unwrap_err();
+        assert_eq!(err.kind, UnknownState);
+        assert_eq!(err.span.column, 39);
+    }
+
+    #[test]
+    fn test_pda_labels_pushing_several_symbols_are_taken_in_steps() {
+        let automaton = Parser::parse(
+            r#"{
+            "type": "PDA",
+            "pda": {"transitions": {}, "startState": "q0", "acceptStates": ["q1"]},
+            "states": {"q0": {}, "q0'": {}, "q1": {"isAccept": true, "left": 100}},
+            "transitions": [
+                {"stateA": "q0", "label": "a,ϵ,AZ", "stateB": "q1"},
+                {"stateA": "q1", "label": "b,A,ϵ", "stateB": "q1"}
+            ]
+        }"#,
+        )
+        .unwrap()
+        .0;
+
+        let start_state = automaton.get_start_state();
+        let transitions = start_state.get_transitions();
+        assert_eq!(transitions.len(), 1);
+        assert!(transitions[0].push_symbol() == Some(Symbol::CHAR('Z')));
+        let step_state = transitions[0].to();
+        assert_eq!(step_state.id, "q0''");
+        assert_eq!(step_state.position.x, 50.0);
+        let step_transitions = step_state.get_transitions();
+        assert!(step_transitions[0].transition_on() == Symbol::EPSILON);
+        assert!(step_transitions[0].push_symbol() == Some(Symbol::CHAR('A')));
+        assert_eq!(step_transitions[0].next_state_id(), "q1");
+        assert_eq!(automaton.all_states().len(), 4);
+
+        // A ends on top of the stack so b can pop it
+        assert!(PDA::new(Rc::new(automaton))
+            .simulate(String::from("ab"))
+            .unwrap());
     }
 }
@@ -0,0 +1,364 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use crate::automaton_graph::AutomatonType::{DFA, NFA};
+use crate::automaton_graph::{Automaton, Position, State, Symbol, Tests, Transition};
+use crate::parser::ParserErrorKind::{
+    DuplicateObjName, InvalidLabel, InvalidToken, OutOfInput, ScopeError, UnknownState,
+};
+use crate::parser::{Parser, ParserError, Span};
+
+/// A cell of a transition table along with where it is found in the file
+struct TableCell {
+    text: String,
+    span: Span,
+}
+
+impl Parser {
+    /// Parses a DFA or NFA written as a transition table in CSV or Markdown
+    ///
+    ///     |      | 0       | 1  |
+    ///     |------|---------|----|
+    ///     | →q0  | {q0,q1} | q0 |
+    ///     | *q1  | ∅       | ∅  |
+    ///
+    /// The header row lists the symbols and each row after it a state, marked with → (or ->)
+    /// when it is the start state and * when it is an accept state. The first state is the start
+    /// state when none are marked. Cells hold the state transitioned to, a set of states, or are left
+    /// empty, `-` or `∅` when there is no transition. Tables are loaded as an NFA when any cell holds
+    /// more than one state or there is an ϵ column, and as a DFA otherwise
+    ///
+    /// Returns a [ParserError] pointing to where in the file the first problem was found
+    pub fn parse_transition_table(program: &str) -> Result<Automaton, ParserError> {
+        let is_markdown = program.trim_start().starts_with('|');
+        let mut rows = program
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(idx, line)| Self::table_row(line, idx as u32 + 1, is_markdown))
+            .collect::<Result<Vec<Vec<TableCell>>, ParserError>>()?
+            .into_iter()
+            // The row under the header of a Markdown table only sets the alignment of columns
+            .filter(|row| {
+                !(is_markdown
+                    && row.iter().all(|cell| {
+                        !cell.text.is_empty() && cell.text.chars().all(|c| matches!(c, '-' | ':'))
+                    }))
+            });
+
+        let header = rows.next().ok_or_else(|| {
+            ParserError::new(
+                OutOfInput,
+                String::from("The transition table is empty"),
+                Span {
+                    line: 1,
+                    column: 1,
+                    len: 1,
+                },
+            )
+        })?;
+
+        let alphabet = header
+            .iter()
+            .skip(1)
+            .map(|cell| {
+                let mut chars = cell.text.chars();
+                match (chars.next(), chars.next()) {
+                    // ε is easier to type than ϵ
+                    (Some('ε'), None) => Ok(Symbol::EPSILON),
+                    (Some(c), None) => Ok(Symbol::from(c)),
+                    _ => Err(ParserError::new(
+                        InvalidLabel,
+                        format!("Column '{}' has to be headed by a single symbol", cell.text),
+                        cell.span,
+                    )),
+                }
+            })
+            .collect::<Result<Vec<Symbol>, ParserError>>()?;
+
+        let mut state_list: Vec<Rc<State>> = vec![];
+        let mut states_by_id: HashMap<String, Rc<State>> = HashMap::new();
+        let mut start_state: Option<Rc<State>> = None;
+        let mut state_rows: Vec<(Rc<State>, Vec<TableCell>)> = vec![];
+
+        for row in rows {
+            if row.len() != header.len() {
+                return Err(ParserError::new(
+                    ScopeError,
+                    format!(
+                        "Row has {} cells but the header has {}",
+                        row.len(),
+                        header.len()
+                    ),
+                    row[0].span,
+                ));
+            }
+
+            let mut row = row.into_iter();
+            let state_cell = row.next().expect("rows are never empty");
+            let (mut is_start, mut is_accept) = (false, false);
+            let mut id = state_cell.text.as_str();
+            loop {
+                if let Some(rest) = id.strip_prefix('→').or_else(|| id.strip_prefix("->")) {
+                    is_start = true;
+                    id = rest;
+                } else if let Some(rest) = id.strip_prefix('*') {
+                    is_accept = true;
+                    id = rest;
+                } else {
+                    break;
+                }
+            }
+            let id = id.trim();
+
+            if id.is_empty() {
+                return Err(ParserError::new(
+                    InvalidToken,
+                    String::from("Row is missing the name of its state"),
+                    state_cell.span,
+                ));
+            }
+            if states_by_id.contains_key(id) {
+                return Err(ParserError::new(
+                    DuplicateObjName,
+                    format!("State '{}' has more than one row", id),
+                    state_cell.span,
+                ));
+            }
+
+            let state = Rc::new(State::new(
+                String::from(id),
+                None,
+                Position::default(),
+                is_accept,
+                RefCell::new(vec![]),
+            ));
+
+            if is_start {
+                if start_state.is_some() {
+                    return Err(ParserError::new(
+                        DuplicateObjName,
+                        String::from("More than one state is marked as the start state"),
+                        state_cell.span,
+                    ));
+                }
+                start_state = Some(state.clone());
+            }
+
+            states_by_id.insert(String::from(id), state.clone());
+            state_list.push(state.clone());
+            state_rows.push((state, row.collect()));
+        }
+
+        let mut is_deterministic = !alphabet.contains(&Symbol::EPSILON);
+
+        // Transitions are added once every state is known so rows can refer to states below them
+        for (state, cells) in state_rows {
+            for (cell, symbol) in cells.iter().zip(&alphabet) {
+                let to_states = Self::table_cell_states(cell)?;
+                is_deterministic &= to_states.len() <= 1;
+
+                for to_state in to_states {
+                    let to = states_by_id.get(to_state).cloned().ok_or_else(|| {
+                        ParserError::new(
+                            UnknownState,
+                            format!("Cannot find state '{}' which has no row", to_state),
+                            cell.span,
+                        )
+                    })?;
+                    state.add_transition(Transition::new(to, *symbol, None, None));
+                }
+            }
+        }
+
+        let start_state = start_state
+            .or_else(|| state_list.first().cloned())
+            .ok_or_else(|| {
+                ParserError::new(
+                    OutOfInput,
+                    String::from("The transition table has no states"),
+                    header[0].span,
+                )
+            })?;
+
+        Ok(Automaton::new(
+            if is_deterministic { DFA } else { NFA },
+            start_state,
+            state_list
+                .iter()
+                .filter(|state| state.is_accept_state)
+                .cloned()
+                .collect::<Vec<Rc<State>>>(),
+            state_list,
+            Tests::default(),
+        ))
+    }
+
+    /// Splits a line of the table into its cells
+    ///
+    /// CSV cells can be quoted, and commas inside a set such as {q1,q2} do not end a cell
+    fn table_row(
+        line: &str,
+        line_number: u32,
+        is_markdown: bool,
+    ) -> Result<Vec<TableCell>, ParserError> {
+        let mut cells = vec![];
+        let mut text = String::new();
+        let mut start_column = 1;
+        let (mut in_quotes, mut in_set, mut is_row_started) = (false, false, false);
+        let mut chars = line.chars().enumerate().peekable();
+
+        while let Some((idx, c)) = chars.next() {
+            let column = idx as u32 + 1;
+            match c {
+                '"' if !is_markdown && in_quotes && chars.peek().map(|(_, c)| *c) == Some('"') => {
+                    chars.next();
+                    text.push('"');
+                }
+                '"' if !is_markdown => in_quotes = !in_quotes,
+                '{' => {
+                    in_set = true;
+                    text.push(c);
+                }
+                '}' => {
+                    in_set = false;
+                    text.push(c);
+                }
+                ',' if !is_markdown && !in_quotes && !in_set => {
+                    cells.push(Self::table_cell(
+                        &mut text,
+                        line_number,
+                        start_column,
+                        column,
+                    ));
+                    start_column = column + 1;
+                }
+                '|' if is_markdown => {
+                    // Markdown rows start with a | that does not end a cell
+                    if is_row_started || !text.trim().is_empty() {
+                        cells.push(Self::table_cell(
+                            &mut text,
+                            line_number,
+                            start_column,
+                            column,
+                        ));
+                    }
+                    is_row_started = true;
+                    start_column = column + 1;
+                }
+                c => text.push(c),
+            }
+        }
+
+        if in_quotes {
+            return Err(ParserError::new(
+                ScopeError,
+                String::from("No closing '\"' was found for the cell"),
+                Span {
+                    line: line_number,
+                    column: start_column,
+                    len: 1,
+                },
+            ));
+        }
+
+        // Markdown rows also end with a | that does not start a cell
+        if !is_markdown || !text.trim().is_empty() {
+            let end_column = line.chars().count() as u32 + 1;
+            cells.push(Self::table_cell(
+                &mut text,
+                line_number,
+                start_column,
+                end_column,
+            ));
+        }
+
+        Ok(cells)
+    }
+
+    /// Takes the text gathered for a cell, trimming the spaces around it
+    fn table_cell(text: &mut String, line: u32, start_column: u32, end_column: u32) -> TableCell {
+        let cell = TableCell {
+            text: text.trim().to_string(),
+            span: Span {
+                line,
+                column: start_column,
+                len: (end_column - start_column).max(1),
+            },
+        };
+        text.clear();
+        cell
+    }
+
+    /// Reads the states held by a cell, where a cell with no transition holds none
+    fn table_cell_states(cell: &TableCell) -> Result<Vec<&str>, ParserError> {
+        let text = cell.text.as_str();
+        if matches!(text, "" | "-" | "∅") {
+            return Ok(vec![]);
+        }
+
+        let states = match text.strip_prefix('{') {
+            Some(set) => set.strip_suffix('}').ok_or_else(|| {
+                ParserError::new(
+                    ScopeError,
+                    format!("No closing '}}' was found for the set '{}'", text),
+                    cell.span,
+                )
+            })?,
+            None => text,
+        };
+
+        Ok(states
+            .split(',')
+            .map(str::trim)
+            .filter(|state| !state.is_empty())
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod transition_table_tests {
+    use crate::automaton_graph::TableFormat;
+    use crate::parser::{Parser, ParserErrorKind, Span};
+
+    #[test]
+    fn test_transition_tables_round_trip() {
+        let nfa = Parser::parse_dsl("nfa start=q0 accept=q1\nq1 -a-> q0; q0 -a,ϵ-> q1; q0 -b-> q0")
+            .unwrap();
+
+        for format in [TableFormat::Csv, TableFormat::Markdown] {
+            let table = nfa.to_transition_table(format).unwrap();
+            assert_eq!(
+                Parser::parse_transition_table(&table)
+                    .unwrap()
+                    .to_transition_table(format)
+                    .unwrap(),
+                table
+            );
+        }
+    }
+
+    #[test]
+    fn test_student_table() {
+        let dfa = Parser::parse_transition_table(
+            "|    | 0  | 1 |\n| :-: | -- | - |\n| -> q0 | q1 | - |\n| *q1 | q1 | q0 |\n",
+        )
+        .unwrap();
+        assert_eq!(
+            dfa.to_dsl(),
+            "dfa start=q0 accept=q1\nq0 -0-> q1\nq1 -0-> q1\nq1 -1-> q0\n"
+        );
+
+        let error = Parser::parse_transition_table(",a\nq0,{q0,q2}\n").unwrap_err();
+        assert_eq!(error.kind, ParserErrorKind::UnknownState);
+        assert_eq!(
+            error.span,
+            Span {
+                line: 2,
+                column: 4,
+                len: 7
+            }
+        );
+    }
+}
@@ -1,10 +1,6 @@
 use std::fmt;
 
-use crate::parser::ParserError::{
-    MissingObjSeparator, NoObjName, ObjNameMismatch, ObjNameNotFound, ObjNameOverFlow,
-    ObjNameSyntaxErr, OutOfInput, ScopeError, UnknownTransitionName,
-};
-use crate::parser::{ParserError, Scope, Separator, SkeletonState};
+use crate::parser::{ParserError, ParserErrorKind, Scope, Separator, SkeletonState, Span};
 
 impl Into<char> for Separator {
     /// Converts the separator from a u8 to a char equivalent
@@ -29,21 +25,86 @@ impl Into<char> for Scope {
     }
 }
 
+impl ParserError {
+    /// Creates an error found at a location in the program
+    pub fn new(kind: ParserErrorKind, message: String, span: Span) -> ParserError {
+        ParserError {
+            kind,
+            message,
+            span,
+        }
+    }
+
+    /// Renders the error the way rustc does, showing the offending line with a
+    /// caret under the part of it that caused the error
+    ///
+    ///     error[ScopeError]: Expected closing scope '}' but found ']'
+    ///      --> input.txt:3:15
+    ///       |
+    ///     3 |     "states": ]
+    ///       |               ^
+    ///
+    /// # Arguments
+    ///
+    /// * `file_name`: Name of the file shown alongside the line and column
+    /// * `program`: The full program the error was found in
+    pub fn render(&self, file_name: &str, program: &str) -> String {
+        self.span.render(
+            &format!("error[{:?}]: {}", self.kind, self.message),
+            file_name,
+            program,
+        )
+    }
+}
+
+impl Span {
+    /// Renders a heading followed by the line of the program the span is on,
+    /// with a caret under the part of the line the span covers
+    ///
+    /// # Arguments
+    ///
+    /// * `heading`: Describes what is found at the span
+    /// * `file_name`: Name of the file shown alongside the line and column
+    /// * `program`: The full program the span is from
+    pub fn render(&self, heading: &str, file_name: &str, program: &str) -> String {
+        let line_number = self.line.to_string();
+        let gutter = " ".repeat(line_number.len());
+
+        let mut rendered = format!(
+            "{}\n{}--> {}:{}:{}\n",
+            heading, gutter, file_name, self.line, self.column
+        );
+
+        if let Some(line) = program.lines().nth(self.line as usize - 1) {
+            let line = line.trim_end_matches('\r');
+            // Keep tabs so the caret lines up with the text above it
+            let padding = line
+                .chars()
+                .take(self.column as usize - 1)
+                .map(|c| if c == '\t' { '\t' } else { ' ' })
+                .collect::<String>();
+
+            rendered.push_str(&format!("{} |\n", gutter));
+            rendered.push_str(&format!("{} | {}\n", line_number, line));
+            rendered.push_str(&format!(
+                "{} | {}{}\n",
+                gutter,
+                padding,
+                "^".repeat(self.len.max(1) as usize)
+            ));
+        }
+
+        rendered
+    }
+}
+
 impl fmt::Display for ParserError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match self {
-            OutOfInput(err_message) => write!(f, "OutOfInput {}", err_message),
-            NoObjName(err_message) => write!(f, "NoObjName {}", err_message),
-            ObjNameSyntaxErr(err_message) => write!(f, "ObjNameSyntaxErr {}", err_message),
-            ObjNameNotFound(err_message) => write!(f, "ObjNameNotFound {}", err_message),
-            ObjNameOverFlow(err_message) => write!(f, "ObjNameOverFlow {}", err_message),
-            ObjNameMismatch(err_message) => write!(f, "ObjNameMismatch {}", err_message),
-            MissingObjSeparator(err_message) => write!(f, "MissingObjSeparator {}", err_message),
-            ScopeError(err_message) => write!(f, "NoScopeFound {}", err_message),
-            UnknownTransitionName(err_message) => {
-                write!(f, "UnknownTransitionName {}", err_message)
-            }
-        }
+        write!(
+            f,
+            "{:?} {} at line {} column {}",
+            self.kind, self.message, self.span.line, self.span.column
+        )
     }
 }
 
@@ -63,4 +124,13 @@ impl SkeletonState {
             _ => None,
         }
     }
+
+    /// Checks if the section is found at the outermost scope of the file
+    /// rather than inside the dfa/nfa/pda section
+    pub fn is_top_level(&self) -> bool {
+        !matches!(
+            self,
+            SkeletonState::StartState | SkeletonState::AcceptStates
+        )
+    }
 }
@@ -0,0 +1,270 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::parser::ParserErrorKind::UnknownState;
+use crate::parser::{
+    AutomatonSection, Issues, Parser, ParserError, Severity, Span, ValidationIssue,
+};
+
+impl Parser {
+    /// Checks an automaton file for every problem it has rather than stopping at the first one
+    ///
+    /// Syntax errors stop the section they are found in from being checked any further,
+    /// but the other sections are still checked
+    ///
+    /// # Arguments
+    ///
+    /// * `program`: The json describing the automaton
+    pub fn validate(program: &str) -> Vec<ValidationIssue> {
+        Self::parse_program(program).1.issues
+    }
+
+    /// Checks the start and accept states given in the "dfa" / "nfa" / "pda" object exist,
+    /// and that the accept states agree with the states marked "isAccept"
+    ///
+    /// # Arguments
+    ///
+    /// * `automaton_section`: The start and accept states that were given
+    /// * `states`: Each state id along with whether it is marked as accepting and where it is found
+    /// * `states_span`: Where the states object starts
+    /// * `issues`: Where problems found are added
+    pub(in crate::parser) fn validate_start_and_accept_states(
+        automaton_section: &AutomatonSection,
+        states: &HashMap<String, (bool, Span)>,
+        states_span: Span,
+        issues: &mut Issues,
+    ) {
+        let (start_state, start_state_span) = automaton_section
+            .start_state
+            .clone()
+            .unwrap_or_else(|| (String::from("start"), states_span));
+        if !states.contains_key(&start_state) {
+            issues.error(ParserError::new(
+                UnknownState,
+                format!("Cannot find start state '{}'", start_state),
+                start_state_span,
+            ));
+        }
+
+        let Some(accept_states) = &automaton_section.accept_states else {
+            return;
+        };
+
+        for (accept_state, accept_state_span) in accept_states {
+            match states.get(accept_state) {
+                None => issues.error(ParserError::new(
+                    UnknownState,
+                    format!("Cannot find accept state '{}'", accept_state),
+                    *accept_state_span,
+                )),
+                Some((false, _)) => issues.warning(
+                    format!(
+                        "State '{}' is listed in acceptStates but is not marked isAccept",
+                        accept_state
+                    ),
+                    *accept_state_span,
+                ),
+                Some((true, _)) => {}
+            }
+        }
+
+        // Sorted so the issues come out in the order the states are given
+        let mut unlisted_states = states
+            .iter()
+            .filter(|(state, (is_accept_state, _))| {
+                *is_accept_state && !accept_states.iter().any(|(accept, _)| accept == *state)
+            })
+            .collect::<Vec<_>>();
+        unlisted_states.sort_by_key(|(_, (_, span))| (span.line, span.column));
+
+        for (state, (_, state_span)) in unlisted_states {
+            issues.warning(
+                format!(
+                    "State '{}' is marked isAccept but is not listed in acceptStates so it does not accept",
+                    state
+                ),
+                *state_span,
+            );
+        }
+    }
+}
+
+impl Issues {
+    /// Adds an error that does not stop the section it is found in from being read
+    pub(in crate::parser) fn error(&mut self, err: ParserError) {
+        self.issues
+            .push(ValidationIssue::error(err.message.clone(), err.span));
+        self.first_error.get_or_insert(err);
+    }
+
+    pub(in crate::parser) fn warning(&mut self, message: String, span: Span) {
+        self.issues.push(ValidationIssue::warning(message, span));
+    }
+
+    /// Adds a syntax error to the issues found, returning the value if there was no error
+    pub(in crate::parser) fn record<T>(&mut self, result: Result<T, ParserError>) -> Option<T> {
+        match result {
+            Ok(value) => Some(value),
+            Err(err) => {
+                self.issues.push(ValidationIssue::from(&err));
+                self.first_error.get_or_insert(err);
+                None
+            }
+        }
+    }
+
+    pub(in crate::parser) fn has_errors(&self) -> bool {
+        self.first_error.is_some()
+    }
+}
+
+impl ValidationIssue {
+    fn error(message: String, span: Span) -> ValidationIssue {
+        ValidationIssue {
+            severity: Severity::Error,
+            message,
+            span,
+        }
+    }
+
+    fn warning(message: String, span: Span) -> ValidationIssue {
+        ValidationIssue {
+            severity: Severity::Warning,
+            message,
+            span,
+        }
+    }
+
+    /// Renders the issue the same way as [ParserError::render]
+    ///
+    ///     warning: Unrecognised object name 'editor' is ignored
+    ///      --> input.txt:5:5
+    ///       |
+    ///     5 |     "editor": {}
+    ///       |     ^^^^^^^^
+    pub fn render(&self, file_name: &str, program: &str) -> String {
+        self.span.render(
+            &format!("{}: {}", self.severity, self.message),
+            file_name,
+            program,
+        )
+    }
+}
+
+impl From<&ParserError> for ValidationIssue {
+    fn from(err: &ParserError) -> ValidationIssue {
+        ValidationIssue::error(format!("{:?}: {}", err.kind, err.message), err.span)
+    }
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Severity::Error => write!(f, "error"),
+            Severity::Warning => write!(f, "warning"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod validator_tests {
+    use crate::parser::ParserErrorKind::DuplicateObjName;
+    use crate::parser::{Parser, Severity};
+
+    const BROKEN_DFA: &str = r#"{
+        "type": "DFA",
+        "states": {"s0": {"isAccept": true, "colour": "red"}, "s0": {}},
+        "transitions": [
+            {"stateA": "s0", "label": "a", "stateB": "s9"},
+            {"stateA": "s0", "label": "a", "stateB": "s0"},
+            {"stateA": "s0", "label": "a,b,c", "stateB": "s0"},
+            {"stateA": "s0", "label": "ϵ"}
+        ],
+        "bulkTests": {"accept": "a\nab", "reject": ""}
+    }"#;
+
+    #[test]
+    fn test_every_issue_is_reported() {
+        let issues = Parser::validate(BROKEN_DFA);
+        let messages = |severity: Severity| {
+            issues
+                .iter()
+                .filter(|issue| issue.severity == severity)
+                .map(|issue| issue.message.as_str())
+                .collect::<Vec<&str>>()
+        };
+
+        assert_eq!(
+            messages(Severity::Error),
+            vec![
+                "State id 's0' is given more than once",
+                "Cannot find start state 'start'",
+                "Cannot find state 's9' referenced by stateB in transition",
+                "State 's0' has more than one transition on 'a' so the DFA is not deterministic",
+                "Label 'a,b,c' has 3 symbols but a DFA label needs 1",
+                "Missing state_b transition name for transition",
+                "A DFA cannot have a transition on ϵ",
+            ]
+        );
+        assert_eq!(
+            messages(Severity::Warning),
+            vec![
+                "Unrecognised property 'colour' of state 's0' is ignored",
+                "Test string 'ab' uses symbols {'b'} that are not in the alphabet",
+            ]
+        );
+        assert!(issues
+            .iter()
+            .any(|issue| issue.message.contains("'s9'") && issue.span.line == 5));
+
+        // Parsing stops at the first of the same errors
+        let err = Parser::parse(BROKEN_DFA).unwrap_err();
+        assert_eq!(err.kind, DuplicateObjName);
+        assert_eq!(err.message, messages(Severity::Error)[0]);
+    }
+
+    #[test]
+    fn test_syntax_errors_are_reported_as_issues() {
+        let issues =
+            Parser::validate(r#"{"type": "DFA", "states": {"start": {}}, "transitions": [}"#);
+
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].severity, Severity::Error);
+        assert!(issues[0].message.starts_with("ScopeError"));
+    }
+
+    #[test]
+    fn test_inconsistent_accept_states_are_reported() {
+        let issues = Parser::validate(
+            r#"{
+            "type": "DFA",
+            "nfa": {"startState": "q0", "acceptStates": ["q1", "q2", "q3"]},
+            "states": {"q0": {"isAccept": true}, "q1": {}, "q2": {"isAccept": true}},
+            "transitions": []
+        }"#,
+        );
+        let messages = issues
+            .iter()
+            .map(|issue| (issue.severity, issue.message.as_str()))
+            .collect::<Vec<(Severity, &str)>>();
+
+        assert_eq!(
+            messages,
+            vec![
+                (
+                    Severity::Warning,
+                    "Object 'nfa' does not match the type DFA of the automaton"
+                ),
+                (
+                    Severity::Warning,
+                    "State 'q1' is listed in acceptStates but is not marked isAccept"
+                ),
+                (Severity::Error, "Cannot find accept state 'q3'"),
+                (
+                    Severity::Warning,
+                    "State 'q0' is marked isAccept but is not listed in acceptStates so it does not accept"
+                ),
+            ]
+        );
+    }
+}
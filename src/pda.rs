@@ -1,16 +1,26 @@
 use std::rc::Rc;
 
-use crate::automaton_graph::{Automaton, Symbol};
+use crate::automaton_graph::{ArenaGraph, Automaton};
 
+mod pda_determinism;
+mod pda_intersection;
+mod pda_language;
 mod pda_simulator;
 
+pub use pda_simulator::PdaRunner;
+
 pub struct PDA {
     automaton_graph: Rc<Automaton>,
+    /// The automaton stored by index, which runs step through
+    graph: Rc<ArenaGraph>,
+    is_deterministic: bool,
 }
 
 impl PDA {
     pub fn new(automaton: Rc<Automaton>) -> PDA {
         PDA {
+            is_deterministic: Self::find_determinism_conflicts(&automaton).is_empty(),
+            graph: Rc::new(ArenaGraph::new(&automaton)),
             automaton_graph: automaton,
         }
     }
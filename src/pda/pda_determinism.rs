@@ -0,0 +1,143 @@
+use std::fmt;
+use std::fmt::{Display, Formatter};
+
+use crate::automaton_graph::{Automaton, Symbol, Transition};
+use crate::pda::PDA;
+
+/// A pair of transitions leaving the same state which a deterministic
+/// PDA would not be able to choose between
+#[derive(Debug)]
+pub struct DeterminismConflict {
+    /// Display id of the state both transitions leave from
+    pub state: String,
+    pub first_label: String,
+    pub second_label: String,
+}
+
+impl Display for DeterminismConflict {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "State {} has conflicting transitions '{}' and '{}'",
+            self.state, self.first_label, self.second_label
+        )
+    }
+}
+
+impl PDA {
+    /// Returns true if no configuration of the PDA can take more than one transition
+    pub fn is_deterministic(&self) -> bool {
+        self.is_deterministic
+    }
+
+    /// Returns every pair of transitions that stops the PDA from being deterministic
+    pub fn determinism_conflicts(&self) -> Vec<DeterminismConflict> {
+        Self::find_determinism_conflicts(&self.automaton_graph)
+    }
+
+    /// Checks each state for transitions that could both be taken from the same configuration
+    ///
+    /// Two transitions conflict when they read the same input symbol (or either reads ϵ)
+    /// and expect the same stack top (or either pops ϵ, which matches any stack top)
+    ///
+    /// # Arguments
+    ///
+    /// * `automaton`: The PDA to check
+    pub(in crate::pda) fn find_determinism_conflicts(
+        automaton: &Automaton,
+    ) -> Vec<DeterminismConflict> {
+        let mut conflicts = vec![];
+
+        for state in automaton.all_states() {
+            let transitions = state.get_transitions();
+
+            for (idx, first) in transitions.iter().enumerate() {
+                for second in &transitions[idx + 1..] {
+                    if Self::transitions_conflict(first, second) {
+                        conflicts.push(DeterminismConflict {
+                            state: state.alt_id.clone(),
+                            first_label: first.label(),
+                            second_label: second.label(),
+                        });
+                    }
+                }
+            }
+        }
+
+        conflicts
+    }
+
+    /// Checks if two transitions from the same state could both apply to one configuration
+    fn transitions_conflict(first: &Transition, second: &Transition) -> bool {
+        let overlaps = |a: Option<Symbol>, b: Option<Symbol>| match (a, b) {
+            (None, _) | (_, None) => true,
+            (Some(Symbol::EPSILON), _) | (_, Some(Symbol::EPSILON)) => true,
+            (Some(a), Some(b)) => a == b,
+        };
+
+        overlaps(Some(first.transition_on()), Some(second.transition_on()))
+            && overlaps(first.pop_symbol(), second.pop_symbol())
+    }
+}
+
+#[cfg(test)]
+mod determinism_tests {
+    use std::rc::Rc;
+
+    use crate::parser::Parser;
+    use crate::pda::PDA;
+
+    const DETERMINISTIC_PDA: &str = r#"{"type":"PDA","pda":{"transitions":{},"startState":"start","acceptStates":["s1"]},"states":{"start":{},"s0":{"top":100,"left":100,"displayId":"s0"},"s1":{"isAccept":true,"top":100,"left":200,"displayId":"s1"}},"transitions":[{"stateA":"start","label":"a,ϵ,A","stateB":"s0"},{"stateA":"s0","label":"a,ϵ,A","stateB":"s0"},{"stateA":"s0","label":"b,A,ϵ","stateB":"s1"},{"stateA":"s1","label":"b,A,ϵ","stateB":"s1"}],"bulkTests":{"accept":"ab\naabb\naab","reject":"ba\nabb\na"}}"#;
+    const NON_DETERMINISTIC_PDA: &str = r#"{"type":"PDA","pda":{"transitions":{},"startState":"start","acceptStates":["s1"]},"states":{"start":{},"s0":{"top":100,"left":100,"displayId":"s0"},"s1":{"isAccept":true,"top":100,"left":200,"displayId":"s1"}},"transitions":[{"stateA":"start","label":"a,ϵ,A","stateB":"s0"},{"stateA":"s0","label":"a,ϵ,A","stateB":"s0"},{"stateA":"s0","label":"b,A,ϵ","stateB":"s1"},{"stateA":"s0","label":"ϵ,ϵ,ϵ","stateB":"s1"}],"bulkTests":{"accept":"ab","reject":"ba"}}"#;
+
+    #[test]
+    fn test_deterministic_pda_has_no_conflicts() {
+        let pda = PDA::new(Rc::new(Parser::parse(DETERMINISTIC_PDA).unwrap().0));
+        assert!(pda.is_deterministic());
+        assert!(pda.determinism_conflicts().is_empty());
+    }
+
+    #[test]
+    fn test_conflicts_are_reported() {
+        let pda = PDA::new(Rc::new(Parser::parse(NON_DETERMINISTIC_PDA).unwrap().0));
+        let conflicts = pda.determinism_conflicts();
+
+        assert!(!pda.is_deterministic());
+        assert_eq!(conflicts.len(), 2);
+        assert!(conflicts
+            .iter()
+            .all(|conflict| conflict.state == "s0" && conflict.second_label == "ϵ,ϵ,ϵ"));
+    }
+
+    #[test]
+    fn test_deterministic_simulation() {
+        let mut pda = PDA::new(Rc::new(Parser::parse(DETERMINISTIC_PDA).unwrap().0));
+        for accepting_str in pda.automaton_graph.tests.accepting_strings.clone() {
+            assert!(pda.simulate(accepting_str).unwrap());
+        }
+        for rejecting_str in pda.automaton_graph.tests.rejecting_strings.clone() {
+            assert!(!pda.simulate(rejecting_str).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_epsilon_loop_that_pops_and_pushes_back_stops() {
+        for program in [
+            "pda start=q0 accept=q2\nq0 -a,ϵ/A-> q1; q1 -ϵ,A/A-> q1; q1 -b,A/ϵ-> q2",
+            "pda start=q0 accept=q2\nq0 -a,ϵ/A-> q1; q1 -ϵ,A/A-> q1; q0 -b,ϵ/B-> q1; q1 -b,B/ϵ-> q2",
+        ] {
+            let mut pda = PDA::new(Rc::new(Parser::parse_dsl(program).unwrap()));
+            assert!(!pda.simulate(String::from("a")).unwrap());
+            assert!(!pda.simulate(String::from("b")).unwrap());
+        }
+
+        let mut pda = PDA::new(Rc::new(
+            Parser::parse_dsl(
+                "pda start=q0 accept=q2\nq0 -a,ϵ/A-> q1; q1 -ϵ,A/A-> q1; q0 -b,ϵ/B-> q1; q1 -b,B/ϵ-> q2",
+            )
+            .unwrap(),
+        ));
+        assert!(pda.is_deterministic());
+        assert!(pda.simulate(String::from("bb")).unwrap());
+    }
+}
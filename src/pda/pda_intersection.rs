@@ -0,0 +1,170 @@
+use std::cell::RefCell;
+use std::collections::{HashMap, VecDeque};
+use std::rc::Rc;
+
+use crate::automaton_graph::{Automaton, AutomatonType, Layout, State, Symbol, Tests, Transition};
+use crate::dfa::DFA;
+use crate::pda::PDA;
+
+impl PDA {
+    /// Builds the product of this PDA with a DFA, giving a PDA which only accepts
+    /// strings accepted by both
+    ///
+    /// Each state of the product is a pair `(q,p)` of a PDA state `q` and a DFA state `p`.
+    /// A transition `q -a,X,Y-> q'` becomes `(q,p) -a,X,Y-> (q',p')` for every DFA
+    /// transition `p -a-> p'`, while ϵ-transitions leave the DFA state unchanged.
+    /// Only states reachable from the start pair are created
+    ///
+    /// # Arguments
+    ///
+    /// * `dfa`: The DFA describing the regular language to restrict this PDA to
+    pub fn intersect(&self, dfa: &DFA) -> Automaton {
+        let pda_start = self.automaton_graph.get_start_state();
+        let dfa_start = dfa.automaton_graph().get_start_state();
+
+        let start_state = Self::product_state(&pda_start, &dfa_start);
+        let mut all_states: Vec<Rc<State>> = vec![start_state.clone()];
+        let mut states_by_id: HashMap<String, Rc<State>> =
+            HashMap::from([(start_state.id.clone(), start_state.clone())]);
+
+        // Pairs whose transitions have not been built yet
+        let mut pending_pairs = VecDeque::from([(pda_start, dfa_start)]);
+
+        while let Some((pda_state, dfa_state)) = pending_pairs.pop_front() {
+            let from = states_by_id[&Self::product_id(&pda_state, &dfa_state)].clone();
+
+            for transition in pda_state.get_transitions().iter() {
+                // DFA states reached alongside this transition
+                let dfa_targets = match transition.transition_on() {
+                    Symbol::EPSILON => vec![dfa_state.clone()],
+                    symbol => dfa_state
+                        .get_transitions()
+                        .iter()
+                        .filter(|dfa_transition| dfa_transition.transition_on() == symbol)
+                        .map(|dfa_transition| dfa_transition.to())
+                        .collect::<Vec<Rc<State>>>(),
+                };
+
+                for dfa_target in dfa_targets {
+                    let pda_target = transition.to();
+                    let to = states_by_id
+                        .entry(Self::product_id(&pda_target, &dfa_target))
+                        .or_insert_with(|| {
+                            let state = Self::product_state(&pda_target, &dfa_target);
+                            all_states.push(state.clone());
+                            pending_pairs.push_back((pda_target.clone(), dfa_target.clone()));
+                            state
+                        })
+                        .clone();
+
+                    from.add_transition(Transition::new(
+                        to,
+                        transition.transition_on(),
+                        transition.pop_symbol(),
+                        transition.push_symbol(),
+                    ));
+                }
+            }
+        }
+
+        let accept_states = all_states
+            .iter()
+            .filter(|state| state.is_accept_state)
+            .cloned()
+            .collect::<Vec<Rc<State>>>();
+
+        // Product states share the position of their PDA state, so they are laid out again
+        Automaton::new(
+            AutomatonType::PDA,
+            start_state,
+            accept_states,
+            all_states,
+            Tests::default(),
+        )
+        .with_layout(Layout::Layered)
+    }
+
+    /// Returns the id of the product state for a PDA and DFA state, escaping the separators
+    /// in their ids with a backslash so that different pairs never share an id
+    ///
+    /// Ex: "(s0,start)" or "(a\,b,c)" for the PDA state "a,b" and the DFA state "c"
+    fn product_id(pda_state: &State, dfa_state: &State) -> String {
+        let escape = |id: &str| {
+            id.chars().fold(String::new(), |mut escaped, c| {
+                if matches!(c, '\\' | ',' | '(' | ')') {
+                    escaped.push('\\');
+                }
+                escaped.push(c);
+                escaped
+            })
+        };
+        format!("({},{})", escape(&pda_state.id), escape(&dfa_state.id))
+    }
+
+    /// Creates a product state without transitions which accepts only if both of its states accept
+    fn product_state(pda_state: &State, dfa_state: &State) -> Rc<State> {
+        Rc::new(State::new(
+            Self::product_id(pda_state, dfa_state),
+            Some(format!("({},{})", pda_state.alt_id, dfa_state.alt_id)),
+            pda_state.position,
+            pda_state.is_accept_state && dfa_state.is_accept_state,
+            RefCell::new(vec![]),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod intersection_tests {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    use crate::automaton_graph::{Position, State};
+    use crate::dfa::DFA;
+    use crate::parser::Parser;
+    use crate::pda::PDA;
+
+    /// Accepts a^n b^m with 1 <= m <= n
+    const PDA_A_THEN_B: &str = r#"{"type":"PDA","pda":{"transitions":{},"startState":"start","acceptStates":["s1"]},"states":{"start":{},"s0":{"top":100,"left":100,"displayId":"s0"},"s1":{"isAccept":true,"top":100,"left":200,"displayId":"s1"}},"transitions":[{"stateA":"start","label":"a,ϵ,A","stateB":"s0"},{"stateA":"s0","label":"a,ϵ,A","stateB":"s0"},{"stateA":"s0","label":"b,A,ϵ","stateB":"s1"},{"stateA":"s1","label":"b,A,ϵ","stateB":"s1"}],"bulkTests":{"accept":"","reject":""}}"#;
+    /// Accepts strings with an even number of a's
+    const DFA_EVEN_A: &str = r#"{"type":"DFA","dfa":{"transitions":{},"startState":"start","acceptStates":["start"]},"states":{"start":{"isAccept":true},"s0":{"top":100,"left":100,"displayId":"s0"}},"transitions":[{"stateA":"start","label":"a","stateB":"s0"},{"stateA":"start","label":"b","stateB":"start"},{"stateA":"s0","label":"a","stateB":"start"},{"stateA":"s0","label":"b","stateB":"s0"}],"bulkTests":{"accept":"","reject":""}}"#;
+
+    #[test]
+    fn test_intersection_with_even_a() {
+        let pda = PDA::new(Rc::new(Parser::parse(PDA_A_THEN_B).unwrap().0));
+        let dfa = DFA::new(Parser::parse(DFA_EVEN_A).unwrap().0);
+        let product = pda.intersect(&dfa);
+
+        assert_eq!(product.get_start_state().id, "(start,start)");
+
+        let mut product = PDA::new(Rc::new(product));
+        for accepting_str in ["aab", "aabb", "aaaab"] {
+            assert!(product.simulate(accepting_str.to_string()).unwrap());
+        }
+        for rejecting_str in ["ab", "aaab", "aaabbb", "b"] {
+            assert!(!product.simulate(rejecting_str.to_string()).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_product_ids_do_not_collide() {
+        let state = |id: &str| {
+            State::new(
+                String::from(id),
+                None,
+                Position::default(),
+                false,
+                RefCell::new(vec![]),
+            )
+        };
+
+        assert_eq!(PDA::product_id(&state("a,b"), &state("c")), "(a\\,b,c)");
+        assert_ne!(
+            PDA::product_id(&state("a,b"), &state("c")),
+            PDA::product_id(&state("a"), &state("b,c"))
+        );
+        assert_ne!(
+            PDA::product_id(&state("a)"), &state("b")),
+            PDA::product_id(&state("a"), &state(")b"))
+        );
+    }
+}
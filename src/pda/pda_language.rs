@@ -0,0 +1,393 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::automaton_graph::Symbol;
+use crate::pda::PDA;
+
+/// A single action taken by a PDA transition once it is broken into smaller steps
+#[derive(Debug, Copy, Clone)]
+enum Step {
+    /// Reads a symbol from the input or nothing for an ϵ
+    Read(Option<char>),
+    Pop(Symbol),
+    Push(Symbol),
+}
+
+/// An edge between two nodes of the PDA after its transitions have been split into [Step]s
+#[derive(Debug)]
+struct StepEdge {
+    from: usize,
+    to: usize,
+    step: Step,
+}
+
+/// Identifies a variable of the grammar built from the PDA
+#[derive(Debug, Copy, Clone, Hash, Eq, PartialEq)]
+enum Variable {
+    /// Derives the accepted strings
+    Start,
+    /// Strings that reach a node from the start state, the stack may be left non-empty
+    Reach(usize),
+    /// Strings that go between two nodes, leaving the stack as it was found
+    /// without ever popping below it
+    Balanced(usize, usize),
+    /// Strings read between a push and the pop that removes the same symbol
+    Matched(usize, usize),
+}
+
+#[derive(Debug, Copy, Clone)]
+enum GrammarSymbol {
+    Terminal(char),
+    Variable(Variable),
+}
+
+#[derive(Debug)]
+struct Production {
+    variable: Variable,
+    body: Vec<GrammarSymbol>,
+}
+
+/// A context-free grammar generating the same language as a PDA
+#[derive(Debug, Default)]
+struct LanguageGrammar {
+    productions: Vec<Production>,
+}
+
+impl PDA {
+    /// Decides if the language of the PDA is empty, returning a shortest string it
+    /// accepts as a witness when it is not
+    pub fn shortest_accepted_string(&self) -> Option<String> {
+        self.language_grammar()
+            .shortest_strings()
+            .remove(&Variable::Start)
+    }
+
+    /// Returns true if the PDA only accepts a finite number of strings
+    ///
+    /// The language is infinite when the grammar for it has a useful variable that can
+    /// derive itself alongside a non-empty string, as that derivation can be pumped
+    pub fn is_language_finite(&self) -> bool {
+        let grammar = self.language_grammar();
+        let shortest = grammar.shortest_strings();
+        let is_productive = |symbol: &GrammarSymbol| match symbol {
+            GrammarSymbol::Terminal(_) => true,
+            GrammarSymbol::Variable(variable) => shortest.contains_key(variable),
+        };
+
+        // Only productions whose symbols can all derive a string take part in accepting runs
+        let productive = grammar
+            .productions
+            .iter()
+            .filter(|production| production.body.iter().all(is_productive))
+            .collect::<Vec<&Production>>();
+
+        // Find the variables that can derive at least one non-empty string
+        let mut derives_non_empty: HashSet<Variable> = HashSet::new();
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for production in &productive {
+                if !derives_non_empty.contains(&production.variable)
+                    && production.body.iter().any(|symbol| match symbol {
+                        GrammarSymbol::Terminal(_) => true,
+                        GrammarSymbol::Variable(variable) => derives_non_empty.contains(variable),
+                    })
+                {
+                    derives_non_empty.insert(production.variable);
+                    changed = true;
+                }
+            }
+        }
+
+        // Edges from a variable to the ones in its productions, marked if a
+        // non-empty string is derived next to it
+        let mut edges: HashMap<Variable, Vec<(Variable, bool)>> = HashMap::new();
+        for production in &productive {
+            for (idx, symbol) in production.body.iter().enumerate() {
+                if let GrammarSymbol::Variable(variable) = symbol {
+                    let grows = production
+                        .body
+                        .iter()
+                        .enumerate()
+                        .any(|(other_idx, other)| {
+                            other_idx != idx
+                                && match other {
+                                    GrammarSymbol::Terminal(_) => true,
+                                    GrammarSymbol::Variable(other) => {
+                                        derives_non_empty.contains(other)
+                                    }
+                                }
+                        });
+                    edges
+                        .entry(production.variable)
+                        .or_default()
+                        .push((*variable, grows));
+                }
+            }
+        }
+
+        // Variables that can be part of a derivation of an accepted string
+        let useful = match shortest.contains_key(&Variable::Start) {
+            true => Self::reachable_variables(&edges, Variable::Start),
+            false => HashSet::new(),
+        };
+
+        // Look for a growing edge which is part of a cycle of useful variables
+        !edges.iter().any(|(variable, variable_edges)| {
+            useful.contains(variable)
+                && variable_edges.iter().any(|(next, grows)| {
+                    *grows && Self::reachable_variables(&edges, *next).contains(variable)
+                })
+        })
+    }
+
+    /// Finds every variable that can be reached from a variable by following the grammar edges
+    fn reachable_variables(
+        edges: &HashMap<Variable, Vec<(Variable, bool)>>,
+        from: Variable,
+    ) -> HashSet<Variable> {
+        let mut reached = HashSet::from([from]);
+        let mut pending = vec![from];
+
+        while let Some(variable) = pending.pop() {
+            for (next, _) in edges.get(&variable).into_iter().flatten() {
+                if reached.insert(*next) {
+                    pending.push(*next);
+                }
+            }
+        }
+
+        reached
+    }
+
+    /// Breaks each transition into [Step]s so that every edge only reads, pops or pushes.
+    /// The first nodes are the states of the PDA in the same order, followed by the
+    /// nodes created between steps
+    fn step_edges(&self) -> Vec<StepEdge> {
+        let all_states = self.automaton_graph.all_states();
+        let state_idx = |id: &str| {
+            all_states
+                .iter()
+                .position(|state| state.id == id)
+                .expect("Transition leads to a state outside the automaton")
+        };
+
+        let mut node_count = all_states.len();
+        let mut edges = vec![];
+
+        for (from, state) in all_states.iter().enumerate() {
+            for transition in state.get_transitions().iter() {
+                let mut steps = vec![];
+                if let Symbol::CHAR(c) = transition.transition_on() {
+                    steps.push(Step::Read(Some(c)));
+                }
+                if let Some(Symbol::CHAR(c)) = transition.pop_symbol() {
+                    steps.push(Step::Pop(Symbol::CHAR(c)));
+                }
+                if let Some(Symbol::CHAR(c)) = transition.push_symbol() {
+                    steps.push(Step::Push(Symbol::CHAR(c)));
+                }
+                if steps.is_empty() {
+                    steps.push(Step::Read(None));
+                }
+
+                // Chain the steps through fresh nodes ending at the transitions target
+                let to = state_idx(transition.next_state_id());
+                let mut curr = from;
+                for (idx, step) in steps.iter().enumerate() {
+                    let next = if idx == steps.len() - 1 {
+                        to
+                    } else {
+                        node_count += 1;
+                        node_count - 1
+                    };
+                    edges.push(StepEdge {
+                        from: curr,
+                        to: next,
+                        step: *step,
+                    });
+                    curr = next;
+                }
+            }
+        }
+
+        edges
+    }
+
+    /// Builds a grammar whose language is the language of the PDA
+    ///
+    /// Every stack symbol that is popped must have been pushed earlier in the run, so an
+    /// accepting run is made up of reads, pushes that are never popped, and stretches
+    /// between a push and its matching pop. Each of those is given a variable
+    fn language_grammar(&self) -> LanguageGrammar {
+        let edges = self.step_edges();
+        let mut grammar = LanguageGrammar::default();
+
+        // Nodes a balanced stretch can start from, which is right after a push
+        let balanced_sources = edges
+            .iter()
+            .filter(|edge| matches!(edge.step, Step::Push(_)))
+            .map(|edge| edge.to)
+            .collect::<Vec<usize>>();
+
+        // Strings accepted
+        let all_states = self.automaton_graph.all_states();
+        for (idx, state) in all_states.iter().enumerate() {
+            if state.is_accept_state {
+                grammar.add(Variable::Start, vec![Variable::Reach(idx).into()]);
+            }
+        }
+        let start_idx = all_states
+            .iter()
+            .position(|state| state.id == self.automaton_graph.get_start_state().id)
+            .expect("Start state is not part of the automaton");
+        grammar.add(Variable::Reach(start_idx), vec![]);
+
+        for source in &balanced_sources {
+            grammar.add(Variable::Balanced(*source, *source), vec![]);
+        }
+
+        for edge in &edges {
+            match edge.step {
+                Step::Read(read) => {
+                    let read = read.map(GrammarSymbol::Terminal);
+                    grammar.add(
+                        Variable::Reach(edge.to),
+                        [Variable::Reach(edge.from).into()]
+                            .into_iter()
+                            .chain(read)
+                            .collect(),
+                    );
+                    for source in &balanced_sources {
+                        grammar.add(
+                            Variable::Balanced(*source, edge.to),
+                            [Variable::Balanced(*source, edge.from).into()]
+                                .into_iter()
+                                .chain(read)
+                                .collect(),
+                        );
+                    }
+                }
+                // A push that is never popped
+                Step::Push(_) => grammar.add(
+                    Variable::Reach(edge.to),
+                    vec![Variable::Reach(edge.from).into()],
+                ),
+                Step::Pop(_) => {}
+            }
+        }
+
+        // Pair each push with every pop of the same symbol
+        for push in &edges {
+            let Step::Push(pushed) = push.step else {
+                continue;
+            };
+
+            for pop in &edges {
+                if !matches!(pop.step, Step::Pop(popped) if popped == pushed) {
+                    continue;
+                }
+
+                let matched = Variable::Matched(push.from, pop.to);
+                grammar.add(matched, vec![Variable::Balanced(push.to, pop.from).into()]);
+                grammar.add(
+                    Variable::Reach(pop.to),
+                    vec![Variable::Reach(push.from).into(), matched.into()],
+                );
+                for source in &balanced_sources {
+                    grammar.add(
+                        Variable::Balanced(*source, pop.to),
+                        vec![
+                            Variable::Balanced(*source, push.from).into(),
+                            matched.into(),
+                        ],
+                    );
+                }
+            }
+        }
+
+        grammar
+    }
+}
+
+impl From<Variable> for GrammarSymbol {
+    fn from(variable: Variable) -> GrammarSymbol {
+        GrammarSymbol::Variable(variable)
+    }
+}
+
+impl LanguageGrammar {
+    /// Adds a production to the grammar
+    fn add(&mut self, variable: Variable, body: Vec<GrammarSymbol>) {
+        self.productions.push(Production { variable, body })
+    }
+
+    /// Finds a shortest string derived by each variable that derives any string at all
+    fn shortest_strings(&self) -> HashMap<Variable, String> {
+        let mut shortest: HashMap<Variable, String> = HashMap::new();
+
+        // Keep shortening until no production gives a shorter string
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for production in &self.productions {
+                let derived = production
+                    .body
+                    .iter()
+                    .try_fold(String::new(), |mut acc, symbol| {
+                        match symbol {
+                            GrammarSymbol::Terminal(c) => acc.push(*c),
+                            GrammarSymbol::Variable(variable) => {
+                                acc.push_str(shortest.get(variable)?)
+                            }
+                        }
+                        Some(acc)
+                    });
+
+                if let Some(derived) = derived {
+                    let is_shorter = shortest
+                        .get(&production.variable)
+                        .is_none_or(|curr| derived.chars().count() < curr.chars().count());
+                    if is_shorter {
+                        shortest.insert(production.variable, derived);
+                        changed = true;
+                    }
+                }
+            }
+        }
+
+        shortest
+    }
+}
+
+#[cfg(test)]
+mod language_tests {
+    use std::rc::Rc;
+
+    use crate::parser::Parser;
+    use crate::pda::PDA;
+
+    /// Accepts a^n b^m with 1 <= m <= n
+    const INFINITE_PDA: &str = r#"{"type":"PDA","pda":{"transitions":{},"startState":"start","acceptStates":["s1"]},"states":{"start":{},"s0":{"top":100,"left":100,"displayId":"s0"},"s1":{"isAccept":true,"top":100,"left":200,"displayId":"s1"}},"transitions":[{"stateA":"start","label":"a,ϵ,A","stateB":"s0"},{"stateA":"s0","label":"a,ϵ,A","stateB":"s0"},{"stateA":"s0","label":"b,A,ϵ","stateB":"s1"},{"stateA":"s1","label":"b,A,ϵ","stateB":"s1"}],"bulkTests":{"accept":"","reject":""}}"#;
+    /// Accepts only "ab" and "abc"
+    const FINITE_PDA: &str = r#"{"type":"PDA","pda":{"transitions":{},"startState":"start","acceptStates":["s1","s2"]},"states":{"start":{},"s0":{"top":100,"left":100,"displayId":"s0"},"s1":{"isAccept":true,"top":100,"left":200,"displayId":"s1"},"s2":{"isAccept":true,"top":100,"left":300,"displayId":"s2"}},"transitions":[{"stateA":"start","label":"a,ϵ,A","stateB":"s0"},{"stateA":"s0","label":"b,A,ϵ","stateB":"s1"},{"stateA":"s1","label":"c,ϵ,ϵ","stateB":"s2"}],"bulkTests":{"accept":"","reject":""}}"#;
+    /// Pops a symbol that is never pushed before reaching its accept state
+    const EMPTY_PDA: &str = r#"{"type":"PDA","pda":{"transitions":{},"startState":"start","acceptStates":["s1"]},"states":{"start":{},"s0":{"top":100,"left":100,"displayId":"s0"},"s1":{"isAccept":true,"top":100,"left":200,"displayId":"s1"}},"transitions":[{"stateA":"start","label":"a,ϵ,A","stateB":"s0"},{"stateA":"s0","label":"a,ϵ,A","stateB":"s0"},{"stateA":"s0","label":"b,B,ϵ","stateB":"s1"}],"bulkTests":{"accept":"","reject":""}}"#;
+
+    #[test]
+    fn test_shortest_accepted_string() {
+        let pda = PDA::new(Rc::new(Parser::parse(INFINITE_PDA).unwrap().0));
+        assert_eq!(pda.shortest_accepted_string(), Some(String::from("ab")));
+        assert!(!pda.is_language_finite());
+
+        let pda = PDA::new(Rc::new(Parser::parse(FINITE_PDA).unwrap().0));
+        assert_eq!(pda.shortest_accepted_string(), Some(String::from("ab")));
+        assert!(pda.is_language_finite());
+    }
+
+    #[test]
+    fn test_empty_language() {
+        let pda = PDA::new(Rc::new(Parser::parse(EMPTY_PDA).unwrap().0));
+        assert_eq!(pda.shortest_accepted_string(), None);
+        assert!(pda.is_language_finite());
+    }
+}
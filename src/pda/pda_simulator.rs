@@ -1,139 +1,493 @@
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::hash::{Hash, Hasher};
+use std::iter;
+use std::mem;
 use std::rc::Rc;
 
-use crate::automaton_graph::{SimulationError, State, Symbol, Transition};
+use crate::automaton_graph::{ArenaGraph, Edge, SimulationError, StateId, Symbol, SymbolId};
 use crate::pda::PDA;
+use crate::simulator::{Configuration, RunStatus, Runner};
+use crate::trace::Trace;
 
-#[derive(Debug)]
-struct PdaInstance<'a> {
-    curr_state: Rc<State>,
-    stack: Vec<Symbol>,
-    sim_str: &'a str,
+/// Runs a PDA on input which can be fed to it a piece at a time, taking a transition from every
+/// configuration it can be in on each step, where ϵ transitions are steps of their own
+pub struct PdaRunner {
+    graph: Rc<ArenaGraph>,
+    is_deterministic: bool,
+    /// Characters fed to the runner which have not been read by every configuration yet
+    pending: VecDeque<char>,
+    /// Position in the input of the first pending character
+    offset: usize,
+    branches: Vec<Branch>,
+    /// Emptied list of branches from the last step, kept so stepping does not allocate
+    spare_branches: Vec<Branch>,
+    /// Edges the branch being stepped can take, kept between steps so stepping does not allocate
+    edges: Vec<Edge>,
+    /// Configurations a nondeterministic PDA has been in by their position in the input, so runs
+    /// of ϵ transitions which leave the stack as it was are only followed once. Positions behind
+    /// every configuration are dropped along with the input
+    visited: HashMap<usize, HashSet<(StateId, Stack)>>,
+    is_started: bool,
+    status: RunStatus,
 }
 
-impl<'a> Clone for PdaInstance<'a> {
-    fn clone(&self) -> Self {
-        PdaInstance {
-            curr_state: self.curr_state.clone(),
-            stack: self.stack.clone(),
-            sim_str: self.sim_str,
-        }
-    }
+/// One of the configurations a PDA can be in along with the ϵ transitions that led to it
+#[derive(Clone)]
+struct Branch {
+    state: StateId,
+    /// Number of characters of the input read so far
+    position: usize,
+    stack: Stack,
+    /// State left and edge taken to reach this configuration
+    reached_by: Option<(StateId, Edge)>,
+    epsilon_run: Vec<EpsilonVisit>,
+}
+
+/// A stack sharing the symbols below its top with the stack it was pushed onto, so configurations
+/// can be copied and remembered without copying their stacks
+#[derive(Clone, Default)]
+struct Stack(Option<Rc<StackNode>>);
+
+struct StackNode {
+    symbol: SymbolId,
+    below: Stack,
+    height: usize,
+    /// Hash of every symbol on the stack, so stacks are hashed without walking down them
+    hash: u64,
+}
+
+/// A configuration visited while following ϵ-transitions without consuming input
+#[derive(Debug, Clone)]
+struct EpsilonVisit {
+    state: StateId,
+    stack_top: Option<SymbolId>,
+    stack_height: usize,
+    /// Lowest the stack has been since this configuration was visited
+    lowest_height: usize,
 }
 
 impl PDA {
-    pub fn simulate(&mut self, mut simulating_string: String) -> Result<bool, SimulationError> {
-        let mut pda_instances = vec![PdaInstance {
-            curr_state: self.automaton_graph.get_start_state(),
-            stack: vec![],
-            sim_str: &simulating_string[..],
+    pub fn simulate(&mut self, simulating_string: String) -> Result<bool, SimulationError> {
+        self.runner(&simulating_string).finish()
+    }
+
+    /// Runs the PDA on a string, keeping every configuration it went through
+    pub fn trace(&self, simulating_string: &str) -> Trace {
+        Trace::record(
+            simulating_string,
+            Runner::Pda(self.runner(simulating_string)),
+        )
+    }
+
+    /// Starts a run on a string which can be stepped through or fed more input
+    ///
+    /// A deterministic PDA never branches so a single configuration is followed, linear in the length
+    /// of the input apart from ϵ-transitions
+    pub fn runner(&self, simulating_string: &str) -> PdaRunner {
+        let mut runner = PdaRunner {
+            graph: self.graph.clone(),
+            is_deterministic: self.is_deterministic,
+            pending: VecDeque::new(),
+            offset: 0,
+            branches: vec![],
+            spare_branches: vec![],
+            edges: vec![],
+            visited: HashMap::new(),
+            is_started: false,
+            status: RunStatus::Running,
+        };
+        runner.reset();
+        runner.pending.extend(simulating_string.chars());
+        runner.status = if runner.has_accepting_branch() {
+            RunStatus::Accepted
+        } else {
+            RunStatus::Running
+        };
+        runner
+    }
+}
+
+impl PdaRunner {
+    pub fn status(&self) -> &RunStatus {
+        &self.status
+    }
+
+    /// Reads more input, carrying on from every configuration the PDA was left in
+    pub fn feed(&mut self, input: &str) {
+        for c in input.chars() {
+            self.feed_char(c);
+        }
+    }
+
+    pub fn feed_char(&mut self, c: char) {
+        self.pending.push_back(c);
+        if !self.branches.is_empty() && !matches!(self.status, RunStatus::Failed(_)) {
+            self.status = RunStatus::Running;
+        }
+        while self.step() {}
+    }
+
+    /// Whether the input fed so far is accepted, following any ϵ-transitions not yet taken
+    pub fn is_accepting(&mut self) -> bool {
+        while self.step() {}
+        matches!(self.status, RunStatus::Accepted)
+    }
+
+    /// Goes back to the start state with an empty stack, forgetting all of the input fed so far
+    pub fn reset(&mut self) {
+        self.pending.clear();
+        self.offset = 0;
+        self.branches = vec![Branch {
+            state: self.graph.start_state(),
+            position: 0,
+            stack: Stack::default(),
+            reached_by: None,
+            epsilon_run: vec![],
         }];
+        self.visited.clear();
+        self.is_started = false;
+        self.status = if self.has_accepting_branch() {
+            RunStatus::Accepted
+        } else {
+            RunStatus::Running
+        };
+    }
 
-        while !pda_instances.is_empty() {
-            let instance = pda_instances.remove(0);
-            // dbg!(&instance);
+    /// Reads the rest of the input, returning whether it was accepted
+    pub fn finish(mut self) -> Result<bool, SimulationError> {
+        while self.step() {}
+        self.status.into_result()
+    }
 
-            let sim_str = instance.sim_str;
+    /// Takes a transition from every configuration, returning false once no configuration can
+    /// move on with the input fed so far or the input has been accepted
+    ///
+    /// Configurations which have read all of the input wait for more, apart from those of a
+    /// deterministic PDA which moved on with an ϵ-transition. So that a nondeterministic PDA
+    /// pushing on ϵ-transitions forever stops, configurations whose stack has reached
+    /// [PdaRunner::stack_limit] also wait for more input rather than pushing on ϵ-transitions
+    fn step(&mut self) -> bool {
+        if !matches!(self.status, RunStatus::Running) {
+            return false;
+        }
+        let stack_limit = self.stack_limit();
 
-            let transition_epsilon = Self::find_epsilon_transitions(&instance);
+        let mut branches = mem::take(&mut self.branches);
+        let mut next_branches = mem::take(&mut self.spare_branches);
+        let mut edges = mem::take(&mut self.edges);
+        let mut has_moved = false;
+        for branch in branches.drain(..) {
+            let next_char = self.pending.get(branch.position - self.offset).copied();
+            let next_symbol = next_char.and_then(|c| self.graph.symbol_id(Symbol::CHAR(c)));
+            let stack_top = branch.stack.top();
+            let is_at_limit = !self.is_deterministic && branch.stack.height() >= stack_limit;
+            let is_held_back = is_at_limit
+                && self
+                    .graph
+                    .edges_on(branch.state, SymbolId::EPSILON)
+                    .iter()
+                    .any(|edge| Self::can_pop(edge, stack_top) && Self::grows_stack(edge));
 
-            // Accept state with an empty string and at an accept state
-            if sim_str.is_empty() && instance.curr_state.is_accept_state {
-                return Ok(true);
-            }
+            edges.clear();
+            edges.extend(
+                self.graph
+                    .edges_on(branch.state, SymbolId::EPSILON)
+                    .iter()
+                    .chain(match next_symbol {
+                        Some(symbol_id) => self.graph.edges_on(branch.state, symbol_id),
+                        None => &[],
+                    })
+                    .filter(|edge| {
+                        Self::can_pop(edge, stack_top)
+                            && !self.is_epsilon_loop(&branch, edge)
+                            && !(is_at_limit
+                                && edge.symbol == SymbolId::EPSILON
+                                && Self::grows_stack(edge))
+                    }),
+            );
+
+            let is_waiting = is_held_back
+                || (next_char.is_none() && (!self.is_deterministic || edges.is_empty()));
+            let mut branch = Some(branch);
+            for (idx, edge) in edges.iter().enumerate() {
+                // The last edge taken can be given the branch rather than a copy of it
+                let next_branch = if idx + 1 == edges.len() && !is_waiting {
+                    branch.take()
+                } else {
+                    branch.clone()
+                }
+                .map(|branch| self.take_edge(branch, edge))
+                .expect("the branch is only given away on the last edge");
 
-            /*
-               If your in a state where u have an empty transition but no epsilon transitions to use on
-               then pick another instance
-            */
-            if transition_epsilon.is_empty() && sim_str.is_empty() {
-                continue;
+                if self.is_deterministic
+                    || self
+                        .visited
+                        .entry(next_branch.position)
+                        .or_default()
+                        .insert((next_branch.state, next_branch.stack.clone()))
+                {
+                    next_branches.push(next_branch);
+                    has_moved = true;
+                }
+            }
+            if let (true, Some(branch)) = (is_waiting, branch) {
+                next_branches.push(branch);
             }
+        }
+        self.spare_branches = branches;
+        self.edges = edges;
+        self.branches = next_branches;
 
-            let transitions_epsilon = Self::find_epsilon_transitions(&instance);
+        // Input every configuration has read is no longer needed
+        let lowest_position = self
+            .branches
+            .iter()
+            .map(|branch| branch.position)
+            .min()
+            .unwrap_or(self.offset + self.pending.len());
+        while self.offset < lowest_position {
+            self.pending.pop_front();
+            self.offset += 1;
+        }
+        let offset = self.offset;
+        self.visited.retain(|position, _| *position >= offset);
 
-            //  Generate all instances for all epsilon transitions
-            for e in transitions_epsilon {
-                pda_instances.push(PdaInstance {
-                    curr_state: e.to(),
-                    stack: instance.stack.clone(),
-                    sim_str: &sim_str[..],
-                })
+        if self.has_accepting_branch() {
+            self.status = RunStatus::Accepted;
+        } else if !has_moved {
+            self.status = RunStatus::Rejected;
+        }
+        has_moved
+    }
+
+    /// Whether an edge can be taken given the top of the stack, its symbol having been read already
+    fn can_pop(edge: &Edge, stack_top: Option<SymbolId>) -> bool {
+        match edge.pop_symbol {
+            None | Some(SymbolId::EPSILON) => true,
+            Some(symbol_id) => stack_top == Some(symbol_id),
+        }
+    }
+
+    /// Whether an edge leaves the stack one symbol higher
+    fn grows_stack(edge: &Edge) -> bool {
+        matches!(edge.pop_symbol, None | Some(SymbolId::EPSILON))
+            && matches!(edge.push_symbol, Some(symbol_id) if symbol_id != SymbolId::EPSILON)
+    }
+
+    /// Height a nondeterministic PDA can push its stack to with ϵ-transitions, growing with the
+    /// input fed so far so that pushes the rest of the input could pop are still followed
+    fn stack_limit(&self) -> usize {
+        self.graph.state_count()
+            * self.graph.symbol_count()
+            * (self.offset + self.pending.len() + 1)
+    }
+
+    /// Whether an ϵ-transition of a deterministic PDA returns to the state and stack top of a
+    /// configuration of the run of ϵ-transitions it is part of, at least as high up the stack and
+    /// without having popped more than that stack top since
+    ///
+    /// Only the stack top and what the run pushed itself have been looked at in between, so the run
+    /// would repeat forever and the transition is not taken
+    fn is_epsilon_loop(&self, branch: &Branch, edge: &Edge) -> bool {
+        if !self.is_deterministic || edge.symbol != SymbolId::EPSILON {
+            return false;
+        }
+
+        let popped_stack = match edge.pop_symbol {
+            None | Some(SymbolId::EPSILON) => branch.stack.clone(),
+            Some(_) => branch.stack.popped(),
+        };
+        let popped_height = popped_stack.height();
+        let (stack_top, pushed_height) = match edge.push_symbol {
+            Some(symbol_id) if symbol_id != SymbolId::EPSILON => {
+                (Some(symbol_id), popped_height + 1)
             }
+            _ => (popped_stack.top(), popped_height),
+        };
+        let current = EpsilonVisit {
+            state: branch.state,
+            stack_top: branch.stack.top(),
+            stack_height: branch.stack.height(),
+            lowest_height: branch.stack.height(),
+        };
 
-            if sim_str.is_empty() {
-                continue;
+        branch
+            .epsilon_run
+            .iter()
+            .chain(iter::once(&current))
+            .any(|visit| {
+                visit.state == edge.to
+                    && visit.stack_top == stack_top
+                    && visit.lowest_height.min(popped_height) + 1 >= visit.stack_height
+                    && pushed_height >= visit.stack_height
+            })
+    }
+
+    /// Moves a configuration along an edge, which was already checked to be one it can take
+    fn take_edge(&self, mut branch: Branch, edge: &Edge) -> Branch {
+        let from = branch.state;
+        if edge.symbol != SymbolId::EPSILON {
+            branch.position += 1;
+            branch.epsilon_run.clear();
+        } else if self.is_deterministic {
+            branch.epsilon_run.push(EpsilonVisit {
+                state: from,
+                stack_top: branch.stack.top(),
+                stack_height: branch.stack.height(),
+                lowest_height: branch.stack.height(),
+            });
+        }
+
+        // The symbol popped was already matched when picking the edge
+        if !matches!(edge.pop_symbol, None | Some(SymbolId::EPSILON)) {
+            branch.stack = branch.stack.popped();
+        }
+        for visit in branch.epsilon_run.iter_mut() {
+            visit.lowest_height = visit.lowest_height.min(branch.stack.height());
+        }
+        if let Some(symbol_id) = edge.push_symbol {
+            if symbol_id != SymbolId::EPSILON {
+                branch.stack = branch.stack.pushed(symbol_id);
             }
+        }
 
-            let transitions_sym =
-                Self::find_transitions_on_symbol(&instance, sim_str.as_bytes()[0] as char);
-
-            //  Generate all instances for all non-epsilon transitions
-            for t in transitions_sym {
-                let mut stack = instance.stack.clone();
-
-                // Pop from the stack
-                if let Some(to_pop_sym) = t.pop_symbol() {
-                    // Find symbol to pop
-                    if to_pop_sym != Symbol::EPSILON {
-                        if let Some(popped_sym) = stack.pop() {
-                            // If the symbol popped from the stack is diff from what you wanted then the simulation ends
-                            if popped_sym != to_pop_sym {
-                                return Ok(false);
-                            }
-                        } else {
-                            continue;
-                        }
-                    }
-                } else {
-                    panic!("No pop symbol attached to current transition {:?}", t)
-                }
+        branch.state = edge.to;
+        branch.reached_by = Some((from, *edge));
+        branch
+    }
+
+    /// Whether a configuration has read all of the input fed so far and is at an accept state
+    fn has_accepting_branch(&self) -> bool {
+        self.branches.iter().any(|branch| {
+            branch.position == self.offset + self.pending.len()
+                && self.graph.is_accept_state(branch.state)
+        })
+    }
+}
+
+impl Stack {
+    fn top(&self) -> Option<SymbolId> {
+        self.0.as_ref().map(|node| node.symbol)
+    }
 
-                // Push to the stack
-                if let Some(symbol_to_push) = t.push_symbol() {
-                    if symbol_to_push != Symbol::EPSILON {
-                        stack.push(symbol_to_push)
+    fn height(&self) -> usize {
+        self.0.as_ref().map_or(0, |node| node.height)
+    }
+
+    fn hash_value(&self) -> u64 {
+        self.0.as_ref().map_or(0, |node| node.hash)
+    }
+
+    /// The stack with a symbol pushed on top, leaving this stack as it is
+    fn pushed(&self, symbol: SymbolId) -> Stack {
+        let mut hasher = DefaultHasher::new();
+        (self.hash_value(), symbol).hash(&mut hasher);
+        Stack(Some(Rc::new(StackNode {
+            symbol,
+            below: self.clone(),
+            height: self.height() + 1,
+            hash: hasher.finish(),
+        })))
+    }
+
+    /// The stack with its top popped, leaving this stack as it is
+    fn popped(&self) -> Stack {
+        self.0
+            .as_ref()
+            .map_or_else(Stack::default, |node| node.below.clone())
+    }
+
+    /// Symbols on the stack with its top last
+    fn symbols(&self) -> Vec<SymbolId> {
+        let mut symbols = Vec::with_capacity(self.height());
+        let mut node = &self.0;
+        while let Some(stack_node) = node {
+            symbols.push(stack_node.symbol);
+            node = &stack_node.below.0;
+        }
+        symbols.reverse();
+        symbols
+    }
+}
+
+impl PartialEq for Stack {
+    fn eq(&self, other: &Self) -> bool {
+        let (mut node, mut other_node) = (&self.0, &other.0);
+        loop {
+            match (node, other_node) {
+                (None, None) => return true,
+                (Some(stack_node), Some(other_stack_node)) => {
+                    // Stacks pushed onto the same stack share everything below
+                    if Rc::ptr_eq(stack_node, other_stack_node) {
+                        return true;
                     }
-                } else {
-                    panic!("No pop symbol attached to current transition {:?}", t)
+                    if stack_node.hash != other_stack_node.hash
+                        || stack_node.height != other_stack_node.height
+                        || stack_node.symbol != other_stack_node.symbol
+                    {
+                        return false;
+                    }
+                    node = &stack_node.below.0;
+                    other_node = &other_stack_node.below.0;
                 }
-
-                // Add to the stack the new instance pushed
-                let new_instance = PdaInstance {
-                    curr_state: t.to(),
-                    stack,
-                    sim_str: &sim_str[1..],
-                };
-                dbg!(&new_instance);
-                pda_instances.push(new_instance);
+                _ => return false,
             }
         }
-
-        Ok(false)
     }
+}
+
+impl Eq for Stack {}
 
-    fn find_transitions_on_symbol(instance: &PdaInstance, c: char) -> Vec<Transition> {
-        let transitions =
-            Transition::find_transition_by_symbol(instance.curr_state.get_transitions(), c);
-        transitions
+impl Hash for Stack {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write_u64(self.hash_value());
     }
+}
 
-    fn find_epsilon_transitions(instance: &PdaInstance) -> Vec<Transition> {
-        Transition::find_epsilon_transitions(instance.curr_state.get_transitions())
+impl Drop for Stack {
+    fn drop(&mut self) {
+        // Nodes no other stack shares are dropped one at a time, as dropping them recursively
+        // could overflow the call stack for tall stacks
+        let mut node = self.0.take();
+        while let Some(stack_node) = node {
+            node = match Rc::try_unwrap(stack_node) {
+                Ok(mut stack_node) => stack_node.below.0.take(),
+                Err(_) => None,
+            };
+        }
     }
+}
 
-    fn pda_assert(
-        transitions: Vec<Transition>,
-        curr_symbol: char,
-        symbol_index: usize,
-        curr_state: &Rc<State>,
-    ) -> Result<(), SimulationError> {
-        return if transitions.is_empty() {
-            Err(SimulationError::NoTransitionForSymbol(format!(
-                "No transition symbol found for character {} at index {} on state {:#?}",
-                curr_symbol, symbol_index, curr_state
-            )))
-        } else {
-            Ok(())
-        };
+impl Iterator for PdaRunner {
+    type Item = Vec<Configuration>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.is_started && !self.step() {
+            return None;
+        }
+        self.is_started = true;
+
+        Some(
+            self.branches
+                .iter()
+                .map(|branch| Configuration {
+                    state: self.graph.state(branch.state).clone(),
+                    position: branch.position,
+                    stack: branch
+                        .stack
+                        .symbols()
+                        .iter()
+                        .map(|symbol_id| self.graph.symbol(*symbol_id))
+                        .collect(),
+                    reached_by: branch.reached_by.map(|(from, edge)| {
+                        (
+                            self.graph.state(from).clone(),
+                            self.graph.transition(&edge).clone(),
+                        )
+                    }),
+                })
+                .collect(),
+        )
     }
 }
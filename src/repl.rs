@@ -0,0 +1,838 @@
+use std::fmt::Write;
+use std::fs;
+use std::path::Path;
+use std::rc::Rc;
+
+use crate::automaton_graph::AutomatonType::{DFA, NFA, PDA};
+use crate::automaton_graph::{Automaton, AutomatonType};
+use crate::bulk_tests::BulkTestReport;
+use crate::cli::{self, ConvertFormat, InputFormat};
+use crate::debugger::Debugger;
+use crate::dfa::{self, ClassNaming};
+use crate::parser::{Parser, Span};
+use crate::pda;
+use crate::repl::line_editor::LineEditor;
+use crate::simulator::{RunStatus, Simulator};
+
+mod line_editor;
+
+const PROMPT: &str = "automaton> ";
+
+/// Every command of the debugger along with how it is used
+const DEBUG_COMMANDS: [(&str, &str); 8] = [
+    ("step", "step [count]          Takes the next step, or a number of steps, as does an empty line"),
+    ("back", "back [count]          Goes back a step, or a number of steps"),
+    ("run", "run [position]        Runs until a breakpoint is hit, or until the input has been read up to a position"),
+    ("break", "break [state]...      Adds breakpoints on states, or lists the breakpoints"),
+    ("unbreak", "unbreak <state>...    Removes the breakpoints on states"),
+    ("show", "show                  Prints the configurations at the current step"),
+    ("help", "help                  Prints this message"),
+    ("quit", "quit                  Stops debugging, as does exit or Ctrl-D"),
+];
+
+/// Every command of the REPL along with how it is used
+const COMMANDS: [(&str, &str); 19] = [
+    ("load", "load <file> [as <name>]       Loads an automaton, named after its file unless a name is given"),
+    ("list", "list                          Lists the loaded automata"),
+    ("unload", "unload <name>                 Forgets an automaton"),
+    ("show", "show <name>                   Prints an automaton in the text format"),
+    ("sim", "sim <name> <string>...        Runs an automaton on each string, \"\" being the empty string"),
+    ("debug", "debug <name> <string>         Steps through a run of an automaton on a string"),
+    ("test", "test <name>                   Runs the strings saved with an automaton"),
+    ("reduce", "reduce <name> [as <new name>] [naming <class|merged|first>]  Loads the minimal DFA for a DFA, named <name>_min unless a name is given"),
+    ("eq", "eq <name> <name>              Checks whether two DFAs or NFAs accept the same language"),
+    ("intersect", "intersect <pda> <dfa> [as <name>]  Loads the PDA accepting what both accept, named <pda>_<dfa> unless a name is given"),
+    ("export", "export <name> <format> [file] Writes an automaton as site, jff, dot, tikz, svg, mermaid, dsl, csv or md"),
+    ("add", "add <name> <state> [<label> <state>]  Adds a state, or transitions written as in the text format, Ex: a,X/Y"),
+    ("remove", "remove <name> <state> [<label> <state>]  Removes a state, or the transitions on the symbols of a label"),
+    ("start", "start <name> <state>          Makes a state the start state"),
+    ("accept", "accept <name> <state>...      Makes states accept states"),
+    ("unaccept", "unaccept <name> <state>...    Stops states from being accept states"),
+    ("history", "history                       Lists the commands entered"),
+    ("help", "help                          Prints this message"),
+    ("quit", "quit                          Leaves the REPL, as does exit or Ctrl-D"),
+];
+
+/// Keeps automata loaded between commands so they can be simulated, edited and compared
+pub struct Repl {
+    /// Automata along with their names, in the order they were loaded
+    automata: Vec<(String, Automaton)>,
+    editor: LineEditor,
+}
+
+impl Repl {
+    pub fn new() -> Repl {
+        Repl {
+            automata: vec![],
+            editor: LineEditor::new(),
+        }
+    }
+
+    /// Loads each file and reads commands until quit, exit or the end of input
+    pub fn run(&mut self, files: &[String]) {
+        for file in files {
+            Self::print_result(self.execute(&format!("load \"{}\"", file)));
+        }
+
+        loop {
+            let loaded = self
+                .automata
+                .iter()
+                .map(|(name, automaton)| {
+                    (
+                        name.clone(),
+                        automaton
+                            .all_states()
+                            .iter()
+                            .map(|state| state.id.clone())
+                            .collect(),
+                    )
+                })
+                .collect::<Vec<(String, Vec<String>)>>();
+            let complete = |line: &str| Self::complete(line, &loaded);
+
+            let Some(line) = self.editor.read_line(PROMPT, &complete) else {
+                break;
+            };
+            if matches!(line.trim(), "quit" | "exit") {
+                break;
+            }
+
+            Self::print_result(self.execute(&line));
+        }
+    }
+
+    fn print_result(result: Result<String, String>) {
+        match result {
+            Ok(output) => print!("{}", output),
+            Err(message) => eprintln!("{}", message.trim_end()),
+        }
+    }
+
+    /// Runs a command, returning what it printed or why it failed
+    pub fn execute(&mut self, line: &str) -> Result<String, String> {
+        let words = Self::split_words(line)?;
+        let Some((command, args)) = words.split_first() else {
+            return Ok(String::new());
+        };
+        let args = args.iter().map(String::as_str).collect::<Vec<&str>>();
+
+        match (command.as_str(), &args[..]) {
+            ("load", [file]) => self.load(file, &Self::name_from_file(file)?),
+            ("load", [file, "as", name]) => self.load(file, name),
+            ("list", []) => Ok(self.list()),
+            ("unload", [name]) => {
+                self.find(name)?;
+                self.automata.retain(|(loaded_name, _)| loaded_name != name);
+                Ok(String::new())
+            }
+            ("show", [name]) => Ok(self.find(name)?.to_dsl()),
+            ("sim", [name, strings @ ..]) if !strings.is_empty() => self.simulate(name, strings),
+            ("debug", [name, string]) => self.debug(name, string),
+            ("test", [name]) => Ok(BulkTestReport::run(name, self.find(name)?.clone()).to_text()),
+            ("reduce", [name]) => self.reduce(name, &format!("{}_min", name), None),
+            ("reduce", [name, "as", new_name]) => self.reduce(name, new_name, None),
+            ("reduce", [name, "naming", naming]) => {
+                self.reduce(name, &format!("{}_min", name), Some(naming))
+            }
+            ("reduce", [name, "as", new_name, "naming", naming]) => {
+                self.reduce(name, new_name, Some(naming))
+            }
+            ("eq", [name, other_name]) => self.equivalent(name, other_name),
+            ("intersect", [pda_name, dfa_name]) => {
+                self.intersect(pda_name, dfa_name, &format!("{}_{}", pda_name, dfa_name))
+            }
+            ("intersect", [pda_name, dfa_name, "as", new_name]) => {
+                self.intersect(pda_name, dfa_name, new_name)
+            }
+            ("export", [name, format]) => self.export(name, format),
+            ("export", [name, format, file]) => {
+                fs::write(file, self.export(name, format)?)
+                    .map_err(|err| format!("Could not write to the file {}: {}", file, err))?;
+                Ok(String::new())
+            }
+            ("add", [name, state]) => self
+                .find_mut(name)?
+                .add_state(state)
+                .map(|_| String::new())
+                .map_err(|err| err.to_string()),
+            ("add", [name, from, label, to]) => self.edit_edges(name, from, label, to, true),
+            ("remove", [name, state]) => self
+                .find_mut(name)?
+                .remove_state(state)
+                .map(|_| String::new())
+                .map_err(|err| err.to_string()),
+            ("remove", [name, from, label, to]) => self.edit_edges(name, from, label, to, false),
+            ("start", [name, state]) => self
+                .find_mut(name)?
+                .set_start_state(state)
+                .map(|_| String::new())
+                .map_err(|err| err.to_string()),
+            ("accept" | "unaccept", [name, states @ ..]) if !states.is_empty() => {
+                let automaton = self.find_mut(name)?;
+                for state in states {
+                    automaton
+                        .set_accept_state(state, command == "accept")
+                        .map_err(|err| err.to_string())?;
+                }
+                Ok(String::new())
+            }
+            ("history", []) => Ok(self
+                .editor
+                .history()
+                .iter()
+                .enumerate()
+                .map(|(idx, line)| format!("{:>4}  {}\n", idx + 1, line))
+                .collect()),
+            ("help", []) => Ok(COMMANDS
+                .iter()
+                .map(|(_, usage)| format!("{}\n", usage))
+                .collect()),
+            _ => match COMMANDS.iter().find(|(name, _)| name == command) {
+                Some((_, usage)) => Err(format!("Usage: {}", usage)),
+                None => Err(format!(
+                    "Unknown command '{}', help lists the commands",
+                    command
+                )),
+            },
+        }
+    }
+
+    fn load(&mut self, file: &str, name: &str) -> Result<String, String> {
+        Self::check_name(name)?;
+        let program = fs::read_to_string(file)
+            .map_err(|err| format!("Could not read the file {}: {}", file, err))?;
+        let automaton = cli::load_automaton(file, &program, InputFormat::detect(file, &program))?;
+
+        let output = format!(
+            "Loaded {} as {}, a {} with {} states\n",
+            file,
+            name,
+            Self::type_name(automaton.automaton_type),
+            automaton.all_states().len()
+        );
+        self.insert(name, automaton);
+        Ok(output)
+    }
+
+    fn list(&self) -> String {
+        if self.automata.is_empty() {
+            return String::from("No automata are loaded\n");
+        }
+
+        let mut output = String::new();
+        for (name, automaton) in &self.automata {
+            // Writing to a String cannot fail
+            let _ = writeln!(
+                output,
+                "{}\t{}\t{} states",
+                name,
+                Self::type_name(automaton.automaton_type),
+                automaton.all_states().len()
+            );
+        }
+        output
+    }
+
+    fn simulate(&self, name: &str, strings: &[&str]) -> Result<String, String> {
+        let simulator = Simulator::new(self.find(name)?.clone());
+        // A single run is fed each string in turn
+        let mut runner = simulator.runner("");
+
+        let mut output = String::new();
+        for string in strings {
+            runner.reset();
+            runner.feed(string);
+            let accepted = runner.is_accepting();
+            if let RunStatus::Failed(err) = runner.status() {
+                return Err(format!("{:?}", err));
+            }
+            let _ = writeln!(
+                output,
+                "{}\t\"{}\"",
+                if accepted { "accept" } else { "reject" },
+                string
+            );
+        }
+        Ok(output)
+    }
+
+    /// Loads the minimal DFA for a DFA, displaying its states by the states merged into them
+    /// unless another naming is given
+    fn reduce(
+        &mut self,
+        name: &str,
+        new_name: &str,
+        naming: Option<&str>,
+    ) -> Result<String, String> {
+        let naming = match naming {
+            Some(naming) => ClassNaming::from_name(naming)
+                .ok_or_else(|| format!("Unknown naming '{}'", naming))?,
+            None => ClassNaming::default(),
+        };
+        Self::check_name(new_name)?;
+        let automaton = self.find(name)?;
+        if !matches!(automaton.automaton_type, DFA) {
+            return Err(format!("{} is not a DFA, only DFAs can be reduced", name));
+        }
+
+        let state_count = automaton.all_states().len();
+        let Some(steps) = dfa::DFA::new(automaton.clone()).reduce_with_naming(naming) else {
+            return Ok(format!("{} cannot be reduced any further\n", name));
+        };
+
+        let output = format!(
+            "Reduced {} from {} to {} states as {}\n",
+            name,
+            state_count,
+            steps.reduced_dfa.all_states().len(),
+            new_name
+        );
+        self.insert(new_name, steps.reduced_dfa);
+        Ok(output)
+    }
+
+    fn equivalent(&self, name: &str, other_name: &str) -> Result<String, String> {
+        let (automaton, other) = (self.find(name)?, self.find(other_name)?);
+        if matches!(automaton.automaton_type, PDA) || matches!(other.automaton_type, PDA) {
+            return Err(String::from(
+                "Whether PDAs accept the same language cannot be decided",
+            ));
+        }
+
+        Ok(match automaton.distinguishing_string(other) {
+            None => format!("{} and {} accept the same language\n", name, other_name),
+            Some(string) => {
+                let (accepting, rejecting) = if Simulator::new(automaton.clone())
+                    .accepts(&string)
+                    .unwrap_or_default()
+                {
+                    (name, other_name)
+                } else {
+                    (other_name, name)
+                };
+                format!(
+                    "{} and {} differ on \"{}\", which {} accepts and {} rejects\n",
+                    name, other_name, string, accepting, rejecting
+                )
+            }
+        })
+    }
+
+    fn intersect(
+        &mut self,
+        pda_name: &str,
+        dfa_name: &str,
+        new_name: &str,
+    ) -> Result<String, String> {
+        Self::check_name(new_name)?;
+        let (pda, dfa) = (self.find(pda_name)?, self.find(dfa_name)?);
+        if !matches!(pda.automaton_type, PDA) {
+            return Err(format!(
+                "{} is not a PDA, only PDAs can be intersected",
+                pda_name
+            ));
+        }
+        if !matches!(dfa.automaton_type, DFA) {
+            return Err(format!(
+                "{} is not a DFA, PDAs can only be intersected with DFAs",
+                dfa_name
+            ));
+        }
+
+        let product = pda::PDA::new(Rc::new(pda.clone())).intersect(&dfa::DFA::new(dfa.clone()));
+        let output = format!(
+            "Intersected {} with {} as {}, a PDA with {} states\n",
+            pda_name,
+            dfa_name,
+            new_name,
+            product.all_states().len()
+        );
+        self.insert(new_name, product);
+        Ok(output)
+    }
+
+    fn export(&self, name: &str, format: &str) -> Result<String, String> {
+        let format = ConvertFormat::from_name(format)
+            .ok_or_else(|| format!("Unknown format '{}'", format))?;
+        cli::convert(self.find(name)?, format)
+    }
+
+    /// Adds or removes the transitions between two states written in a label of the text format
+    ///
+    /// Transitions pushing several symbols are added through new states, as in the text format
+    fn edit_edges(
+        &mut self,
+        name: &str,
+        from: &str,
+        label: &str,
+        to: &str,
+        is_adding: bool,
+    ) -> Result<String, String> {
+        let label_span = Span {
+            line: 1,
+            column: 1,
+            len: label.chars().count() as u32,
+        };
+        let transitions =
+            Parser::parse_dsl_label(self.find(name)?.automaton_type, label, label_span)
+                .map_err(|err| err.message)?;
+
+        let automaton = self.find_mut(name)?;
+        for steps in transitions {
+            if !is_adding {
+                if steps.len() > 1 {
+                    return Err(format!(
+                        "'{}' was added through states of its own, remove those states instead",
+                        label
+                    ));
+                }
+                let (symbol, _, _) = steps[0];
+                automaton
+                    .remove_edge(from, to, symbol)
+                    .map_err(|err| err.to_string())?;
+                continue;
+            }
+
+            let mut step_ids = vec![String::from(from)];
+            for _ in 1..steps.len() {
+                let mut id = format!("{}'", step_ids[step_ids.len() - 1]);
+                while automaton.all_states().iter().any(|state| state.id == id) {
+                    id.push('\'');
+                }
+                automaton.add_state(&id).map_err(|err| err.to_string())?;
+                step_ids.push(id);
+            }
+            step_ids.push(String::from(to));
+
+            let added =
+                steps
+                    .iter()
+                    .zip(step_ids.windows(2))
+                    .try_for_each(|((symbol, pop, push), ids)| {
+                        automaton.add_edge(&ids[0], &ids[1], *symbol, *pop, *push)
+                    });
+            if let Err(err) = added {
+                // Removing the new states also removes any of the steps already added
+                for id in &step_ids[1..step_ids.len() - 1] {
+                    let _ = automaton.remove_state(id);
+                }
+                return Err(err.to_string());
+            }
+        }
+        Ok(String::new())
+    }
+
+    /// Steps through a run of an automaton, reading debugger commands until quit
+    fn debug(&mut self, name: &str, input: &str) -> Result<String, String> {
+        let automaton = self.find(name)?;
+        let state_ids = automaton
+            .all_states()
+            .iter()
+            .map(|state| state.id.clone())
+            .collect::<Vec<String>>();
+        let simulator = Simulator::new(automaton.clone());
+        let mut debugger = Debugger::new(&simulator, input);
+        print!("{}", debugger.render());
+
+        let complete = |line: &str| Self::complete_debug(line, &state_ids);
+        // The prompt shows which step of the run is being shown
+        while let Some(line) = self
+            .editor
+            .read_line(&format!("debug {}> ", debugger.step_number()), &complete)
+        {
+            match Self::execute_debug(&mut debugger, &state_ids, &line) {
+                Ok(Some(output)) => print!("{}", output),
+                Ok(None) => break,
+                Err(message) => eprintln!("{}", message),
+            }
+        }
+
+        Ok(String::new())
+    }
+
+    /// Runs a debugger command, returning what it printed, [None] when debugging should stop
+    /// or why the command failed
+    fn execute_debug(
+        debugger: &mut Debugger,
+        state_ids: &[String],
+        line: &str,
+    ) -> Result<Option<String>, String> {
+        let words = line.split_whitespace().collect::<Vec<&str>>();
+        let count = |word: &str| {
+            word.parse::<usize>()
+                .map_err(|_| format!("'{}' is not a number", word))
+        };
+        let check_states = |states: &[&str]| match states
+            .iter()
+            .find(|state| !state_ids.iter().any(|state_id| state_id == *state))
+        {
+            Some(state) => Err(format!("There is no state '{}'", state)),
+            None => Ok(()),
+        };
+
+        let mut output = String::new();
+        match words[..] {
+            [] | ["step" | "s"] => {
+                debugger.step();
+            }
+            ["step" | "s", steps] => {
+                for _ in 0..count(steps)? {
+                    debugger.step();
+                }
+            }
+            ["back" | "b"] => {
+                debugger.back();
+            }
+            ["back" | "b", steps] => {
+                for _ in 0..count(steps)? {
+                    debugger.back();
+                }
+            }
+            ["run" | "r"] => {
+                if let Some(state_id) = debugger.continue_run() {
+                    output = format!("Stopped at the breakpoint on {}\n", state_id);
+                }
+            }
+            ["run" | "r", position] => debugger.run_to(count(position)?),
+            ["break"] => {
+                return Ok(Some(if debugger.breakpoints().is_empty() {
+                    String::from("No breakpoints are set\n")
+                } else {
+                    format!("Breakpoints: {}\n", debugger.breakpoints().join(", "))
+                }))
+            }
+            ["break", ref states @ ..] => {
+                check_states(states)?;
+                for state in states {
+                    debugger.add_breakpoint(state);
+                }
+            }
+            ["unbreak", ref states @ ..] if !states.is_empty() => {
+                check_states(states)?;
+                for state in states {
+                    debugger.remove_breakpoint(state);
+                }
+            }
+            ["show"] => {}
+            ["help"] => {
+                return Ok(Some(
+                    DEBUG_COMMANDS
+                        .iter()
+                        .map(|(_, usage)| format!("{}\n", usage))
+                        .collect(),
+                ))
+            }
+            ["quit" | "exit" | "q"] => return Ok(None),
+            [command, ..] => {
+                return Err(
+                    match DEBUG_COMMANDS.iter().find(|(name, _)| *name == command) {
+                        Some((_, usage)) => format!("Usage: {}", usage),
+                        None => format!("Unknown command '{}', help lists the commands", command),
+                    },
+                )
+            }
+        }
+
+        output.push_str(&debugger.render());
+        Ok(Some(output))
+    }
+
+    /// Finds what the last word of a debugger command could be completed to
+    fn complete_debug(line: &str, state_ids: &[String]) -> Vec<String> {
+        let words = line.split_whitespace().collect::<Vec<&str>>();
+        let word = if line.ends_with(char::is_whitespace) || words.is_empty() {
+            ""
+        } else {
+            words[words.len() - 1]
+        };
+        let is_first_word = words.is_empty() || (words.len() == 1 && !word.is_empty());
+
+        let candidates: Vec<String> = if is_first_word {
+            DEBUG_COMMANDS
+                .iter()
+                .map(|(name, _)| name.to_string())
+                .collect()
+        } else if matches!(words[0], "break" | "unbreak") {
+            state_ids.to_vec()
+        } else {
+            vec![]
+        };
+
+        candidates
+            .into_iter()
+            .filter(|candidate| candidate.starts_with(word))
+            .collect()
+    }
+
+    fn find(&self, name: &str) -> Result<&Automaton, String> {
+        self.automata
+            .iter()
+            .find(|(loaded_name, _)| loaded_name == name)
+            .map(|(_, automaton)| automaton)
+            .ok_or_else(|| format!("No automaton is named {}", name))
+    }
+
+    fn find_mut(&mut self, name: &str) -> Result<&mut Automaton, String> {
+        self.automata
+            .iter_mut()
+            .find(|(loaded_name, _)| loaded_name == name)
+            .map(|(_, automaton)| automaton)
+            .ok_or_else(|| format!("No automaton is named {}", name))
+    }
+
+    /// Adds an automaton, replacing any automaton with the same name
+    fn insert(&mut self, name: &str, automaton: Automaton) {
+        match self.find_mut(name) {
+            Ok(loaded) => *loaded = automaton,
+            Err(_) => self.automata.push((String::from(name), automaton)),
+        }
+    }
+
+    fn type_name(automaton_type: AutomatonType) -> &'static str {
+        match automaton_type {
+            DFA => "DFA",
+            NFA => "NFA",
+            PDA => "PDA",
+        }
+    }
+
+    /// Names an automaton after its file, Ex: dfas/even.json is named even
+    fn name_from_file(file: &str) -> Result<String, String> {
+        let name = Path::new(file)
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .unwrap_or_default()
+            .replace(['-', '.', ' '], "_");
+        Self::check_name(&name).map(|_| name).map_err(|_| {
+            format!(
+                "Cannot name the automaton after {}, use load {} as <name>",
+                file, file
+            )
+        })
+    }
+
+    /// Names are letters, digits and underscores so they can be told apart from the other words of a command
+    fn check_name(name: &str) -> Result<(), String> {
+        if !name.is_empty() && name.chars().all(|c| c.is_alphanumeric() || c == '_') {
+            Ok(())
+        } else {
+            Err(format!(
+                "'{}' cannot be used as a name, names are made of letters, digits and _",
+                name
+            ))
+        }
+    }
+
+    /// Splits a command into words on spaces, where words can be quoted to hold spaces or be empty
+    fn split_words(line: &str) -> Result<Vec<String>, String> {
+        let mut words = vec![];
+        let mut word: Option<String> = None;
+        let mut chars = line.chars();
+
+        while let Some(c) = chars.next() {
+            match c {
+                '"' => {
+                    let mut quoted = word.take().unwrap_or_default();
+                    loop {
+                        match chars.next() {
+                            Some('"') => break,
+                            Some(c) => quoted.push(c),
+                            None => return Err(String::from("No closing '\"' was found")),
+                        }
+                    }
+                    word = Some(quoted);
+                }
+                c if c.is_whitespace() => words.extend(word.take()),
+                c => word.get_or_insert_with(String::new).push(c),
+            }
+        }
+        words.extend(word);
+
+        Ok(words)
+    }
+
+    /// Finds what the last word of a line could be completed to
+    ///
+    /// # Arguments
+    ///
+    /// * `line`: The line up to the cursor
+    /// * `loaded`: Names of the loaded automata along with the ids of their states
+    fn complete(line: &str, loaded: &[(String, Vec<String>)]) -> Vec<String> {
+        let state_ids = |name: &str| {
+            loaded
+                .iter()
+                .find(|(loaded_name, _)| loaded_name == name)
+                .map(|(_, state_ids)| state_ids.clone())
+                .unwrap_or_default()
+        };
+        let mut words = line.split_whitespace().collect::<Vec<&str>>();
+        let word = if line.ends_with(char::is_whitespace) || words.is_empty() {
+            ""
+        } else {
+            words.pop().unwrap_or_default()
+        };
+        let strings = |words: &[&str]| words.iter().map(|word| word.to_string()).collect();
+
+        let candidates: Vec<String> = match words[..] {
+            [] => strings(&COMMANDS.map(|(name, _)| name)),
+            ["load"] | ["export", _, _] => return Self::complete_path(word),
+            ["load", _] | ["intersect", _, _] => strings(&["as"]),
+            ["reduce", _] => strings(&["as", "naming"]),
+            ["reduce", _, "as", _] => strings(&["naming"]),
+            ["reduce", .., "naming"] => strings(&ClassNaming::NAMES),
+            ["export", _] => strings(&ConvertFormat::NAMES),
+            ["eq" | "intersect", _] | [_] => loaded.iter().map(|(name, _)| name.clone()).collect(),
+            ["add" | "remove", name, _, _] | ["add" | "remove" | "start", name] => state_ids(name),
+            ["accept" | "unaccept", name, ..] => state_ids(name),
+            _ => vec![],
+        };
+
+        candidates
+            .into_iter()
+            .filter(|candidate| candidate.starts_with(word))
+            .collect()
+    }
+
+    /// Files and folders a partly typed path could be completed to, folders ending in /
+    fn complete_path(path: &str) -> Vec<String> {
+        let (folder, file_start) = match path.rsplit_once('/') {
+            Some((folder, file_start)) => (format!("{}/", folder), file_start),
+            None => (String::new(), path),
+        };
+
+        let Ok(entries) = fs::read_dir(if folder.is_empty() { "." } else { &folder }) else {
+            return vec![];
+        };
+        let mut paths = entries
+            .filter_map(|entry| entry.ok())
+            .filter_map(|entry| {
+                let file_name = entry.file_name().into_string().ok()?;
+                if !file_name.starts_with(file_start) {
+                    return None;
+                }
+                let is_folder = entry.file_type().is_ok_and(|file_type| file_type.is_dir());
+                Some(format!(
+                    "{}{}{}",
+                    folder,
+                    file_name,
+                    if is_folder { "/" } else { "" }
+                ))
+            })
+            .collect::<Vec<String>>();
+        paths.sort();
+        paths
+    }
+}
+
+#[cfg(test)]
+mod repl_tests {
+    use crate::debugger::Debugger;
+    use crate::parser::Parser;
+    use crate::repl::Repl;
+    use crate::simulator::Simulator;
+
+    #[test]
+    fn test_edit_and_query_automata() {
+        let mut repl = Repl::new();
+        let file = std::env::temp_dir().join("repl_test_even.aut");
+        std::fs::write(
+            &file,
+            "dfa start=q0 accept=q0\nq0 -a-> q1; q1 -a-> q2; q2 -a-> q1\n",
+        )
+        .unwrap();
+
+        assert_eq!(
+            repl.execute(&format!("load {} as even", file.display())),
+            Ok(format!(
+                "Loaded {} as even, a DFA with 3 states\n",
+                file.display()
+            ))
+        );
+        assert_eq!(
+            repl.execute("sim even \"\" aa a"),
+            Ok(String::from(
+                "accept\t\"\"\nreject\t\"aa\"\nreject\t\"a\"\n"
+            ))
+        );
+        repl.execute("accept even q2").unwrap();
+        assert_eq!(
+            repl.execute("reduce even"),
+            Ok(String::from(
+                "Reduced even from 3 to 2 states as even_min\n"
+            ))
+        );
+        assert_eq!(
+            repl.execute("reduce even as classes naming class"),
+            Ok(String::from("Reduced even from 3 to 2 states as classes\n"))
+        );
+        assert!(repl.execute("reduce even naming colour").is_err());
+        assert_eq!(
+            repl.execute("eq even even_min"),
+            Ok(String::from("even and even_min accept the same language\n"))
+        );
+
+        repl.execute("add even q2 b q0").unwrap();
+        assert_eq!(
+            repl.execute("eq even_min even"),
+            Ok(String::from(
+                "even_min and even differ on \"aab\", which even accepts and even_min rejects\n"
+            ))
+        );
+        assert!(repl.execute("add even q2 b q1").is_err());
+        assert!(repl.execute("intersect even even").is_err());
+        assert!(repl.execute("frobnicate").is_err());
+    }
+
+    #[test]
+    fn test_complete() {
+        let state_ids = vec![String::from("q0"), String::from("q1")];
+        let loaded = vec![
+            (String::from("even"), state_ids.clone()),
+            (String::from("odd"), state_ids),
+        ];
+
+        assert_eq!(Repl::complete("re", &loaded), vec!["reduce", "remove"]);
+        assert_eq!(Repl::complete("sim e", &loaded), vec!["even"]);
+        assert_eq!(
+            Repl::complete("export odd m", &loaded),
+            vec!["mermaid", "md"]
+        );
+        assert_eq!(Repl::complete("start odd ", &loaded), vec!["q0", "q1"]);
+    }
+
+    #[test]
+    fn test_debug_commands() {
+        let dfa = Parser::parse_dsl("dfa start=q0 accept=q1\nq0 -a-> q1; q1 -a-> q0").unwrap();
+        let state_ids = vec![String::from("q0"), String::from("q1")];
+        let mut debugger = Debugger::new(&Simulator::new(dfa), "aaa");
+
+        assert_eq!(
+            Repl::execute_debug(&mut debugger, &state_ids, "step 2"),
+            Ok(Some(String::from("step 2\n q0    aa|a\n")))
+        );
+        assert!(Repl::execute_debug(&mut debugger, &state_ids, "break q2").is_err());
+        Repl::execute_debug(&mut debugger, &state_ids, "back 2").unwrap();
+        Repl::execute_debug(&mut debugger, &state_ids, "break q0").unwrap();
+        assert_eq!(
+            Repl::execute_debug(&mut debugger, &state_ids, "run"),
+            Ok(Some(String::from(
+                "Stopped at the breakpoint on q0\nstep 2\n*q0    aa|a\n"
+            )))
+        );
+        assert_eq!(
+            Repl::execute_debug(&mut debugger, &state_ids, "run 3"),
+            Ok(Some(String::from(
+                "step 3\n q1    aaa|\nThe string is accepted\n"
+            )))
+        );
+        assert_eq!(
+            Repl::execute_debug(&mut debugger, &state_ids, "quit"),
+            Ok(None)
+        );
+        assert_eq!(
+            Repl::complete_debug("unbreak ", &state_ids),
+            vec!["q0", "q1"]
+        );
+    }
+}
@@ -0,0 +1,290 @@
+use std::io::{self, IsTerminal, Read, Write};
+use std::process::{Command, Stdio};
+
+/// Reads lines typed at a terminal, letting them be edited, recalled from the history with the
+/// arrow keys and completed with tab
+///
+/// Keys are read one at a time by switching the terminal out of line mode with `stty`. When stdin
+/// is not a terminal or `stty` cannot be run, such as on Windows, whole lines are read instead
+pub struct LineEditor {
+    history: Vec<String>,
+    /// Settings the terminal had before it was switched out of line mode, restored when dropped
+    saved_terminal: Option<String>,
+}
+
+/// A key, or sequence of bytes sent for a key, read from the terminal
+enum Key {
+    Char(char),
+    Enter,
+    Backspace,
+    Delete,
+    Tab,
+    Up,
+    Down,
+    Left,
+    Right,
+    Home,
+    End,
+    /// Ctrl-C, which drops the line being typed
+    Cancel,
+    /// Ctrl-D, which ends input when the line is empty
+    EndOfInput,
+    /// Ctrl-U, which clears the line up to the cursor
+    ClearToStart,
+    Ignored,
+}
+
+impl LineEditor {
+    pub fn new() -> LineEditor {
+        let saved_terminal = if io::stdin().is_terminal() && io::stdout().is_terminal() {
+            Self::stty(&["-g"]).filter(|_| Self::stty(&["-icanon", "-echo", "-isig"]).is_some())
+        } else {
+            None
+        };
+
+        LineEditor {
+            history: vec![],
+            saved_terminal,
+        }
+    }
+
+    /// Lines read so far, oldest first
+    pub fn history(&self) -> &Vec<String> {
+        &self.history
+    }
+
+    /// Reads a line, returning [None] once there is no more input
+    ///
+    /// # Arguments
+    ///
+    /// * `prompt`: Shown before the line when reading from a terminal
+    /// * `complete`: Given the line up to the cursor, returns the words the last word could be completed to
+    pub fn read_line(
+        &mut self,
+        prompt: &str,
+        complete: &dyn Fn(&str) -> Vec<String>,
+    ) -> Option<String> {
+        let line = if self.saved_terminal.is_some() {
+            self.read_edited_line(prompt, complete)
+        } else {
+            if io::stdin().is_terminal() {
+                print!("{}", prompt);
+                let _ = io::stdout().flush();
+            }
+            let mut line = String::new();
+            match io::stdin().read_line(&mut line) {
+                Ok(0) | Err(_) => None,
+                Ok(_) => Some(String::from(line.trim_end_matches(['\r', '\n']))),
+            }
+        }?;
+
+        if !line.trim().is_empty() && self.history.last() != Some(&line) {
+            self.history.push(line.clone());
+        }
+        Some(line)
+    }
+
+    fn read_edited_line(
+        &mut self,
+        prompt: &str,
+        complete: &dyn Fn(&str) -> Vec<String>,
+    ) -> Option<String> {
+        let mut line: Vec<char> = vec![];
+        let mut cursor = 0;
+        let mut history_idx = self.history.len();
+        // Line being typed before moving through the history
+        let mut draft: Vec<char> = vec![];
+
+        Self::redraw(prompt, &line, cursor);
+        loop {
+            match Self::read_key()? {
+                Key::Char(c) => {
+                    line.insert(cursor, c);
+                    cursor += 1;
+                }
+                Key::Enter => {
+                    println!();
+                    return Some(line.into_iter().collect());
+                }
+                Key::Backspace if cursor > 0 => {
+                    cursor -= 1;
+                    line.remove(cursor);
+                }
+                Key::Delete if cursor < line.len() => {
+                    line.remove(cursor);
+                }
+                Key::Tab => {
+                    let before_cursor = line[..cursor].iter().collect::<String>();
+                    let word = before_cursor
+                        .rsplit(char::is_whitespace)
+                        .next()
+                        .unwrap_or_default();
+                    let candidates = complete(&before_cursor);
+                    let common_prefix = Self::common_prefix(&candidates);
+
+                    let mut completion = common_prefix
+                        .chars()
+                        .skip(word.chars().count())
+                        .collect::<Vec<char>>();
+                    if candidates.len() == 1 && !common_prefix.ends_with('/') {
+                        completion.push(' ');
+                    }
+
+                    if candidates.len() > 1 && completion.is_empty() {
+                        println!("\n{}", candidates.join("  "));
+                    } else if candidates.is_empty() {
+                        // Rings the bell as there is nothing to complete to
+                        print!("\x07");
+                    }
+                    for c in completion {
+                        line.insert(cursor, c);
+                        cursor += 1;
+                    }
+                }
+                Key::Up if history_idx > 0 => {
+                    if history_idx == self.history.len() {
+                        draft = line;
+                    }
+                    history_idx -= 1;
+                    line = self.history[history_idx].chars().collect();
+                    cursor = line.len();
+                }
+                Key::Down if history_idx < self.history.len() => {
+                    history_idx += 1;
+                    line = match self.history.get(history_idx) {
+                        Some(entry) => entry.chars().collect(),
+                        None => draft.clone(),
+                    };
+                    cursor = line.len();
+                }
+                Key::Left if cursor > 0 => cursor -= 1,
+                Key::Right if cursor < line.len() => cursor += 1,
+                Key::Home => cursor = 0,
+                Key::End => cursor = line.len(),
+                Key::Cancel => {
+                    println!("^C");
+                    return Some(String::new());
+                }
+                Key::EndOfInput if line.is_empty() => {
+                    println!();
+                    return None;
+                }
+                Key::EndOfInput if cursor < line.len() => {
+                    line.remove(cursor);
+                }
+                Key::ClearToStart => {
+                    line.drain(..cursor);
+                    cursor = 0;
+                }
+                _ => {}
+            }
+            Self::redraw(prompt, &line, cursor);
+        }
+    }
+
+    /// Clears the line on the terminal and writes it again with the cursor where it is in the line
+    fn redraw(prompt: &str, line: &[char], cursor: usize) {
+        print!("\r\x1b[K{}{}", prompt, line.iter().collect::<String>());
+        if cursor < line.len() {
+            print!("\x1b[{}D", line.len() - cursor);
+        }
+        let _ = io::stdout().flush();
+    }
+
+    /// Reads the bytes sent for a key, returning [None] once stdin is closed
+    fn read_key() -> Option<Key> {
+        let byte = Self::read_byte()?;
+        Some(match byte {
+            b'\r' | b'\n' => Key::Enter,
+            127 | 8 => Key::Backspace,
+            b'\t' => Key::Tab,
+            1 => Key::Home,
+            3 => Key::Cancel,
+            4 => Key::EndOfInput,
+            5 => Key::End,
+            21 => Key::ClearToStart,
+            // Escape sequences sent for the arrow, home, end and delete keys
+            27 => match (Self::read_byte()?, Self::read_byte()?) {
+                (b'[', b'A') => Key::Up,
+                (b'[', b'B') => Key::Down,
+                (b'[', b'C') => Key::Right,
+                (b'[', b'D') => Key::Left,
+                (b'[', b'H') | (b'O', b'H') => Key::Home,
+                (b'[', b'F') | (b'O', b'F') => Key::End,
+                (b'[', b'3') => {
+                    Self::read_byte()?;
+                    Key::Delete
+                }
+                _ => Key::Ignored,
+            },
+            byte if byte < 32 => Key::Ignored,
+            byte => {
+                // The leading byte of a UTF-8 character tells how many bytes follow it
+                let len = match byte {
+                    0xF0.. => 4,
+                    0xE0.. => 3,
+                    0xC0.. => 2,
+                    _ => 1,
+                };
+                let mut bytes = vec![byte];
+                for _ in 1..len {
+                    bytes.push(Self::read_byte()?);
+                }
+                match String::from_utf8(bytes).ok()?.chars().next() {
+                    Some(c) => Key::Char(c),
+                    None => Key::Ignored,
+                }
+            }
+        })
+    }
+
+    fn read_byte() -> Option<u8> {
+        let mut byte = [0];
+        match io::stdin().read(&mut byte) {
+            Ok(1) => Some(byte[0]),
+            _ => None,
+        }
+    }
+
+    /// Longest start shared by every candidate
+    fn common_prefix(candidates: &[String]) -> String {
+        let Some(first) = candidates.first() else {
+            return String::new();
+        };
+
+        first
+            .chars()
+            .enumerate()
+            .take_while(|(idx, c)| {
+                candidates
+                    .iter()
+                    .all(|candidate| candidate.chars().nth(*idx) == Some(*c))
+            })
+            .map(|(_, c)| c)
+            .collect()
+    }
+
+    /// Runs stty on the terminal, returning what it printed when it succeeded
+    fn stty(args: &[&str]) -> Option<String> {
+        let output = Command::new("stty")
+            .args(args)
+            .stdin(Stdio::inherit())
+            .stderr(Stdio::null())
+            .output()
+            .ok()?;
+
+        if output.status.success() {
+            Some(String::from_utf8_lossy(&output.stdout).trim().to_string())
+        } else {
+            None
+        }
+    }
+}
+
+impl Drop for LineEditor {
+    fn drop(&mut self) {
+        if let Some(settings) = &self.saved_terminal {
+            Self::stty(&[settings]);
+        }
+    }
+}
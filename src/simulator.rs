@@ -0,0 +1,236 @@
+use std::rc::Rc;
+
+use crate::automaton_graph::AutomatonType::{DFA, NFA, PDA};
+use crate::automaton_graph::{Automaton, SimulationError, State, Symbol, Transition};
+use crate::dfa;
+use crate::dfa::DfaRunner;
+use crate::nfa;
+use crate::nfa::NfaRunner;
+use crate::pda;
+use crate::pda::PdaRunner;
+use crate::trace::Trace;
+
+/// Runs strings on an automaton of any type using the simulator for that type
+pub enum Simulator {
+    Dfa(dfa::DFA),
+    Nfa(nfa::NFA),
+    Pda(pda::PDA),
+}
+
+/// Where an automaton is partway through a run
+#[derive(Debug, Clone)]
+pub struct Configuration {
+    pub state: Rc<State>,
+    /// Number of characters of the input read so far
+    pub position: usize,
+    /// Stack of a PDA with its top last, always empty for DFAs and NFAs
+    pub stack: Vec<Symbol>,
+    /// State left and transition taken to reach this configuration, [None] for the start
+    /// configuration and for NFAs, whose states can be reached in more than one way at once
+    pub reached_by: Option<(Rc<State>, Transition)>,
+}
+
+/// Whether a run has finished and how
+#[derive(Debug)]
+pub enum RunStatus {
+    Running,
+    Accepted,
+    Rejected,
+    /// The automaton broke one of the rules of its type, such as a DFA with two transitions on a symbol
+    Failed(SimulationError),
+}
+
+/// Runs an automaton of any type, using the runner for that type
+///
+/// A run can be stepped through as an iterator, where each item is the configurations the automaton
+/// can be in after another step, starting with the start configuration. DFAs and deterministic PDAs
+/// are only ever in one configuration. Input can also be fed to a run a piece at a time
+pub enum Runner {
+    Dfa(DfaRunner),
+    Nfa(NfaRunner),
+    Pda(PdaRunner),
+}
+
+impl Simulator {
+    pub fn new(automaton: Automaton) -> Simulator {
+        match automaton.automaton_type {
+            DFA => Simulator::Dfa(dfa::DFA::new(automaton)),
+            NFA => Simulator::Nfa(nfa::NFA::new(automaton)),
+            PDA => Simulator::Pda(pda::PDA::new(Rc::new(automaton))),
+        }
+    }
+
+    /// Whether the automaton accepts a string
+    pub fn accepts(&self, string: &str) -> Result<bool, SimulationError> {
+        self.runner(string).finish()
+    }
+
+    /// Runs a string, keeping every configuration the automaton went through
+    pub fn trace(&self, string: &str) -> Trace {
+        match self {
+            Simulator::Dfa(dfa) => dfa.trace(string),
+            Simulator::Nfa(nfa) => nfa.trace(string),
+            Simulator::Pda(pda) => pda.trace(string),
+        }
+    }
+
+    /// Starts a run on a string which can be stepped through or fed more input
+    pub fn runner(&self, string: &str) -> Runner {
+        match self {
+            Simulator::Dfa(dfa) => Runner::Dfa(dfa.runner(string)),
+            Simulator::Nfa(nfa) => Runner::Nfa(nfa.runner(string)),
+            Simulator::Pda(pda) => Runner::Pda(pda.runner(string)),
+        }
+    }
+}
+
+impl RunStatus {
+    /// Turns a finished run into whether the string was accepted
+    pub fn into_result(self) -> Result<bool, SimulationError> {
+        match self {
+            RunStatus::Accepted => Ok(true),
+            RunStatus::Running | RunStatus::Rejected => Ok(false),
+            RunStatus::Failed(err) => Err(err),
+        }
+    }
+}
+
+impl Runner {
+    pub fn status(&self) -> &RunStatus {
+        match self {
+            Runner::Dfa(runner) => runner.status(),
+            Runner::Nfa(runner) => runner.status(),
+            Runner::Pda(runner) => runner.status(),
+        }
+    }
+
+    /// Reads more input, carrying on from where the automaton was left
+    ///
+    /// Input is read as it is fed so long input can be run a piece at a time without keeping all of it
+    pub fn feed(&mut self, input: &str) {
+        match self {
+            Runner::Dfa(runner) => runner.feed(input),
+            Runner::Nfa(runner) => runner.feed(input),
+            Runner::Pda(runner) => runner.feed(input),
+        }
+    }
+
+    pub fn feed_char(&mut self, c: char) {
+        match self {
+            Runner::Dfa(runner) => runner.feed_char(c),
+            Runner::Nfa(runner) => runner.feed_char(c),
+            Runner::Pda(runner) => runner.feed_char(c),
+        }
+    }
+
+    /// Whether the input fed so far is accepted
+    pub fn is_accepting(&mut self) -> bool {
+        match self {
+            Runner::Dfa(runner) => runner.is_accepting(),
+            Runner::Nfa(runner) => runner.is_accepting(),
+            Runner::Pda(runner) => runner.is_accepting(),
+        }
+    }
+
+    /// Goes back to the start configuration, forgetting all of the input fed so far
+    pub fn reset(&mut self) {
+        match self {
+            Runner::Dfa(runner) => runner.reset(),
+            Runner::Nfa(runner) => runner.reset(),
+            Runner::Pda(runner) => runner.reset(),
+        }
+    }
+
+    /// Reads the rest of the input, returning whether it was accepted
+    pub fn finish(self) -> Result<bool, SimulationError> {
+        match self {
+            Runner::Dfa(runner) => runner.finish(),
+            Runner::Nfa(runner) => runner.finish(),
+            Runner::Pda(runner) => runner.finish(),
+        }
+    }
+}
+
+impl Iterator for Runner {
+    type Item = Vec<Configuration>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self {
+            Runner::Dfa(runner) => runner.next(),
+            Runner::Nfa(runner) => runner.next(),
+            Runner::Pda(runner) => runner.next(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod simulator_tests {
+    use crate::parser::Parser;
+    use crate::simulator::Simulator;
+
+    #[test]
+    fn test_feed_input_in_chunks() {
+        let pda = Simulator::new(
+            Parser::parse_dsl(
+                "pda start=q0 accept=q0,q3\nq0 -ϵ,ϵ/Z-> q1; q1 -a,ϵ/A-> q1; q1 -ϵ,ϵ/ϵ-> q2; q2 -b,A/ϵ-> q2; q2 -ϵ,Z/ϵ-> q3",
+            )
+            .unwrap(),
+        );
+        let mut runner = pda.runner("");
+        assert!(runner.is_accepting());
+        runner.feed("aa");
+        assert!(!runner.is_accepting());
+        runner.feed("b");
+        runner.feed_char('b');
+        assert!(runner.is_accepting());
+        runner.feed("b");
+        assert!(!runner.is_accepting());
+        runner.reset();
+        runner.feed("ab");
+        assert!(runner.is_accepting());
+
+        let nfa = Simulator::new(
+            Parser::parse_dsl("nfa start=q0 accept=q1\nq0 -a-> q0; q0 -b-> q0; q0 -b-> q1")
+                .unwrap(),
+        );
+        let mut runner = nfa.runner("");
+        for chunk in ["ab", "", "ab", "bab"] {
+            runner.feed(chunk);
+        }
+        assert!(runner.is_accepting());
+        runner.feed("a");
+        assert!(!runner.finish().unwrap());
+    }
+
+    #[test]
+    fn test_pda_pushing_on_epsilon_forever_stops() {
+        let pda = Simulator::new(
+            Parser::parse_dsl(
+                "pda start=q0 accept=q3\nq0 -ϵ,ϵ/Z-> q1; q1 -ϵ,ϵ/A-> q1; q1 -ϵ,ϵ/ϵ-> q2; q2 -b,A/ϵ-> q2; q2 -ϵ,Z/ϵ-> q3",
+            )
+            .unwrap(),
+        );
+        assert!(pda.accepts("").unwrap());
+        assert!(pda.accepts("bbbbbbbbbbbbbbbbbbbbbbbbb").unwrap());
+        assert!(!pda.accepts("ba").unwrap());
+
+        let mut runner = pda.runner("bb");
+        assert!(runner.is_accepting());
+        runner.feed("bbbbbbbbbbbbbbbbbbbb");
+        assert!(runner.is_accepting());
+    }
+
+    #[test]
+    fn test_pda_with_a_tall_stack() {
+        let pda = Simulator::new(
+            Parser::parse_dsl(
+                "pda start=q0 accept=q1\nq0 -a,ϵ/A-> q0; q0 -b,A/ϵ-> q1; q1 -b,A/ϵ-> q1",
+            )
+            .unwrap(),
+        );
+        let string = "a".repeat(200_000) + &"b".repeat(199_999);
+        assert!(pda.accepts(&string).unwrap());
+        // The run ends with every symbol still on the stack
+        assert!(!pda.accepts(&"a".repeat(200_000)).unwrap());
+    }
+}
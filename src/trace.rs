@@ -0,0 +1,290 @@
+use std::fmt::Write;
+
+use crate::automaton_graph::{Highlight, JsonValue, Symbol};
+use crate::simulator::{RunStatus, Runner};
+
+/// Every configuration a run of an automaton went through, which can be written as json so runs
+/// can be rendered or compared outside the program
+#[derive(Debug, Clone, PartialEq)]
+pub struct Trace {
+    pub input: String,
+    /// Configurations the automaton could be in after each step, starting with the start configuration
+    ///
+    /// DFAs and deterministic PDAs are in one configuration per step while NFAs are in one for each
+    /// of their active states
+    pub steps: Vec<Vec<TraceConfiguration>>,
+    /// Whether the string was accepted, or why it could not be simulated
+    pub result: Result<bool, String>,
+}
+
+/// A configuration of a run, holding ids rather than states so it outlives the automaton
+#[derive(Debug, Clone, PartialEq)]
+pub struct TraceConfiguration {
+    pub state_id: String,
+    /// Number of characters of the input read so far
+    pub position: usize,
+    /// Transition taken to reach the configuration, [None] for the start configuration and for NFAs
+    pub transition: Option<TraceTransition>,
+    /// Stack of a PDA with its top last, always empty for DFAs and NFAs
+    pub stack: Vec<Symbol>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TraceTransition {
+    /// Id of the state the transition was taken from
+    pub from: String,
+    pub symbol: Symbol,
+    pub pop_symbol: Option<Symbol>,
+    pub push_symbol: Option<Symbol>,
+}
+
+impl Trace {
+    /// Runs a runner to the end, keeping each step it takes
+    pub fn record(input: &str, mut runner: Runner) -> Trace {
+        let steps = runner
+            .by_ref()
+            .map(|configurations| {
+                configurations
+                    .into_iter()
+                    .map(|configuration| TraceConfiguration {
+                        state_id: configuration.state.id.clone(),
+                        position: configuration.position,
+                        transition: configuration.reached_by.map(|(from, transition)| {
+                            TraceTransition {
+                                from: from.id.clone(),
+                                symbol: transition.transition_on(),
+                                pop_symbol: transition.pop_symbol(),
+                                push_symbol: transition.push_symbol(),
+                            }
+                        }),
+                        stack: configuration.stack,
+                    })
+                    .collect()
+            })
+            .collect();
+        let result = match runner.status() {
+            RunStatus::Accepted => Ok(true),
+            RunStatus::Running | RunStatus::Rejected => Ok(false),
+            RunStatus::Failed(err) => Err(format!("{:?}", err)),
+        };
+
+        Trace {
+            input: String::from(input),
+            steps,
+            result,
+        }
+    }
+
+    /// Ids of the states the automaton could be in after each step, without repeats
+    pub fn active_states(&self) -> Vec<Vec<&str>> {
+        self.steps
+            .iter()
+            .map(|configurations| {
+                let mut state_ids: Vec<&str> = vec![];
+                for configuration in configurations {
+                    if !state_ids.contains(&configuration.state_id.as_str()) {
+                        state_ids.push(&configuration.state_id);
+                    }
+                }
+                state_ids
+            })
+            .collect()
+    }
+
+    /// The states the run ended in along with every transition it took, for drawing the run
+    ///
+    /// NFAs do not record the transitions they take so only their states are highlighted
+    pub fn highlight(&self) -> Highlight {
+        Highlight {
+            states: self
+                .active_states()
+                .last()
+                .into_iter()
+                .flatten()
+                .map(|state_id| String::from(*state_id))
+                .collect(),
+            transitions: self
+                .steps
+                .iter()
+                .flatten()
+                .filter_map(|configuration| {
+                    let transition = configuration.transition.as_ref()?;
+                    Some((transition.from.clone(), configuration.state_id.clone()))
+                })
+                .collect(),
+        }
+    }
+
+    /// Writes whether the string was accepted followed by a line for each configuration, giving the
+    /// transition taken, the input split at what has been read and for PDAs the stack with its top first
+    ///
+    ///     "ab" is accepted
+    ///       0  q0                |ab
+    ///       1  q0 -a,ϵ/A-> q0  a|b    stack: A
+    pub fn to_text(&self) -> String {
+        let mut text = match &self.result {
+            Ok(true) => format!("\"{}\" is accepted\n", self.input),
+            Ok(false) => format!("\"{}\" is rejected\n", self.input),
+            Err(message) => format!("\"{}\" could not be simulated: {}\n", self.input, message),
+        };
+        let input = self.input.chars().collect::<Vec<char>>();
+
+        for (step, configurations) in self.steps.iter().enumerate() {
+            for configuration in configurations {
+                let state = match &configuration.transition {
+                    Some(transition) => format!(
+                        "{} -{}-> {}",
+                        transition.from,
+                        transition.label(),
+                        configuration.state_id
+                    ),
+                    None => configuration.state_id.clone(),
+                };
+                // Writing to a String cannot fail
+                let _ = write!(
+                    text,
+                    "  {:<3}{:<12}  {}|{}",
+                    step,
+                    state,
+                    input[..configuration.position].iter().collect::<String>(),
+                    input[configuration.position..].iter().collect::<String>()
+                );
+                if !configuration.stack.is_empty() {
+                    let _ = write!(
+                        text,
+                        "    stack: {}",
+                        configuration
+                            .stack
+                            .iter()
+                            .rev()
+                            .map(|symbol| symbol.to_string())
+                            .collect::<String>()
+                    );
+                }
+                text.push('\n');
+            }
+        }
+
+        text
+    }
+
+    /// Builds the trace as json, with the result followed by the configurations of each step
+    pub(crate) fn json_value(&self) -> JsonValue {
+        let symbol = |symbol: Symbol| JsonValue::String(symbol.to_string());
+        let steps = self
+            .steps
+            .iter()
+            .map(|configurations| {
+                JsonValue::Array(
+                    configurations
+                        .iter()
+                        .map(|configuration| {
+                            let mut entries = vec![
+                                (
+                                    String::from("state"),
+                                    JsonValue::String(configuration.state_id.clone()),
+                                ),
+                                (
+                                    String::from("position"),
+                                    JsonValue::Number(configuration.position as f64),
+                                ),
+                                (
+                                    String::from("stack"),
+                                    JsonValue::Array(
+                                        configuration.stack.iter().copied().map(symbol).collect(),
+                                    ),
+                                ),
+                            ];
+                            if let Some(transition) = &configuration.transition {
+                                let mut transition_entries = vec![
+                                    (
+                                        String::from("from"),
+                                        JsonValue::String(transition.from.clone()),
+                                    ),
+                                    (String::from("symbol"), symbol(transition.symbol)),
+                                ];
+                                if let Some(pop_symbol) = transition.pop_symbol {
+                                    transition_entries
+                                        .push((String::from("pop"), symbol(pop_symbol)));
+                                }
+                                if let Some(push_symbol) = transition.push_symbol {
+                                    transition_entries
+                                        .push((String::from("push"), symbol(push_symbol)));
+                                }
+                                entries.push((
+                                    String::from("transition"),
+                                    JsonValue::Object(transition_entries),
+                                ));
+                            }
+                            JsonValue::Object(entries)
+                        })
+                        .collect(),
+                )
+            })
+            .collect();
+
+        JsonValue::Object(vec![
+            (String::from("input"), JsonValue::String(self.input.clone())),
+            match &self.result {
+                Ok(accepted) => (String::from("accepted"), JsonValue::Bool(*accepted)),
+                Err(message) => (String::from("error"), JsonValue::String(message.clone())),
+            },
+            (String::from("steps"), JsonValue::Array(steps)),
+        ])
+    }
+}
+
+impl TraceTransition {
+    /// Label of the transition as written in the text format, Ex: "a" for DFAs & NFAs or "a,X/Y" for PDAs
+    pub fn label(&self) -> String {
+        match (self.pop_symbol, self.push_symbol) {
+            (Some(pop_symbol), Some(push_symbol)) => {
+                format!("{},{}/{}", self.symbol, pop_symbol, push_symbol)
+            }
+            _ => self.symbol.to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod trace_tests {
+    use crate::parser::Parser;
+    use crate::simulator::Simulator;
+
+    #[test]
+    fn test_nfa_trace_has_active_states() {
+        let simulator = Simulator::new(
+            Parser::parse_dsl("nfa start=q0 accept=q2\nq0 -a-> q0; q0 -a-> q1; q1 -b-> q2")
+                .unwrap(),
+        );
+        let trace = simulator.trace("ab");
+
+        assert_eq!(trace.result, Ok(true));
+        assert_eq!(
+            trace.active_states(),
+            vec![vec!["q0"], vec!["q0", "q1"], vec!["q2"]]
+        );
+        assert!(trace
+            .steps
+            .iter()
+            .flatten()
+            .all(|configuration| configuration.transition.is_none()));
+        assert_eq!(trace, simulator.trace("ab"));
+    }
+
+    #[test]
+    fn test_pda_trace() {
+        let simulator = Simulator::new(
+            Parser::parse_dsl("pda start=q0 accept=q1\nq0 -a,ϵ/A-> q0; q0 -b,A/ϵ-> q1").unwrap(),
+        );
+        let trace = simulator.trace("ab");
+
+        assert_eq!(
+            trace.to_text(),
+            "\"ab\" is accepted\n  0  q0            |ab\n  1  q0 -a,ϵ/A-> q0  a|b    stack: A\n  2  q0 -b,A/ϵ-> q1  ab|\n"
+        );
+        assert!(trace.json_value().to_string().contains(
+            "\"transition\": {\n          \"from\": \"q0\",\n          \"symbol\": \"b\",\n          \"pop\": \"A\",\n          \"push\": \"ϵ\"\n        }"
+        ));
+    }
+}